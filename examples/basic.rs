@@ -1,6 +1,5 @@
 use bevy::prelude::*;
-use bevy_flycam::MovementSettings;
-use bevy_flycam::PlayerPlugin;
+use bevy_flycam::prelude::*;
 
 //From bevy examples:
 //https://github.com/bevyengine/bevy/blob/latest/examples/3d/3d_scene.rs
@@ -13,7 +12,7 @@ fn main() {
         .insert_resource(MovementSettings {
             sensitivity: 0.00015, // default: 0.00012
             speed: 12.0,
-            boost: 4.,
+            ..Default::default()
         })
         .add_startup_system(setup)
         .run();
@@ -1,5 +1,5 @@
 use bevy::prelude::StandardMaterial;
-use bevy::{input::mouse::MouseWheel, prelude::*, render::camera::Projection};
+use bevy::{input::mouse::MouseWheel, prelude::*};
 use bevy_flycam::{FlyCam, MovementSettings, NoCameraPlayerPlugin};
 
 // From bevy examples:
@@ -97,19 +97,14 @@ fn scroll(
     mut settings: ResMut<MovementSettings>,
     scroll_type: Res<State<ScrollType>>,
     mut mouse_wheel_events: EventReader<MouseWheel>,
-    mut query: Query<(&FlyCam, &mut Projection)>,
 ) {
     for event in mouse_wheel_events.iter() {
         if *scroll_type.current() == ScrollType::MovementSpeed {
             settings.speed = (settings.speed + event.y * 0.1).abs();
             println!("Speed: {:?}", settings.speed);
         } else {
-            for (_camera, project) in query.iter_mut() {
-                if let Projection::Perspective(perspective) = project.into_inner() {
-                    perspective.fov = (perspective.fov - event.y * 0.01).abs();
-                    println!("FOV: {:?}", perspective.fov);
-                }
-            }
+            settings.fov = (settings.fov - event.y * 0.01).abs();
+            println!("FOV: {:?}", settings.fov);
         }
     }
 }
@@ -0,0 +1,351 @@
+use bevy::prelude::*;
+
+use crate::fluid::{FlyCamFluid, FlyCamSwimming};
+use crate::replay::{FlyCamPlayback, FlyCamRecorder, RecordedFrame, RecordedMotion};
+use crate::rotation::FlyCamFrame;
+use crate::target::smoothing_blend;
+use crate::walk::{WalkSettings, WalkState};
+use crate::{
+    FlyCam, FlyCamMode, FlyCamRotation, FlyCamTarget, FlyCamTime, InputState, MovementSettings,
+    TerrainSettings,
+};
+
+/// One piece of input for the first-person controls, used in [`FlyCamMode::Fly`],
+/// [`FlyCamMode::Walk`], [`FlyCamMode::Planet`] and [`FlyCamMode::Terrain`].
+///
+/// The keyboard, mouse and gamepad systems turn what they read into these every frame,
+/// and the movement is worked out from the actions alone. Send your own
+/// [`FlyCamInput`] events to drive the camera from tests, replays or AI, or to plug in
+/// another input crate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum FlyCamAction {
+    /// Move forwards (positive) or backwards, from -1 to 1 of full speed for this frame.
+    /// With [`MovementSettings::stepping`], each one is a single step.
+    MoveForward(f32),
+    /// Move right (positive) or left, like [`FlyCamAction::MoveForward`]
+    MoveRight(f32),
+    /// Move up (positive) or down, like [`FlyCamAction::MoveForward`]
+    MoveUp(f32),
+    /// Turn left (positive) or right at the keyboard turning rate, from -1 to 1
+    Yaw(f32),
+    /// Tilt up (positive) or down, like [`FlyCamAction::Yaw`]
+    Pitch(f32),
+    /// Roll anticlockwise (positive) or clockwise, like [`FlyCamAction::Yaw`]
+    Roll(f32),
+    /// Mouse-style look, in pixels of motion. Scaled by [`MovementSettings::sensitivity`], and
+    /// slowed while zoomed in with [`MovementSettings::fov_compensated_look`].
+    Look(Vec2),
+    /// Look by angle, turning by this many radians of yaw (x) and pitch (y) this frame, as sticks,
+    /// touch, the gyro and the look keys do.
+    /// Unlike [`FlyCamAction::Rotate`], it's slowed while zoomed in like [`FlyCamAction::Look`].
+    StickLook(Vec2),
+    /// Dolly along the view direction, in scroll steps scaled by
+    /// [`MovementSettings::sensitivity`]
+    Scroll(f32),
+    /// Slide right (positive) or left, in scroll steps scaled by
+    /// [`MovementSettings::horizontal_scroll_sensitivity`]
+    ScrollSideways(f32),
+    /// Multiplies the speed of this frame's movement and keyboard turning
+    Boost(f32),
+    /// Move by this much this frame, along the camera's own axes (x right, y up, negative z
+    /// forward), no faster than [`MovementSettings::max_speed`]
+    Translate(Vec3),
+    /// Turn by exactly this many radians of yaw (x), pitch (y) and roll (z) this frame
+    Rotate(Vec3),
+    /// Jump, if standing on the ground in [`FlyCamMode::Walk`]
+    Jump,
+    /// Crouch for this frame in [`FlyCamMode::Walk`]. Keep sending it to stay down.
+    Crouch,
+}
+
+/// A [`FlyCamAction`] for one flycam, or for all of them
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FlyCamInput {
+    /// The flycam to act on, or `None` for all of them
+    pub camera: Option<Entity>,
+    pub action: FlyCamAction,
+}
+
+impl FlyCamInput {
+    /// An action for every flycam
+    pub fn all(action: FlyCamAction) -> Self {
+        Self {
+            camera: None,
+            action,
+        }
+    }
+
+    /// An action for a single flycam
+    pub fn camera(camera: Entity, action: FlyCamAction) -> Self {
+        Self {
+            camera: Some(camera),
+            action,
+        }
+    }
+}
+
+/// Marks flycams that get their actions from somewhere other than the keyboard
+#[derive(Component)]
+pub(crate) struct ExternalInput;
+
+/// What a frame's worth of actions add up to for one camera
+#[derive(Default)]
+struct Totals {
+    movement: Vec3,
+    turn: Vec3,
+    look: Vec2,
+    stick_look: Vec2,
+    scroll: f32,
+    scroll_sideways: f32,
+    boost: Option<f32>,
+    translate: Vec3,
+    rotate: Vec3,
+    jump: bool,
+    crouch: bool,
+}
+
+impl Totals {
+    fn add(&mut self, action: FlyCamAction) {
+        match action {
+            FlyCamAction::MoveForward(amount) => self.movement.z += amount,
+            FlyCamAction::MoveRight(amount) => self.movement.x += amount,
+            FlyCamAction::MoveUp(amount) => self.movement.y += amount,
+            FlyCamAction::Yaw(amount) => self.turn.x += amount,
+            FlyCamAction::Pitch(amount) => self.turn.y += amount,
+            FlyCamAction::Roll(amount) => self.turn.z += amount,
+            FlyCamAction::Look(delta) => self.look += delta,
+            FlyCamAction::StickLook(angles) => self.stick_look += angles,
+            FlyCamAction::Scroll(amount) => self.scroll += amount,
+            FlyCamAction::ScrollSideways(amount) => self.scroll_sideways += amount,
+            FlyCamAction::Boost(boost) => *self.boost.get_or_insert(1.) *= boost,
+            FlyCamAction::Translate(offset) => self.translate += offset,
+            FlyCamAction::Rotate(angles) => self.rotate += angles,
+            FlyCamAction::Jump => self.jump = true,
+            FlyCamAction::Crouch => self.crouch = true,
+        }
+    }
+}
+
+/// Moves and turns each first-person flycam by the actions sent to it this frame, or by the
+/// next frame of its [`FlyCamPlayback`], and records them for any [`FlyCamRecorder`]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(crate) fn apply_actions(
+    time: Res<FlyCamTime>,
+    windows: Res<Windows>,
+    settings: Res<MovementSettings>,
+    walk_settings: Res<WalkSettings>,
+    terrain: Res<TerrainSettings>,
+    mut state: ResMut<InputState>,
+    mut actions: EventReader<FlyCamInput>,
+    mut query: Query<
+        (
+            Entity,
+            &mut FlyCamTarget,
+            &mut FlyCamRotation,
+            &FlyCamFrame,
+            &FlyCamMode,
+            Option<&mut FlyCamRecorder>,
+            Option<&mut FlyCamPlayback>,
+            Option<&mut WalkState>,
+            Option<&mut FlyCamSwimming>,
+        ),
+        With<FlyCam>,
+    >,
+    fluids: Query<&FlyCamFluid>,
+) {
+    let actions: Vec<FlyCamInput> = actions.iter().copied().collect();
+    let dt = time.delta_seconds();
+
+    // Look smoothing is shared, so it only applies to looks meant for every camera.
+    let mut shared_look: Vec2 = actions
+        .iter()
+        .filter_map(|input| match (input.camera, input.action) {
+            (None, FlyCamAction::Look(delta)) => Some(delta),
+            _ => None,
+        })
+        .fold(Vec2::ZERO, |sum, delta| sum + delta);
+    if settings.look_smoothing > 0. {
+        // Exponential smoothing: ease towards the raw delta with the configured time constant
+        let blend = smoothing_blend(settings.look_smoothing, dt);
+        let mut smoothed = state.smoothed_motion + (shared_look - state.smoothed_motion) * blend;
+        // Settle instead of creeping forever
+        if smoothed.length_squared() < 1e-6 {
+            smoothed = Vec2::ZERO;
+        }
+        if state.smoothed_motion != smoothed {
+            state.smoothed_motion = smoothed;
+        }
+        shared_look = smoothed;
+    }
+
+    let window_scale = windows
+        .get_primary()
+        .map_or(0., |window| window.height().min(window.width()));
+    // Zoomed in, the same look input turns the view by a proportionally smaller angle.
+    let look_scale = settings.zoom_look_scale(state.zoom);
+
+    for (entity, mut target, mut rotation, frame, mode, recorder, mut playback, walk, swimming) in
+        query.iter_mut()
+    {
+        if !mode.uses_fly_controls() {
+            continue;
+        }
+        // Played-back frames last as long as they did when they were recorded.
+        let mut dt = dt;
+        let mut camera_actions = Vec::new();
+        let mut recorded_motion = None;
+        match playback.as_deref_mut().and_then(FlyCamPlayback::next) {
+            Some((start, recorded)) => {
+                if let Some((translation, angles)) = start {
+                    target.translation = translation;
+                    target.rotation = frame.0 * angles.to_quat();
+                    *rotation = angles;
+                }
+                dt = recorded.delta;
+                recorded_motion = recorded.motion;
+                camera_actions.extend_from_slice(&recorded.actions);
+            }
+            None => {
+                if shared_look != Vec2::ZERO {
+                    camera_actions.push(FlyCamAction::Look(shared_look));
+                }
+                camera_actions.extend(actions.iter().filter_map(|input| {
+                    match (input.camera, input.action) {
+                        (None, FlyCamAction::Look(_)) => None,
+                        (Some(camera), _) if camera != entity => None,
+                        (_, action) => Some(action),
+                    }
+                }));
+            }
+        }
+        let mut totals = Totals::default();
+        for &action in &camera_actions {
+            totals.add(action);
+        }
+        let mut boost = totals.boost.unwrap_or(1.);
+
+        let swimming = swimming.and_then(|swimming| {
+            let fluid = fluids.get(swimming.fluid).ok()?;
+            Some((swimming, fluid))
+        });
+        // Swimming moves freely in 3D, even for walkers.
+        let level = matches!(mode, FlyCamMode::Walk | FlyCamMode::Terrain) && swimming.is_none();
+        let mut offset = Vec3::ZERO;
+        let mut walk = walk.filter(|_| level && *mode == FlyCamMode::Walk);
+        if let Some(walk) = walk.as_mut() {
+            let rise = walk.step(&walk_settings, totals.jump, totals.crouch, dt);
+            if rise != 0. {
+                offset += frame.up() * rise;
+            }
+            boost *= walk.speed_multiplier(&walk_settings);
+        }
+        // The roll offset tilts the picture, not the way the camera moves.
+        let untilted = target.rotation * Quat::from_rotation_z(-rotation.roll_offset);
+        let (forward, right) = if settings.fly_along_look && !level {
+            (target.forward(), untilted * Vec3::X)
+        } else {
+            // Level with the frame's horizon
+            let local_z = frame.0.inverse() * target.local_z();
+            (
+                (frame.0 * -Vec3::new(local_z.x, 0., local_z.z)).normalize_or_zero(),
+                (frame.0 * Vec3::new(local_z.z, 0., -local_z.x)).normalize_or_zero(),
+            )
+        };
+        let up = if settings.vertical_along_view {
+            untilted * Vec3::Y
+        } else {
+            frame.up()
+        };
+        if level {
+            totals.movement.y = 0.;
+        }
+
+        let mut turn = totals.rotate;
+        let direction = settings
+            .lock_to_plane(
+                right * totals.movement.x + up * totals.movement.y + forward * totals.movement.z,
+            )
+            .normalize_or_zero()
+            * totals.movement.length().min(1.);
+        if settings.stepping {
+            offset += direction * settings.step_distance;
+            turn += totals.turn * settings.step_angle;
+        }
+        // How far the camera moves under its own power this frame, which max_speed limits
+        let mut travel = Vec3::ZERO;
+        if !settings.stepping {
+            match swimming {
+                Some((mut swimming, fluid)) => {
+                    let drift = direction * settings.speed * boost * fluid.speed_multiplier
+                        + frame.up() * fluid.buoyancy;
+                    let blend = smoothing_blend(fluid.drag, dt);
+                    let velocity = swimming.velocity.lerp(settings.lock_to_plane(drift), blend);
+                    swimming.velocity = velocity;
+                    travel += velocity * dt;
+                }
+                None => travel += direction * dt * settings.speed * boost,
+            }
+            let rate = settings.speed * boost * dt / 100. * std::f32::consts::PI;
+            turn += totals.turn * Vec3::new(rate * 2., rate, rate);
+        }
+        if totals.scroll != 0. {
+            let mut view = target.forward();
+            if level {
+                view = (view - frame.up() * view.dot(frame.up())).normalize_or_zero();
+            }
+            travel += settings.lock_to_plane(view * totals.scroll * settings.sensitivity * boost);
+        }
+        if totals.scroll_sideways != 0. {
+            let sideways = totals.scroll_sideways * settings.horizontal_scroll_sensitivity * boost;
+            travel += settings.lock_to_plane(right * sideways);
+        }
+        if totals.translate != Vec3::ZERO {
+            travel += settings.lock_to_plane(target.rotation * totals.translate);
+        }
+        // No time passing, e.g. on the first frame or while paused, isn't a speed to cap.
+        if settings.max_speed > 0. && dt > 0. {
+            travel = travel.clamp_length_max(settings.max_speed * dt);
+        }
+        offset += travel;
+        if let (Some(walk), Some(source)) = (walk.as_mut(), &terrain.source) {
+            offset = walk.follow_ground(
+                &walk_settings,
+                source.as_ref(),
+                target.translation,
+                offset,
+                dt,
+            );
+        }
+        if totals.look != Vec2::ZERO {
+            let look = totals.look * settings.sensitivity * window_scale * look_scale;
+            turn.x -= look.x.to_radians();
+            turn.y -= look.y.to_radians();
+        }
+        turn += (totals.stick_look * look_scale).extend(0.);
+
+        // The actions are still worked through above so walking and swimming keep up, but a
+        // recorded frame moves exactly as it did, whatever the window, sensitivity or zoom now.
+        if let Some(motion) = recorded_motion {
+            (offset, turn) = (motion.offset, motion.turn);
+        }
+        if let Some(mut recorder) = recorder {
+            let recording = &mut recorder.recording;
+            if recording.start.is_none() {
+                recording.start = Some((target.translation, *rotation));
+            }
+            recording.frames.push(RecordedFrame {
+                delta: dt,
+                actions: camera_actions,
+                motion: Some(RecordedMotion { offset, turn }),
+            });
+        }
+
+        if offset != Vec3::ZERO {
+            target.translation += offset;
+        }
+        if turn != Vec3::ZERO {
+            rotation.rotate(turn.x, turn.y, turn.z);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use crate::target::smoothing_blend;
+use crate::{FlyCam, FlyCamAction, FlyCamInput, FlyCamMode, FlyCamTarget, FlyCamTime};
+
+/// How far away the scene is in some direction, for [`FlyCamAutoSpeed`]. Implement it with your
+/// picking or physics raycasts.
+///
+/// Closures taking the ray's origin and direction work too.
+pub trait DepthSource: Send + Sync + 'static {
+    /// Distance along the ray from `origin` in `direction` (a unit vector) to the first thing
+    /// it hits, or `None` if it hits nothing
+    fn distance(&self, origin: Vec3, direction: Vec3) -> Option<f32>;
+}
+
+impl<F> DepthSource for F
+where
+    F: Fn(Vec3, Vec3) -> Option<f32> + Send + Sync + 'static,
+{
+    fn distance(&self, origin: Vec3, direction: Vec3) -> Option<f32> {
+        self(origin, direction)
+    }
+}
+
+/// Scales a flycam's speed with how far away whatever is under the crosshair is, like in an
+/// editor: flying up close to small details slows it right down, and open space speeds it up.
+/// Add it to a flycam to turn it on.
+#[derive(Component, Clone)]
+pub struct FlyCamAutoSpeed {
+    pub source: Arc<dyn DepthSource>,
+    /// Distance at which the camera moves at its usual speed. Twice as far is twice as fast.
+    pub reference_distance: f32,
+    /// Least the speed is multiplied by, however close the scene is
+    pub min_multiplier: f32,
+    /// Most the speed is multiplied by, however far away the scene is
+    pub max_multiplier: f32,
+    /// Time constant in seconds for the speed to follow the distance, so it doesn't lurch
+    /// when the crosshair passes over an edge
+    pub smoothing: f32,
+    /// The smoothed distance, kept while the crosshair is over nothing
+    distance: Option<f32>,
+}
+
+impl FlyCamAutoSpeed {
+    pub fn new(source: impl DepthSource) -> Self {
+        Self {
+            source: Arc::new(source),
+            reference_distance: 10.,
+            min_multiplier: 0.01,
+            max_multiplier: 100.,
+            smoothing: 0.2,
+            distance: None,
+        }
+    }
+
+    /// What the speed is being multiplied by at the moment
+    pub fn multiplier(&self) -> f32 {
+        match self.distance {
+            Some(distance) if self.reference_distance > 0. => (distance / self.reference_distance)
+                .clamp(
+                    self.min_multiplier,
+                    self.max_multiplier.max(self.min_multiplier),
+                ),
+            _ => 1.,
+        }
+    }
+}
+
+/// Boosts each auto speed flycam by how far away the scene in front of it is
+pub(crate) fn auto_speed(
+    time: Res<FlyCamTime>,
+    mut actions: EventWriter<FlyCamInput>,
+    mut query: Query<(Entity, &FlyCamTarget, &FlyCamMode, &mut FlyCamAutoSpeed), With<FlyCam>>,
+) {
+    for (entity, target, mode, mut auto_speed) in query.iter_mut() {
+        if !mode.uses_fly_controls() {
+            continue;
+        }
+        if let Some(distance) = auto_speed
+            .source
+            .distance(target.translation, target.forward())
+        {
+            let smoothed = match auto_speed.distance {
+                Some(smoothed) => {
+                    let blend = smoothing_blend(auto_speed.smoothing, time.delta_seconds());
+                    smoothed + (distance - smoothed) * blend
+                }
+                None => distance,
+            };
+            auto_speed.distance = Some(smoothed);
+        }
+        let multiplier = auto_speed.multiplier();
+        if multiplier != 1. {
+            actions.send(FlyCamInput::camera(entity, FlyCamAction::Boost(multiplier)));
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+/// Which of the flycam's inputs to hold back
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockedInput {
+    /// Mouse look
+    pub look: bool,
+    /// The movement keys, gamepads and leafwing actions
+    pub movement: bool,
+    /// Scrolling forwards and backwards, and sideways
+    pub scroll: bool,
+    /// The key that grabs and releases the cursor
+    pub grab_toggle: bool,
+    /// The other keys: zoom, speed and sensitivity, plane lock, copying the pose, profiles,
+    /// spectating and photo mode
+    pub hotkeys: bool,
+}
+
+impl BlockedInput {
+    pub const NONE: Self = Self {
+        look: false,
+        movement: false,
+        scroll: false,
+        grab_toggle: false,
+        hotkeys: false,
+    };
+    pub const ALL: Self = Self {
+        look: true,
+        movement: true,
+        scroll: true,
+        grab_toggle: true,
+        hotkeys: true,
+    };
+
+    /// Whether anything is blocked
+    pub fn any(&self) -> bool {
+        *self != Self::NONE
+    }
+
+    /// Everything blocked by either
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            look: self.look || other.look,
+            movement: self.movement || other.movement,
+            scroll: self.scroll || other.scroll,
+            grab_toggle: self.grab_toggle || other.grab_toggle,
+            hotkeys: self.hotkeys || other.hotkeys,
+        }
+    }
+}
+
+/// Lets other plugins and UI hold back the flycam's input while they're using the mouse or
+/// keyboard, e.g. while a text box has focus. Each blocker is named, so several can block at
+/// once without undoing each other; input is blocked while any of them blocks it.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_flycam::{BlockedInput, FlyCamInputBlockers};
+/// fn typing(mut blockers: ResMut<FlyCamInputBlockers>) {
+///     blockers.block("chat", BlockedInput::ALL);
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FlyCamInputBlockers {
+    blockers: HashMap<&'static str, BlockedInput>,
+}
+
+impl FlyCamInputBlockers {
+    /// Blocks `input` on behalf of `source`, replacing what it blocked before
+    pub fn block(&mut self, source: &'static str, input: BlockedInput) {
+        if input.any() {
+            self.blockers.insert(source, input);
+        } else {
+            self.blockers.remove(source);
+        }
+    }
+
+    /// Stops `source` blocking anything
+    pub fn unblock(&mut self, source: &'static str) {
+        self.blockers.remove(source);
+    }
+
+    /// What `source` is blocking
+    pub fn blocked_by(&self, source: &str) -> BlockedInput {
+        self.blockers.get(source).copied().unwrap_or_default()
+    }
+
+    /// What's blocked by any source
+    pub fn blocked(&self) -> BlockedInput {
+        self.blockers
+            .values()
+            .fold(BlockedInput::NONE, |blocked, input| blocked.union(*input))
+    }
+}
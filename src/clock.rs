@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+use bevy::time::TimeSystem;
+use bevy::utils::{Duration, Instant};
+
+use crate::{FlyCamSchedule, MovementSettings};
+
+/// Which clock moves the camera, see [`MovementSettings::time_source`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum FlyCamTimeSource {
+    /// Bevy's `Time`, so the camera slows down and stops along with the game
+    #[default]
+    Game,
+    /// The wall clock, so the camera keeps working while the game is paused or slowed down,
+    /// e.g. for a debug camera
+    Real,
+}
+
+/// How long the last frame took for the flycam, on the clock picked by
+/// [`MovementSettings::time_source`] and at most [`MovementSettings::max_delta`]. Every flycam
+/// system moves by this instead of `Time`; use it in your own camera systems to keep in step.
+#[derive(Clone, Debug, Default)]
+pub struct FlyCamTime {
+    delta: Duration,
+    /// The frame's delta, while `delta` is a fixed step's, see [`FlyCamSchedule::fixed`]
+    frame_delta: Duration,
+    elapsed: Duration,
+    last_real: Option<Instant>,
+    /// Stands in for the wall clock in the test harness
+    pub(crate) fake_now: Option<Instant>,
+}
+
+impl FlyCamTime {
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    pub fn delta_seconds_f64(&self) -> f64 {
+        self.delta.as_secs_f64()
+    }
+
+    /// Every frame's delta so far, added up
+    pub fn time_since_startup(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn seconds_since_startup(&self) -> f64 {
+        self.elapsed.as_secs_f64()
+    }
+}
+
+/// Adds the flycam clock, once, for both the 3D and 2D plugins
+pub(crate) fn add_clock(app: &mut App) {
+    if app.world.contains_resource::<FlyCamTime>() {
+        return;
+    }
+    app.init_resource::<FlyCamTime>()
+        .add_system_to_stage(CoreStage::First, tick.after(TimeSystem));
+}
+
+/// Takes the frame's delta from the chosen clock, cut down to the longest frame allowed
+fn tick(settings: Res<MovementSettings>, time: Res<Time>, mut clock: ResMut<FlyCamTime>) {
+    let now = clock.fake_now.unwrap_or_else(Instant::now);
+    let real = clock
+        .last_real
+        .map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+    clock.last_real = Some(now);
+    let delta = match settings.time_source {
+        FlyCamTimeSource::Game => time.delta(),
+        FlyCamTimeSource::Real => real,
+    };
+    let delta = if settings.max_delta > 0. {
+        delta.min(Duration::from_secs_f32(settings.max_delta))
+    } else {
+        delta
+    };
+    clock.delta = delta;
+    clock.frame_delta = delta;
+    clock.elapsed += delta;
+}
+
+/// Moves the flycam by the fixed step while in [`FlyCamSchedule::fixed`]'s stage
+pub(crate) fn start_fixed_step(schedule: Res<FlyCamSchedule>, mut clock: ResMut<FlyCamTime>) {
+    if let Some(step) = schedule.fixed_timestep {
+        clock.delta = Duration::from_secs_f64(step);
+    }
+}
+
+/// Goes back to the frame's delta once the fixed steps are done, for easing the camera
+pub(crate) fn end_fixed_steps(mut clock: ResMut<FlyCamTime>) {
+    clock.delta = clock.frame_delta;
+}
@@ -0,0 +1,109 @@
+use bevy::asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use serde::Deserialize;
+
+use crate::{FlyCam, FlyCamMode, KeyBindings, MovementSettings};
+
+/// Contents of a `.flycam.ron` file. Every section is optional; sections that are left out
+/// don't touch the current values, and fields left out of a section get their defaults.
+///
+/// ```ron
+/// (
+///     settings: Some((speed: 20.0, look_smoothing: 0.1)),
+///     bindings: Some((move_ascend: [E], move_descend: [Q])),
+///     mode: Some(Fly),
+/// )
+/// ```
+#[derive(Deserialize, TypeUuid, Default)]
+#[uuid = "d5f4de4c-f126-41b9-b45d-bcd6fa7d9b7e"]
+#[serde(default)]
+pub struct FlyCamConfig {
+    pub settings: Option<MovementSettings>,
+    pub bindings: Option<KeyBindings>,
+    /// Mode every flycam is switched to
+    pub mode: Option<FlyCamMode>,
+}
+
+#[derive(Default)]
+struct FlyCamConfigLoader;
+
+impl AssetLoader for FlyCamConfigLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let config: FlyCamConfig = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(config));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["flycam.ron"]
+    }
+}
+
+/// Keeps the config asset loaded
+struct FlyCamConfigHandle(Handle<FlyCamConfig>);
+
+/// Loads [`FlyCamConfig`] from `path` (relative to the assets folder) and applies it whenever
+/// it (re)loads. Turn on bevy's `watch_for_changes` to tweak the file while the game runs.
+pub struct FlyCamConfigPlugin {
+    pub path: String,
+}
+
+impl Default for FlyCamConfigPlugin {
+    fn default() -> Self {
+        Self {
+            path: "flycam.ron".into(),
+        }
+    }
+}
+
+impl Plugin for FlyCamConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<FlyCamConfig>()
+            .init_asset_loader::<FlyCamConfigLoader>()
+            .add_system(apply_config);
+        let handle = app
+            .world
+            .resource::<AssetServer>()
+            .load::<FlyCamConfig, _>(self.path.as_str());
+        app.insert_resource(FlyCamConfigHandle(handle));
+    }
+}
+
+fn apply_config(
+    mut events: EventReader<AssetEvent<FlyCamConfig>>,
+    configs: Res<Assets<FlyCamConfig>>,
+    handle: Res<FlyCamConfigHandle>,
+    mut settings: ResMut<MovementSettings>,
+    mut bindings: ResMut<KeyBindings>,
+    mut modes: Query<&mut FlyCamMode, With<FlyCam>>,
+) {
+    for event in events.iter() {
+        let changed = match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => handle,
+            AssetEvent::Removed { .. } => continue,
+        };
+        if *changed != handle.0 {
+            continue;
+        }
+        if let Some(config) = configs.get(changed) {
+            if let Some(new_settings) = &config.settings {
+                *settings = new_settings.clone();
+            }
+            if let Some(new_bindings) = &config.bindings {
+                *bindings = new_bindings.clone();
+            }
+            if let Some(mode) = config.mode {
+                for mut current in modes.iter_mut() {
+                    *current = mode;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use bevy::diagnostic::{Diagnostic, DiagnosticId, Diagnostics};
+use bevy::prelude::*;
+
+use crate::{FlyCam, FlyCamTime, FlyCamVelocity};
+
+/// Adds flycam diagnostics to an App, specifically "flycam_speed", "flycam_distance" and
+/// "flycam_rotation_rate", measured from the first flycam. They show up in
+/// `LogDiagnosticsPlugin` output next to fps.
+#[derive(Default)]
+pub struct FlyCamDiagnosticsPlugin;
+
+/// What the rotation rate is measured against, and the running distance total
+#[derive(Default)]
+struct FlyCamDiagnosticsState {
+    last_rotation: Option<Quat>,
+    distance: f64,
+}
+
+impl Plugin for FlyCamDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(Self::setup_system)
+            .init_resource::<FlyCamDiagnosticsState>()
+            .add_system(Self::diagnostic_system);
+    }
+}
+
+impl FlyCamDiagnosticsPlugin {
+    pub const SPEED: DiagnosticId =
+        DiagnosticId::from_u128(132453563773300549318197690354361497855);
+    pub const DISTANCE: DiagnosticId =
+        DiagnosticId::from_u128(110032243840716835241880397732469729236);
+    pub const ROTATION_RATE: DiagnosticId =
+        DiagnosticId::from_u128(126982261870814493288197564053836635615);
+
+    fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics.add(Diagnostic::new(Self::SPEED, "flycam_speed", 20).with_suffix("/s"));
+        diagnostics.add(Diagnostic::new(Self::DISTANCE, "flycam_distance", 1));
+        diagnostics.add(
+            Diagnostic::new(Self::ROTATION_RATE, "flycam_rotation_rate", 20).with_suffix("°/s"),
+        );
+    }
+
+    fn diagnostic_system(
+        mut diagnostics: ResMut<Diagnostics>,
+        time: Res<FlyCamTime>,
+        mut state: ResMut<FlyCamDiagnosticsState>,
+        query: Query<(&Transform, &FlyCamVelocity), With<FlyCam>>,
+    ) {
+        let dt = time.delta_seconds_f64();
+        let (transform, velocity) = match query.iter().next() {
+            Some(camera) => camera,
+            None => return,
+        };
+        if dt == 0.0 {
+            return;
+        }
+
+        let speed = velocity.0.length() as f64;
+        state.distance += speed * dt;
+        let distance = state.distance;
+        diagnostics.add_measurement(Self::SPEED, || speed);
+        diagnostics.add_measurement(Self::DISTANCE, || distance);
+
+        if let Some(last_rotation) = state.last_rotation {
+            let angle = last_rotation.angle_between(transform.rotation) as f64;
+            diagnostics.add_measurement(Self::ROTATION_RATE, || angle.to_degrees() / dt);
+        }
+        state.last_rotation = Some(transform.rotation);
+    }
+}
@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+
+use crate::{FlyCamRig, FlyCamTarget, FlyCamTime};
+
+/// A dolly zoom, the vertigo shot: the camera moves along its view while its field of view
+/// widens or narrows to keep whatever is [`focus_distance`](Self::focus_distance) ahead the
+/// same size, so the background seems to stretch or squash around it.
+///
+/// Add it to a flycam with a perspective projection to play it. Other movement carries on on
+/// top of it, and the field of view follows wherever the camera really is, smoothing included.
+/// It removes itself once it's done, leaving the field of view where it ended up until
+/// [`MovementSettings::fov`](crate::MovementSettings::fov) next changes.
+#[derive(Component, Clone, Debug)]
+pub struct FlyCamDollyZoom {
+    /// How far to move along the view, negative to back away
+    pub distance: f32,
+    /// How far ahead the subject that keeps its size is when the dolly zoom starts
+    pub focus_distance: f32,
+    /// How long the dolly zoom takes, in seconds
+    pub duration: f32,
+    /// Eases the move: how far along it is, from 0 to 1, at each point in time from 0 to 1.
+    /// Smoothstep by default.
+    pub curve: fn(f32) -> f32,
+    elapsed: f32,
+    /// How far along the curve the target has been moved so far
+    progress: f32,
+    /// Where the subject is
+    focus: Option<Vec3>,
+    /// Half the height of the view around the subject
+    half_height: Option<f32>,
+}
+
+impl FlyCamDollyZoom {
+    /// Moves `distance` along the view over `duration` seconds, keeping the subject
+    /// `focus_distance` ahead the same size
+    pub fn new(distance: f32, focus_distance: f32, duration: f32) -> Self {
+        Self {
+            distance,
+            focus_distance,
+            duration,
+            curve: |t| t * t * (3. - 2. * t),
+            elapsed: 0.,
+            progress: 0.,
+            focus: None,
+            half_height: None,
+        }
+    }
+
+    /// Eases the move with `curve` instead of smoothstep
+    pub fn with_curve(mut self, curve: fn(f32) -> f32) -> Self {
+        self.curve = curve;
+        self
+    }
+}
+
+/// Moves dolly zooming flycams' targets along their view
+pub(crate) fn dolly_zoom(
+    time: Res<FlyCamTime>,
+    mut query: Query<(&mut FlyCamDollyZoom, &mut FlyCamTarget, &Transform)>,
+) {
+    for (mut dolly, mut target, transform) in query.iter_mut() {
+        if dolly.progress >= 1. {
+            continue;
+        }
+        let forward = target.forward();
+        if dolly.focus.is_none() {
+            dolly.focus = Some(transform.translation + forward * dolly.focus_distance);
+        }
+        dolly.elapsed += time.delta_seconds();
+        let t = if dolly.duration > 0. {
+            (dolly.elapsed / dolly.duration).min(1.)
+        } else {
+            1.
+        };
+        let progress = if t < 1. { (dolly.curve)(t) } else { 1. };
+        let step = progress - dolly.progress;
+        dolly.progress = progress;
+        target.translation += forward * dolly.distance * step;
+    }
+}
+
+/// Keeps the subject of each dolly zoom the same size by changing the field of view to match
+/// how far away it is, and finishes dolly zooms once the camera has got there
+pub(crate) fn dolly_zoom_fov(
+    mut commands: Commands,
+    mut query: Query<(
+        Entity,
+        &mut FlyCamDollyZoom,
+        &Transform,
+        &FlyCamTarget,
+        Option<&FlyCamRig>,
+    )>,
+    mut projections: Query<&mut Projection>,
+) {
+    for (entity, mut dolly, transform, target, rig) in query.iter_mut() {
+        // The projection is on the flycam itself or the pitching child of its rig.
+        let mut projection = match projections.get_mut(rig.map_or(entity, |rig| rig.camera)) {
+            Ok(projection) => projection,
+            Err(_) => continue,
+        };
+        let perspective = match projection.as_mut() {
+            Projection::Perspective(perspective) => perspective,
+            _ => continue,
+        };
+        // A rig's own transform only turns with the heading, so look along the target.
+        let forward = target.forward();
+        let focus = match dolly.focus {
+            Some(focus) => focus,
+            None => continue,
+        };
+        let focus_distance = dolly.focus_distance;
+        let half_height = *dolly
+            .half_height
+            .get_or_insert_with(|| focus_distance * (perspective.fov / 2.).tan());
+        let distance = (focus - transform.translation).dot(forward);
+        if distance > 0. {
+            let fov = 2. * (half_height / distance).atan();
+            if perspective.fov != fov {
+                perspective.fov = fov;
+            }
+        }
+        if dolly.progress >= 1. && transform.translation.distance(target.translation) < 1e-4 {
+            commands.entity(entity).remove::<FlyCamDollyZoom>();
+        }
+    }
+}
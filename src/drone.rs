@@ -0,0 +1,152 @@
+use bevy::prelude::*;
+
+use crate::rotation::wrap_angle;
+use crate::target::smoothing_blend;
+use crate::{
+    pressed, FlyCam, FlyCamInputBlockers, FlyCamKeys, FlyCamMode, FlyCamTarget, FlyCamTime,
+    KeyBindings,
+};
+
+/// Settings for [`FlyCamMode::Drone`], a quadcopter-style camera. The movement keys tilt
+/// the drone, which then accelerates in the direction it leans.
+/// Angles are in radians.
+pub struct DroneSettings {
+    /// Mass of the drone. Heavier drones take longer to speed up and slow down.
+    pub mass: f32,
+    /// How strongly air resistance slows the drone down
+    pub drag: f32,
+    /// Furthest the drone leans forwards, backwards or sideways
+    pub max_tilt: f32,
+    /// Time constant in seconds for the drone to lean to where the keys ask
+    pub tilt_response: f32,
+    /// Extra thrust from the ascend key as a multiple of what it takes to hover.
+    /// The descend key takes the same amount away.
+    pub throttle: f32,
+    /// Downwards acceleration, in units per second squared
+    pub gravity: f32,
+    /// Radians per second that Q/E turn the drone
+    pub yaw_speed: f32,
+}
+
+impl Default for DroneSettings {
+    fn default() -> Self {
+        Self {
+            mass: 1.,
+            drag: 0.5,
+            max_tilt: 30f32.to_radians(),
+            tilt_response: 0.15,
+            throttle: 1.,
+            gravity: 9.81,
+            yaw_speed: 2.,
+        }
+    }
+}
+
+/// How a drone camera is flying. Added when a flycam switches to [`FlyCamMode::Drone`].
+#[derive(Component, Clone, Copy, Debug)]
+pub struct DroneState {
+    /// Units per second
+    pub velocity: Vec3,
+    /// Heading around the global y axis
+    pub yaw: f32,
+    /// Forward (x) and sideways (y) lean
+    pub tilt: Vec2,
+}
+
+/// Starts the drone hovering where the camera is, facing the same way
+#[allow(clippy::type_complexity)]
+pub(crate) fn enter_drone(
+    mut commands: Commands,
+    query: Query<(Entity, &Transform, &FlyCamMode), (With<FlyCam>, Changed<FlyCamMode>)>,
+) {
+    for (entity, transform, mode) in query.iter() {
+        if *mode != FlyCamMode::Drone {
+            continue;
+        }
+        let forward = transform.forward();
+        commands.entity(entity).insert(DroneState {
+            velocity: Vec3::ZERO,
+            yaw: f32::atan2(-forward.x, -forward.z),
+            tilt: Vec2::ZERO,
+        });
+    }
+}
+
+/// Leans the drone with the movement keys and flies it under thrust, gravity and drag.
+/// Thrust is raised as the drone leans so it holds its height unless ascending or descending.
+pub(crate) fn drone_camera(
+    keys: Res<FlyCamKeys>,
+    time: Res<FlyCamTime>,
+    settings: Res<DroneSettings>,
+    bindings: Res<KeyBindings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut query: Query<(&mut FlyCamTarget, &FlyCamMode, &mut DroneState), With<FlyCam>>,
+) {
+    let dt = time.delta_seconds();
+    let bindings = bindings.for_mode(FlyCamMode::Drone);
+    let blocked = blockers.blocked();
+
+    let mut lean = Vec2::ZERO;
+    if pressed(&keys, &bindings.move_forward) {
+        lean.x += 1.;
+    }
+    if pressed(&keys, &bindings.move_backward) {
+        lean.x -= 1.;
+    }
+    if pressed(&keys, &bindings.move_right) {
+        lean.y += 1.;
+    }
+    if pressed(&keys, &bindings.move_left) {
+        lean.y -= 1.;
+    }
+    if blocked.movement {
+        lean = Vec2::ZERO;
+    }
+    let lean = lean.normalize_or_zero() * settings.max_tilt;
+
+    let mut throttle = 0.;
+    if pressed(&keys, &bindings.move_ascend) {
+        throttle += settings.throttle;
+    }
+    if pressed(&keys, &bindings.move_descend) {
+        throttle -= settings.throttle;
+    }
+    if blocked.movement {
+        throttle = 0.;
+    }
+
+    let mut turn = 0.;
+    if pressed(&keys, &bindings.yaw_left) {
+        turn += 1.;
+    }
+    if pressed(&keys, &bindings.yaw_right) {
+        turn -= 1.;
+    }
+    if blocked.look {
+        turn = 0.;
+    }
+
+    let tilt_blend = smoothing_blend(settings.tilt_response, dt);
+    let mass = settings.mass.max(f32::EPSILON);
+    for (mut target, mode, mut state) in query.iter_mut() {
+        if *mode != FlyCamMode::Drone {
+            continue;
+        }
+        state.yaw = wrap_angle(state.yaw + turn * settings.yaw_speed * dt);
+        let tilt = state.tilt + (lean - state.tilt) * tilt_blend;
+        state.tilt = tilt;
+
+        let rotation = Quat::from_rotation_y(state.yaw)
+            * Quat::from_rotation_x(-tilt.x)
+            * Quat::from_rotation_z(-tilt.y);
+        let up = rotation * Vec3::Y;
+        let hover = settings.gravity / up.y.max(0.5);
+        let acceleration = up * hover * (1. + throttle)
+            - Vec3::Y * settings.gravity
+            - state.velocity * settings.drag / mass;
+        state.velocity += acceleration * dt;
+
+        target.translation += state.velocity * dt;
+        target.rotation = rotation;
+    }
+}
@@ -0,0 +1,215 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContext};
+
+use crate::{FlyCam, FlyCamMode, FlyCamTimeSource, KeyBindings, MovementSettings};
+
+/// Settings for the window added by [`FlyCamEguiPlugin`]
+pub struct FlyCamEguiSettings {
+    /// Whether the window is showing
+    pub open: bool,
+    /// Keys that show and hide the window
+    pub toggle: Vec<KeyCode>,
+}
+
+impl Default for FlyCamEguiSettings {
+    fn default() -> Self {
+        Self {
+            open: true,
+            toggle: vec![KeyCode::F1],
+        }
+    }
+}
+
+/// Index into [`KeyBindings::actions_mut`] of the action waiting for a new key
+#[derive(Default)]
+struct Rebinding(Option<usize>);
+
+fn settings_window(
+    mut egui_context: ResMut<EguiContext>,
+    keys: Res<Input<KeyCode>>,
+    mut panel: ResMut<FlyCamEguiSettings>,
+    mut settings: ResMut<MovementSettings>,
+    mut bindings: ResMut<KeyBindings>,
+    mut rebinding: Local<Rebinding>,
+    mut modes: Query<&mut FlyCamMode, With<FlyCam>>,
+) {
+    if keys.any_just_pressed(panel.toggle.iter().copied()) {
+        panel.open = !panel.open;
+    }
+    if !panel.open {
+        return;
+    }
+
+    // Edit copies so the resources are only marked as changed when something was changed.
+    let mut edited_settings = settings.clone();
+    let mut edited_bindings = bindings.clone();
+    let mut settings_changed = false;
+    let mut bindings_changed = false;
+
+    if let Some(index) = rebinding.0 {
+        if let Some(key) = keys.get_just_pressed().next() {
+            if *key != KeyCode::Escape {
+                *edited_bindings.actions_mut()[index].1 = vec![*key];
+                bindings_changed = true;
+            }
+            rebinding.0 = None;
+        }
+    }
+
+    let mut open = panel.open;
+    egui::Window::new("Flycam")
+        .open(&mut open)
+        .show(egui_context.ctx_mut(), |ui| {
+            ui.heading("Movement");
+            let s = &mut edited_settings;
+            for response in [
+                ui.add(
+                    egui::Slider::new(&mut s.sensitivity, 0.00001..=0.001)
+                        .logarithmic(true)
+                        .text("sensitivity"),
+                ),
+                ui.add(
+                    egui::Slider::new(&mut s.speed, 0.1..=1000.)
+                        .logarithmic(true)
+                        .text("speed"),
+                ),
+                ui.add(
+                    egui::Slider::new(&mut s.max_speed, 0.0..=100_000.)
+                        .logarithmic(true)
+                        .text("max speed"),
+                ),
+                ui.add(egui::Slider::new(&mut s.fov, 0.1..=3.).text("fov")),
+                ui.add(egui::Slider::new(&mut s.zoom_fov, 0.05..=3.).text("zoom fov")),
+                ui.add(egui::Slider::new(&mut s.zoom_time, 0.0..=2.).text("zoom time")),
+                ui.checkbox(&mut s.fov_compensated_look, "slower look when zoomed"),
+                ui.add(egui::Slider::new(&mut s.look_smoothing, 0.0..=1.).text("look smoothing")),
+                ui.add(
+                    egui::Slider::new(&mut s.translation_smoothing, 0.0..=1.)
+                        .text("translation smoothing"),
+                ),
+                ui.add(
+                    egui::Slider::new(&mut s.rotation_smoothing, 0.0..=1.)
+                        .text("rotation smoothing"),
+                ),
+                ui.checkbox(&mut s.stepping, "stepping"),
+                ui.add(
+                    egui::Slider::new(&mut s.step_distance, 0.01..=100.)
+                        .logarithmic(true)
+                        .text("step distance"),
+                ),
+                ui.add(egui::Slider::new(&mut s.step_angle, 0.0..=1.6).text("step angle")),
+                ui.add(egui::Slider::new(&mut s.grid_snap, 0.0..=10.).text("grid snap")),
+                ui.checkbox(&mut s.plane_lock, "plane lock"),
+                ui.checkbox(&mut s.vertical_along_view, "vertical along view"),
+                ui.checkbox(&mut s.fly_along_look, "fly along look"),
+                ui.add(
+                    egui::Slider::new(&mut s.horizontal_scroll_sensitivity, 0.0..=0.001)
+                        .text("sideways scroll"),
+                ),
+                ui.add(
+                    egui::Slider::new(&mut s.web_scroll_sensitivity, 0.1..=10.)
+                        .logarithmic(true)
+                        .text("browser scroll"),
+                ),
+                ui.add(
+                    egui::Slider::new(&mut s.touch_sensitivity, 0.0001..=0.05)
+                        .logarithmic(true)
+                        .text("touch sensitivity"),
+                ),
+                ui.add(
+                    egui::Slider::new(&mut s.keyboard_look_speed, 10.0..=360.)
+                        .text("keyboard look speed"),
+                ),
+                ui.add(
+                    egui::Slider::new(&mut s.mouse_acceleration, 1.0..=3.)
+                        .text("mouse acceleration"),
+                ),
+                ui.add(
+                    egui::Slider::new(&mut s.mouse_acceleration_threshold, 0.0..=5000.)
+                        .text("acceleration threshold"),
+                ),
+                ui.add(egui::Slider::new(&mut s.bank_angle, 0.0..=1.).text("bank angle")),
+                ui.add(egui::Slider::new(&mut s.bank_return, 0.0..=2.).text("bank return")),
+                ui.add(
+                    egui::Slider::new(&mut s.cruise_multiplier, 1.0..=20.)
+                        .text("cruise multiplier"),
+                ),
+                ui.add(egui::Slider::new(&mut s.cruise_delay, 0.0..=10.).text("cruise delay")),
+                ui.add(
+                    egui::Slider::new(&mut s.cruise_ramp_time, 0.0..=10.).text("cruise ramp time"),
+                ),
+                ui.add(
+                    egui::Slider::new(&mut s.double_tap_sprint, 0.0..=1.).text("double tap sprint"),
+                ),
+                ui.add(
+                    egui::Slider::new(&mut s.sprint_multiplier, 1.0..=20.)
+                        .text("sprint multiplier"),
+                ),
+                ui.checkbox(&mut s.toggle_boost, "toggle boost"),
+                ui.checkbox(&mut s.toggle_crouch, "toggle crouch"),
+                ui.add(egui::Slider::new(&mut s.mode_blend_time, 0.0..=2.).text("mode blend time")),
+                ui.radio_value(&mut s.time_source, FlyCamTimeSource::Game, "game time"),
+                ui.radio_value(&mut s.time_source, FlyCamTimeSource::Real, "real time"),
+                ui.add(egui::Slider::new(&mut s.max_delta, 0.0..=2.).text("max frame time")),
+                ui.checkbox(&mut s.recapture_on_click, "recapture on click"),
+            ] {
+                settings_changed |= response.changed();
+            }
+            for tier in s.boost_tiers.iter_mut() {
+                let response = ui.add(
+                    egui::Slider::new(&mut tier.multiplier, 0.01..=100.)
+                        .logarithmic(true)
+                        .text(format!("boost {:?}", tier.keys)),
+                );
+                settings_changed |= response.changed();
+            }
+
+            ui.heading("Key bindings");
+            egui::Grid::new("flycam_key_bindings").show(ui, |ui| {
+                for (index, (action, keys)) in edited_bindings.actions_mut().into_iter().enumerate()
+                {
+                    ui.label(action);
+                    ui.label(format!("{:?}", keys));
+                    let waiting = rebinding.0 == Some(index);
+                    if ui
+                        .button(if waiting { "press a key..." } else { "rebind" })
+                        .clicked()
+                    {
+                        rebinding.0 = if waiting { None } else { Some(index) };
+                    }
+                    ui.end_row();
+                }
+            });
+
+            ui.heading("Mode");
+            for mut mode in modes.iter_mut() {
+                let mut edited_mode = *mode;
+                ui.horizontal_wrapped(|ui| {
+                    for option in FlyCamMode::ALL {
+                        ui.radio_value(&mut edited_mode, option, format!("{:?}", option));
+                    }
+                });
+                if edited_mode != *mode {
+                    *mode = edited_mode;
+                }
+            }
+        });
+    panel.open = open;
+
+    if settings_changed {
+        *settings = edited_settings;
+    }
+    if bindings_changed {
+        *bindings = edited_bindings;
+    }
+}
+
+/// Adds a window for live-editing [`MovementSettings`], [`KeyBindings`] and the flycam mode.
+/// F1 toggles it. Needs `bevy_egui`'s `EguiPlugin` to be added as well.
+pub struct FlyCamEguiPlugin;
+impl Plugin for FlyCamEguiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlyCamEguiSettings>()
+            .add_system(settings_window);
+    }
+}
@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+
+use crate::{FlyCam, FlyCamVelocity};
+
+/// A box of water, or any other fluid, that flycams swim through instead of flying or walking.
+/// The box is centred on the entity and turns and scales with its [`GlobalTransform`].
+///
+/// Inside, the usual first-person controls move the camera in full 3D, even in
+/// [`FlyCamMode::Walk`](crate::FlyCamMode::Walk), but slower, drifting and with a gentle pull
+/// upwards.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct FlyCamFluid {
+    /// Half the size of the box along each of its axes
+    pub half_extents: Vec3,
+    /// Multiplies the movement speed while swimming
+    pub speed_multiplier: f32,
+    /// Time constant in seconds for the camera to pick up speed or slow down
+    pub drag: f32,
+    /// How fast the camera floats upwards when it isn't moving, in units per second
+    pub buoyancy: f32,
+}
+
+impl Default for FlyCamFluid {
+    fn default() -> Self {
+        Self {
+            half_extents: Vec3::splat(0.5),
+            speed_multiplier: 0.4,
+            drag: 0.5,
+            buoyancy: 0.5,
+        }
+    }
+}
+
+impl FlyCamFluid {
+    /// A fluid filling a box of this size
+    pub fn new(size: Vec3) -> Self {
+        Self {
+            half_extents: size / 2.,
+            ..Default::default()
+        }
+    }
+
+    /// Whether `point` is inside the box when the fluid's entity has `transform`
+    pub fn contains(&self, transform: &GlobalTransform, point: Vec3) -> bool {
+        let transform = transform.compute_transform();
+        let local =
+            transform.rotation.inverse() * (point - transform.translation) / transform.scale;
+        local.abs().cmple(self.half_extents).all()
+    }
+}
+
+/// Added to a flycam while it's inside a [`FlyCamFluid`], and taken off when it leaves
+#[derive(Component, Clone, Copy, Debug)]
+pub struct FlyCamSwimming {
+    /// The fluid the camera is in
+    pub fluid: Entity,
+    /// Units per second the camera is drifting at
+    pub velocity: Vec3,
+}
+
+/// Marks flycams as swimming while they're inside a fluid
+#[allow(clippy::type_complexity)]
+pub(crate) fn detect_fluids(
+    mut commands: Commands,
+    fluids: Query<(Entity, &GlobalTransform, &FlyCamFluid)>,
+    mut cameras: Query<
+        (
+            Entity,
+            &Transform,
+            Option<&FlyCamVelocity>,
+            Option<&mut FlyCamSwimming>,
+        ),
+        With<FlyCam>,
+    >,
+) {
+    for (camera, transform, velocity, swimming) in cameras.iter_mut() {
+        let fluid = fluids
+            .iter()
+            .find(|(_, global, fluid)| fluid.contains(global, transform.translation))
+            .map(|(entity, ..)| entity);
+        match (fluid, swimming) {
+            (Some(fluid), Some(mut swimming)) => {
+                if swimming.fluid != fluid {
+                    swimming.fluid = fluid;
+                }
+            }
+            (Some(fluid), None) => {
+                // Carry on at the speed the camera dived in at, and let the drag slow it down.
+                commands.entity(camera).insert(FlyCamSwimming {
+                    fluid,
+                    velocity: velocity.map_or(Vec3::ZERO, |velocity| velocity.0),
+                });
+            }
+            (None, Some(_)) => {
+                commands.entity(camera).remove::<FlyCamSwimming>();
+            }
+            (None, None) => {}
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::prelude::*;
+
+use crate::{
+    edge_scroll, get_boost, pressed, toggles, wheel_notches, FlyCamInputBlockers, FlyCamKeys,
+    FlyCamTime, KeyBindings, MovementSettings,
+};
+
+/// Settings specific to the 2D flycam. Boost and key bindings are shared with the 3D flycam
+/// via [`MovementSettings`] and [`KeyBindings`].
+pub struct FlyCam2dSettings {
+    /// How many pixels per second WASD pans at a zoom scale of 1
+    pub pan_speed: f32,
+    /// How much each scroll step changes the zoom scale
+    pub zoom_sensitivity: f32,
+    /// Smallest allowed projection scale (most zoomed in)
+    pub min_scale: f32,
+    /// Largest allowed projection scale (most zoomed out)
+    pub max_scale: f32,
+    /// Width in pixels of the window border that triggers edge scrolling. 0 disables it.
+    pub edge_scroll_margin: f32,
+}
+
+impl Default for FlyCam2dSettings {
+    fn default() -> Self {
+        Self {
+            pan_speed: 500.,
+            zoom_sensitivity: 0.1,
+            min_scale: 0.1,
+            max_scale: 10.,
+            edge_scroll_margin: 0.,
+        }
+    }
+}
+
+/// A marker component used in queries when you want 2D flycams and not other cameras
+#[derive(Component)]
+pub struct FlyCam2d;
+
+/// Spawns the `Camera2dBundle` to be controlled
+fn setup_player_2d(mut commands: Commands) {
+    commands
+        .spawn_bundle(Camera2dBundle::default())
+        .insert(FlyCam2d);
+}
+
+/// Handles keyboard and edge-of-window panning
+#[allow(clippy::too_many_arguments)]
+fn pan_2d(
+    keys: Res<FlyCamKeys>,
+    time: Res<FlyCamTime>,
+    windows: Res<Windows>,
+    settings: Res<MovementSettings>,
+    settings_2d: Res<FlyCam2dSettings>,
+    bindings: Res<KeyBindings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut query: Query<(&mut Transform, &OrthographicProjection), With<FlyCam2d>>,
+) {
+    if blockers.blocked().movement {
+        return;
+    }
+    let mut direction = Vec2::ZERO;
+    if pressed(&keys, &bindings.move_forward) {
+        direction.y += 1.;
+    }
+    if pressed(&keys, &bindings.move_backward) {
+        direction.y -= 1.;
+    }
+    if pressed(&keys, &bindings.move_left) {
+        direction.x -= 1.;
+    }
+    if pressed(&keys, &bindings.move_right) {
+        direction.x += 1.;
+    }
+
+    direction += edge_scroll(&windows, settings_2d.edge_scroll_margin);
+
+    let direction = direction.normalize_or_zero();
+    if direction == Vec2::ZERO {
+        return;
+    }
+    let boost = get_boost(&keys, &settings);
+    for (mut transform, projection) in query.iter_mut() {
+        let delta = direction * settings_2d.pan_speed * projection.scale * boost;
+        transform.translation += (delta * time.delta_seconds()).extend(0.);
+    }
+}
+
+/// Holding the middle mouse button drags the view around
+fn drag_2d(
+    buttons: Res<Input<MouseButton>>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut motion: EventReader<MouseMotion>,
+    mut query: Query<(&mut Transform, &OrthographicProjection), With<FlyCam2d>>,
+) {
+    let delta: Vec2 = motion.iter().map(|ev| &ev.delta).sum();
+    if !buttons.pressed(MouseButton::Middle) || blockers.blocked().movement {
+        return;
+    }
+    for (mut transform, projection) in query.iter_mut() {
+        // Screen y points down, world y points up.
+        let delta = Vec2::new(-delta.x, delta.y) * projection.scale;
+        transform.translation += delta.extend(0.);
+    }
+}
+
+/// Scroll zooms in and out, clamped between the configured min and max scale
+fn zoom_2d(
+    windows: Res<Windows>,
+    settings_2d: Res<FlyCam2dSettings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    mut query: Query<&mut OrthographicProjection, With<FlyCam2d>>,
+) {
+    if blockers.blocked().scroll {
+        mouse_wheel_events.clear();
+        return;
+    }
+    let notches = wheel_notches(&windows, &mut mouse_wheel_events).y;
+    if notches == 0. {
+        return;
+    }
+    for mut projection in query.iter_mut() {
+        let scale = projection.scale * (1. - notches * settings_2d.zoom_sensitivity);
+        projection.scale = scale.clamp(settings_2d.min_scale, settings_2d.max_scale);
+    }
+}
+
+/// Contains everything needed to add a panning and zooming 2D camera to your game
+pub struct FlyCam2dPlugin;
+impl Plugin for FlyCam2dPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_player_2d)
+            .add_plugin(NoCameraFlyCam2dPlugin);
+    }
+}
+
+/// Same as [`FlyCam2dPlugin`] but does not spawn a camera
+pub struct NoCameraFlyCam2dPlugin;
+impl Plugin for NoCameraFlyCam2dPlugin {
+    fn build(&self, app: &mut App) {
+        crate::clock::add_clock(app);
+        toggles::add_key_latching(app, CoreStage::Update, toggles::latch_keys);
+        app.init_resource::<MovementSettings>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<FlyCam2dSettings>()
+            .init_resource::<FlyCamInputBlockers>()
+            .add_system(pan_2d.after(toggles::latch_keys))
+            .add_system(drag_2d)
+            .add_system(zoom_2d);
+    }
+}
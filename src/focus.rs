@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use crate::target::smoothing_blend;
+use crate::{DepthSource, FlyCamRig, FlyCamTime};
+
+/// Autofocus for a flycam: measures how far away whatever is in the middle of the view is
+/// and keeps it in a [`FocusDistance`] on the flycam, for a depth of field effect to focus on.
+/// Add it to a flycam to turn it on. Photo mode cameras take it over from the camera they
+/// start from, and report the focus with each [`PhotoTaken`](crate::PhotoTaken).
+///
+/// Bevy doesn't come with depth of field yet, so feed [`FocusDistance`] to yours.
+#[derive(Component, Clone)]
+pub struct FlyCamAutofocus {
+    pub source: Arc<dyn DepthSource>,
+    /// Time constant in seconds for the focus to follow the scene, like a lens being pulled
+    /// rather than snapping
+    pub smoothing: f32,
+    /// Where it focuses when nothing is in the middle of the view, and the furthest it
+    /// focuses otherwise
+    pub max_distance: f32,
+}
+
+impl FlyCamAutofocus {
+    pub fn new(source: impl DepthSource) -> Self {
+        Self {
+            source: Arc::new(source),
+            smoothing: 0.3,
+            max_distance: 1000.,
+        }
+    }
+}
+
+/// How far in front of a flycam its view is in focus, kept up to date by [`FlyCamAutofocus`]
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct FocusDistance(pub f32);
+
+/// Focuses each autofocus flycam on what's in the middle of its view
+#[allow(clippy::type_complexity)]
+pub(crate) fn autofocus(
+    mut commands: Commands,
+    time: Res<FlyCamTime>,
+    mut query: Query<(
+        Entity,
+        &FlyCamAutofocus,
+        &GlobalTransform,
+        Option<&FlyCamRig>,
+        Option<&mut FocusDistance>,
+    )>,
+    globals: Query<&GlobalTransform>,
+) {
+    for (entity, autofocus, global, rig, focus) in query.iter_mut() {
+        // A rig looks through its pitching child.
+        let view = match rig.and_then(|rig| globals.get(rig.camera).ok()) {
+            Some(view) => view,
+            None => global,
+        };
+        let distance = autofocus
+            .source
+            .distance(view.translation(), view.forward())
+            .map_or(autofocus.max_distance, |distance| {
+                distance.min(autofocus.max_distance)
+            });
+        match focus {
+            Some(mut focus) => {
+                let blend = smoothing_blend(autofocus.smoothing, time.delta_seconds());
+                let smoothed = focus.0 + (distance - focus.0) * blend;
+                if focus.0 != smoothed {
+                    focus.0 = smoothed;
+                }
+            }
+            None => {
+                commands.entity(entity).insert(FocusDistance(distance));
+            }
+        }
+    }
+}
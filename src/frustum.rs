@@ -0,0 +1,200 @@
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+use bevy::render::primitives::Frustum;
+use bevy::render::view::VisibilitySystems;
+
+use crate::{FlyCam, FlyCamKeys, FlyCamRig, FlyCamSchedule, FlyCamSystem};
+
+/// Settings for the frozen frustum added by [`FlyCamFrustumPlugin`]
+pub struct FlyCamFrustumSettings {
+    /// Keys that freeze the frustum and let it go again
+    pub toggle: Vec<KeyCode>,
+    /// Colour of the frozen frustum's outline, with the `debug-lines` feature
+    pub color: Color,
+}
+
+impl Default for FlyCamFrustumSettings {
+    fn default() -> Self {
+        Self {
+            toggle: vec![KeyCode::F4],
+            color: Color::YELLOW,
+        }
+    }
+}
+
+/// A flycam's view frozen where it was, which culling keeps using while the flycam flies off to
+/// look at what was culled. It's on the camera the flycam looks through: the flycam itself, or
+/// the pitching child of a [`FlyCamRig`].
+#[derive(Component, Clone, Debug)]
+pub struct FlyCamFrozenFrustum {
+    frustum: Frustum,
+    corners: [Vec3; 8],
+    /// The outline being drawn
+    lines: Option<Entity>,
+}
+
+impl FlyCamFrozenFrustum {
+    /// The frustum culling uses
+    pub fn frustum(&self) -> &Frustum {
+        &self.frustum
+    }
+
+    /// Corners in world space: the near plane's, then the far plane's, each going bottom left,
+    /// bottom right, top right, top left
+    pub fn corners(&self) -> [Vec3; 8] {
+        self.corners
+    }
+
+    /// The twelve edges between the corners, for drawing the frustum with your own lines
+    pub fn edges(&self) -> [(Vec3, Vec3); 12] {
+        let c = self.corners;
+        [
+            (c[0], c[1]),
+            (c[1], c[2]),
+            (c[2], c[3]),
+            (c[3], c[0]),
+            (c[4], c[5]),
+            (c[5], c[6]),
+            (c[6], c[7]),
+            (c[7], c[4]),
+            (c[0], c[4]),
+            (c[1], c[5]),
+            (c[2], c[6]),
+            (c[3], c[7]),
+        ]
+    }
+}
+
+/// The corners of a projection's view volume in its camera's space, in the order
+/// [`FlyCamFrozenFrustum::corners`] gives them
+fn view_corners(projection: &Projection) -> [Vec3; 8] {
+    // (depth, half size, centre) of the near and far planes
+    let planes = match projection {
+        Projection::Perspective(perspective) => [perspective.near, perspective.far].map(|depth| {
+            let half_height = depth * (perspective.fov / 2.).tan();
+            let half_size = Vec2::new(half_height * perspective.aspect_ratio, half_height);
+            (depth, half_size, Vec2::ZERO)
+        }),
+        Projection::Orthographic(orthographic) => {
+            let min = Vec2::new(orthographic.left, orthographic.bottom) * orthographic.scale;
+            let max = Vec2::new(orthographic.right, orthographic.top) * orthographic.scale;
+            [orthographic.near, orthographic.far]
+                .map(|depth| (depth, (max - min) / 2., (max + min) / 2.))
+        }
+    };
+    let mut corners = [Vec3::ZERO; 8];
+    for (i, (depth, half_size, centre)) in planes.into_iter().enumerate() {
+        let square = [(-1., -1.), (1., -1.), (1., 1.), (-1., 1.)];
+        for (j, (x, y)) in square.into_iter().enumerate() {
+            let corner = centre + half_size * Vec2::new(x, y);
+            corners[i * 4 + j] = corner.extend(-depth);
+        }
+    }
+    corners
+}
+
+/// Adds a debugging aid for culling and LOD: the toggle key freezes each flycam's frustum where
+/// it is, so culling carries on as if the camera hadn't moved while the flycam flies out to
+/// see what's being drawn. Pressing it again lets the frustum go.
+///
+/// With the `debug-lines` feature it outlines the frozen frustum too. Otherwise draw
+/// [`FlyCamFrozenFrustum::edges`] with whatever lines you use.
+///
+/// Add it alongside [`PlayerPlugin`](crate::PlayerPlugin) or
+/// [`NoCameraPlayerPlugin`](crate::NoCameraPlayerPlugin).
+pub struct FlyCamFrustumPlugin;
+impl Plugin for FlyCamFrustumPlugin {
+    fn build(&self, app: &mut App) {
+        let stage = FlyCamSchedule::stage(app);
+        app.init_resource::<FlyCamFrustumSettings>()
+            .add_system_to_stage(stage, toggle_frozen_frustum.after(FlyCamSystem::Control))
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                apply_frozen_frustum
+                    .after(VisibilitySystems::UpdateOrthographicFrusta)
+                    .after(VisibilitySystems::UpdatePerspectiveFrusta)
+                    .after(VisibilitySystems::UpdateProjectionFrusta)
+                    .before(VisibilitySystems::CheckVisibility),
+            );
+    }
+}
+
+/// Freezes or lets go of every flycam's frustum
+fn toggle_frozen_frustum(
+    mut commands: Commands,
+    keys: Res<FlyCamKeys>,
+    settings: Res<FlyCamFrustumSettings>,
+    flycams: Query<(Entity, Option<&FlyCamRig>), With<FlyCam>>,
+    cameras: Query<(
+        &Frustum,
+        &Projection,
+        &GlobalTransform,
+        Option<&FlyCamFrozenFrustum>,
+    )>,
+    #[cfg(feature = "debug-lines")] mut meshes: Option<ResMut<Assets<Mesh>>>,
+    #[cfg(feature = "debug-lines")] mut materials: Option<ResMut<Assets<StandardMaterial>>>,
+) {
+    if !keys.any_just_pressed(settings.toggle.iter().copied()) {
+        return;
+    }
+    for (flycam, rig) in flycams.iter() {
+        let camera = rig.map_or(flycam, |rig| rig.camera);
+        let (frustum, projection, transform, frozen) = match cameras.get(camera) {
+            Ok(camera) => camera,
+            Err(_) => continue,
+        };
+        if let Some(frozen) = frozen {
+            if let Some(lines) = frozen.lines {
+                commands.entity(lines).despawn();
+            }
+            commands.entity(camera).remove::<FlyCamFrozenFrustum>();
+            continue;
+        }
+        let frozen = FlyCamFrozenFrustum {
+            frustum: *frustum,
+            corners: view_corners(projection).map(|corner| transform.mul_vec3(corner)),
+            lines: None,
+        };
+        // Only apps that render have meshes to draw with.
+        #[cfg(feature = "debug-lines")]
+        let frozen = match (meshes.as_mut(), materials.as_mut()) {
+            (Some(meshes), Some(materials)) => FlyCamFrozenFrustum {
+                lines: Some(spawn_lines(
+                    &mut commands,
+                    meshes,
+                    materials,
+                    &frozen,
+                    settings.color,
+                )),
+                ..frozen
+            },
+            _ => frozen,
+        };
+        commands.entity(camera).insert(frozen);
+    }
+}
+
+/// Spawns an outline of the frozen frustum
+#[cfg(feature = "debug-lines")]
+fn spawn_lines(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    frozen: &FlyCamFrozenFrustum,
+    color: Color,
+) -> Entity {
+    commands
+        .spawn_bundle(PbrBundle {
+            mesh: meshes.add(crate::lines::line_mesh(frozen.edges())),
+            material: materials.add(crate::lines::line_material(color)),
+            ..default()
+        })
+        .id()
+}
+
+/// Puts frozen frustums back after bevy has moved them along with their cameras
+fn apply_frozen_frustum(mut query: Query<(&mut Frustum, &FlyCamFrozenFrustum)>) {
+    for (mut frustum, frozen) in query.iter_mut() {
+        *frustum = frozen.frustum;
+    }
+}
@@ -0,0 +1,173 @@
+use bevy::prelude::*;
+
+use crate::actions::ExternalInput;
+use crate::{
+    FlyCam, FlyCamAction, FlyCamInput, FlyCamInputBlockers, FlyCamMode, FlyCamTime, InputDevice,
+    MovementSettings, SensitivityProfiles,
+};
+
+/// How gamepads fly the camera: the left stick moves, the right trigger ascends, the left
+/// trigger descends and the right stick looks around. The camera's speed and turning follow
+/// how far they're pushed, so a gentle push creeps along.
+///
+/// Gamepads are read from bevy's gamepad input, so enable bevy's `bevy_gilrs` feature for
+/// them to show up.
+pub struct FlyCamGamepadSettings {
+    /// How far the left stick or a trigger has to be pushed before the camera moves, from 0
+    /// to 1
+    pub move_deadzone: f32,
+    /// Response curve from how far the stick is pushed past the deadzone to the speed: 1 is
+    /// linear, and higher gives finer control over slow movement.
+    pub move_exponent: f32,
+    /// How fast the right stick turns the camera when pushed all the way, in radians per
+    /// second. Separate from the mouse's [`MovementSettings::sensitivity`], which works in
+    /// pixels of motion.
+    pub look_sensitivity: f32,
+    /// How far the right stick has to be pushed before the camera turns, from 0 to 1
+    pub look_deadzone: f32,
+    /// Response curve for turning, like `move_exponent`
+    pub look_exponent: f32,
+    /// Turn up when the right stick is pulled back, like a flight stick
+    pub invert_look_y: bool,
+}
+
+impl Default for FlyCamGamepadSettings {
+    fn default() -> Self {
+        Self {
+            move_deadzone: 0.1,
+            move_exponent: 2.,
+            look_sensitivity: 2.5,
+            look_deadzone: 0.15,
+            look_exponent: 2.,
+            invert_look_y: false,
+        }
+    }
+}
+
+/// `deflection` (from 0 to 1) past `deadzone`, rescaled to 0 to 1 and shaped by `exponent`
+pub(crate) fn response(deflection: f32, deadzone: f32, exponent: f32) -> f32 {
+    let deadzone = deadzone.clamp(0., 0.99);
+    if deflection <= deadzone {
+        return 0.;
+    }
+    ((deflection - deadzone) / (1. - deadzone))
+        .min(1.)
+        .powf(exponent.max(f32::EPSILON))
+}
+
+/// `stick` with its length shaped by [`response`], keeping its direction
+pub(crate) fn stick_response(stick: Vec2, deadzone: f32, exponent: f32) -> Vec2 {
+    stick.normalize_or_zero() * response(stick.length(), deadzone, exponent)
+}
+
+/// Turns the gamepads' sticks and triggers into movement actions
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(crate) fn gamepad_move(
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    buttons: Res<Axis<GamepadButton>>,
+    settings: Res<MovementSettings>,
+    gamepad_settings: Res<FlyCamGamepadSettings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut actions: EventWriter<FlyCamInput>,
+    query: Query<(Entity, &FlyCamMode), (With<FlyCam>, Without<ExternalInput>)>,
+) {
+    // Sticks don't have presses to count steps by.
+    if blockers.blocked().movement || settings.stepping {
+        return;
+    }
+    let (deadzone, exponent) = (
+        gamepad_settings.move_deadzone,
+        gamepad_settings.move_exponent,
+    );
+    let mut movement = Vec3::ZERO;
+    for &gamepad in gamepads.iter() {
+        let axis = |axis_type| {
+            axes.get(GamepadAxis::new(gamepad, axis_type))
+                .unwrap_or_default()
+        };
+        let trigger = |button_type| {
+            let value = buttons
+                .get(GamepadButton::new(gamepad, button_type))
+                .unwrap_or_default();
+            response(value, deadzone, exponent)
+        };
+        let stick = stick_response(
+            Vec2::new(
+                axis(GamepadAxisType::LeftStickX),
+                axis(GamepadAxisType::LeftStickY),
+            ),
+            deadzone,
+            exponent,
+        );
+        let vertical =
+            trigger(GamepadButtonType::RightTrigger2) - trigger(GamepadButtonType::LeftTrigger2);
+        movement += Vec3::new(stick.x, vertical, stick.y);
+    }
+    if movement == Vec3::ZERO {
+        return;
+    }
+
+    for (entity, mode) in query.iter() {
+        if !mode.uses_fly_controls() {
+            continue;
+        }
+        let mut send = |action: fn(f32) -> FlyCamAction, amount: f32| {
+            if amount != 0. {
+                actions.send(FlyCamInput::camera(entity, action(amount)));
+            }
+        };
+        send(FlyCamAction::MoveForward, movement.z);
+        send(FlyCamAction::MoveRight, movement.x);
+        send(FlyCamAction::MoveUp, movement.y);
+    }
+}
+
+/// Turns the gamepads' right sticks into look actions
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(crate) fn gamepad_look(
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    time: Res<FlyCamTime>,
+    settings: Res<FlyCamGamepadSettings>,
+    blockers: Res<FlyCamInputBlockers>,
+    profiles: Res<SensitivityProfiles>,
+    mut actions: EventWriter<FlyCamInput>,
+    query: Query<(Entity, &FlyCamMode), (With<FlyCam>, Without<ExternalInput>)>,
+) {
+    if blockers.blocked().look {
+        return;
+    }
+    let mut look = Vec2::ZERO;
+    for &gamepad in gamepads.iter() {
+        let axis = |axis_type| {
+            axes.get(GamepadAxis::new(gamepad, axis_type))
+                .unwrap_or_default()
+        };
+        look += stick_response(
+            Vec2::new(
+                axis(GamepadAxisType::RightStickX),
+                axis(GamepadAxisType::RightStickY),
+            ),
+            settings.look_deadzone,
+            settings.look_exponent,
+        );
+    }
+    if look == Vec2::ZERO {
+        return;
+    }
+    if settings.invert_look_y {
+        look.y = -look.y;
+    }
+    // Pushing right turns right, which is negative yaw.
+    let turn = Vec2::new(-look.x, look.y)
+        * settings.look_sensitivity
+        * profiles.get(InputDevice::Gamepad)
+        * time.delta_seconds();
+
+    for (entity, mode) in query.iter() {
+        if mode.uses_fly_controls() {
+            actions.send(FlyCamInput::camera(entity, FlyCamAction::StickLook(turn)));
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+
+use crate::{
+    FlyCamAction, FlyCamInput, FlyCamInputBlockers, FlyCamSchedule, InputDevice,
+    SensitivityProfiles,
+};
+
+/// A pinch or twist on a trackpad.
+///
+/// Bevy 0.8 doesn't read gestures from the OS, so send these from whatever does on your
+/// platform, e.g. `NSEvent` magnification on macOS or Safari's `gesturechange`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TrackpadGesture {
+    /// Fingers spreading apart (positive) or pinching together, as the change in
+    /// magnification, e.g. 0.1 for 10% bigger
+    Pinch(f32),
+    /// Fingers twisting anticlockwise (positive) or clockwise, in radians
+    Rotate(f32),
+}
+
+/// How far trackpad gestures move the camera
+pub struct FlyCamGestureSettings {
+    /// Scroll steps a pinch with a magnification of 1 dollies forward by. One line of a mouse
+    /// wheel is 1024 steps.
+    pub pinch_steps: f32,
+    /// How far the camera turns for each radian the fingers twist. The world turns with the
+    /// fingers, so twisting anticlockwise turns the camera right.
+    pub rotate_scale: f32,
+}
+
+impl Default for FlyCamGestureSettings {
+    fn default() -> Self {
+        Self {
+            pinch_steps: 4096.,
+            rotate_scale: 1.,
+        }
+    }
+}
+
+/// Flies flycams with [`TrackpadGesture`]s: pinching dollies forwards and backwards, like
+/// scrolling, and twisting turns left and right. Both are scaled by
+/// [`InputDevice::Trackpad`] in the [`SensitivityProfiles`].
+///
+/// Add it alongside [`PlayerPlugin`](crate::PlayerPlugin) or
+/// [`NoCameraPlayerPlugin`](crate::NoCameraPlayerPlugin).
+pub struct FlyCamGesturesPlugin;
+impl Plugin for FlyCamGesturesPlugin {
+    fn build(&self, app: &mut App) {
+        let stage = FlyCamSchedule::stage(app);
+        app.add_event::<TrackpadGesture>()
+            .init_resource::<FlyCamGestureSettings>()
+            .add_system_to_stage(stage, gestures.before(crate::actions::apply_actions));
+    }
+}
+
+/// Turns trackpad gestures into scroll and turn actions
+fn gestures(
+    mut gestures: EventReader<TrackpadGesture>,
+    settings: Res<FlyCamGestureSettings>,
+    profiles: Res<SensitivityProfiles>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut actions: EventWriter<FlyCamInput>,
+) {
+    let blocked = blockers.blocked();
+    let sensitivity = profiles.get(InputDevice::Trackpad);
+    for gesture in gestures.iter() {
+        match *gesture {
+            TrackpadGesture::Pinch(magnification) if !blocked.scroll => {
+                let steps = magnification * settings.pinch_steps * sensitivity;
+                actions.send(FlyCamInput::all(FlyCamAction::Scroll(steps)));
+            }
+            TrackpadGesture::Rotate(radians) if !blocked.look => {
+                let yaw = -radians * settings.rotate_scale * sensitivity;
+                actions.send(FlyCamInput::all(FlyCamAction::StickLook(Vec2::X * yaw)));
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+
+use crate::{cursor_grabbed, FlyCamInputBlockers, MovementSettings};
+
+/// Whether the primary window has the cursor grabbed for mouse look, updated at the end of
+/// every frame. In browsers this follows pointer lock, which the browser can drop at any time,
+/// e.g. on Escape or when switching tabs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FlyCamCursorGrab {
+    grabbed: bool,
+    lost: bool,
+}
+
+impl FlyCamCursorGrab {
+    /// Whether the cursor is grabbed
+    pub fn grabbed(&self) -> bool {
+        self.grabbed
+    }
+
+    /// Whether the cursor was grabbed and has been let go since, e.g. to show a "click to
+    /// look around" prompt. Cleared once it's grabbed again.
+    pub fn lost(&self) -> bool {
+        self.lost
+    }
+}
+
+/// Sent when the cursor is grabbed or let go, whether by the flycam, the browser or the OS
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CursorGrabChanged {
+    pub grabbed: bool,
+}
+
+/// Keeps [`FlyCamCursorGrab`] up to date, and grabs the cursor again on a left click after
+/// it's been lost when [`MovementSettings::recapture_on_click`] is on
+pub(crate) fn track_cursor_grab(
+    mut windows: ResMut<Windows>,
+    buttons: Res<Input<MouseButton>>,
+    settings: Res<MovementSettings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut grab: ResMut<FlyCamCursorGrab>,
+    mut changes: EventWriter<CursorGrabChanged>,
+) {
+    let window = match windows.get_primary_mut() {
+        Some(window) => window,
+        None => return,
+    };
+    let grabbed = cursor_grabbed(window);
+    if grabbed != grab.grabbed {
+        *grab = FlyCamCursorGrab {
+            grabbed,
+            lost: !grabbed,
+        };
+        changes.send(CursorGrabChanged { grabbed });
+    }
+    // Browsers only hand out pointer lock just after the user does something, like clicking.
+    if grab.lost
+        && settings.recapture_on_click
+        && buttons.just_pressed(MouseButton::Left)
+        && !blockers.blocked().grab_toggle
+    {
+        window.set_cursor_lock_mode(true);
+        window.set_cursor_visibility(false);
+    }
+}
@@ -0,0 +1,195 @@
+use bevy::input::touch::{TouchInput, TouchPhase};
+use bevy::prelude::*;
+
+use crate::rotation::wrap_angle;
+use crate::{
+    FlyCam, FlyCamAction, FlyCamInput, FlyCamInputBlockers, FlyCamRotation, FlyCamSchedule,
+};
+
+/// Which way the device is facing, in the browser's earth frame: x east, y north and z up,
+/// with the device's screen facing along its own z and its back camera along its -z.
+///
+/// In browsers [`FlyCamGyroPlugin`] sends these itself from `deviceorientation` events.
+/// Elsewhere, send them from your platform's motion sensors.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DeviceOrientation(pub Quat);
+
+impl DeviceOrientation {
+    /// The orientation a browser reports as `alpha`, `beta` and `gamma`, in degrees
+    pub fn from_browser(alpha: f32, beta: f32, gamma: f32) -> Self {
+        Self(Quat::from_euler(
+            EulerRot::ZXY,
+            alpha.to_radians(),
+            beta.to_radians(),
+            gamma.to_radians(),
+        ))
+    }
+
+    /// Yaw and pitch of the way the back of the device points
+    fn aim(&self) -> Vec2 {
+        let forward = self.0 * -Vec3::Z;
+        Vec2::new(
+            (-forward.x).atan2(forward.y),
+            forward.z.clamp(-1., 1.).asin(),
+        )
+    }
+}
+
+/// Levels the flycams' view out, so the device's current pose looks straight ahead
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RecenterGyro;
+
+/// How turning the device turns the camera
+pub struct FlyCamGyroSettings {
+    /// How far the camera turns for each radian the device turns. 0 turns gyro aiming off.
+    pub sensitivity: f32,
+    /// Recenter when the touchscreen is tapped twice within this many seconds. 0 disables it.
+    pub double_tap_time: f32,
+}
+
+impl Default for FlyCamGyroSettings {
+    fn default() -> Self {
+        Self {
+            sensitivity: 1.,
+            double_tap_time: 0.3,
+        }
+    }
+}
+
+/// Aims flycams by turning the device, on top of mouse and touch look, for AR-style viewers:
+/// the camera turns as far as the device does between [`DeviceOrientation`]s. Tapping the
+/// screen twice, or sending [`RecenterGyro`], levels the view out again.
+///
+/// In browsers the orientation comes from `deviceorientation` events; Safari on iOS only
+/// sends those once the page has asked with `DeviceOrientationEvent.requestPermission()`.
+///
+/// Add it alongside [`PlayerPlugin`](crate::PlayerPlugin) or
+/// [`NoCameraPlayerPlugin`](crate::NoCameraPlayerPlugin).
+pub struct FlyCamGyroPlugin;
+impl Plugin for FlyCamGyroPlugin {
+    fn build(&self, app: &mut App) {
+        let stage = FlyCamSchedule::stage(app);
+        app.add_event::<DeviceOrientation>()
+            .add_event::<RecenterGyro>()
+            .init_resource::<FlyCamGyroSettings>()
+            .add_system_to_stage(stage, double_tap_recenter)
+            .add_system_to_stage(
+                stage,
+                gyro_look
+                    .after(double_tap_recenter)
+                    .before(crate::actions::apply_actions),
+            );
+        #[cfg(target_arch = "wasm32")]
+        browser::listen(app, stage);
+    }
+}
+
+/// Sends [`RecenterGyro`] on a double tap
+fn double_tap_recenter(
+    mut touches: EventReader<TouchInput>,
+    time: Res<Time>,
+    settings: Res<FlyCamGyroSettings>,
+    mut last_tap: Local<Option<f64>>,
+    mut recenter: EventWriter<RecenterGyro>,
+) {
+    let now = time.seconds_since_startup();
+    for touch in touches.iter() {
+        if touch.phase != TouchPhase::Started {
+            continue;
+        }
+        match *last_tap {
+            Some(last)
+                if settings.double_tap_time > 0.
+                    && now - last <= settings.double_tap_time as f64 =>
+            {
+                recenter.send(RecenterGyro);
+                *last_tap = None;
+            }
+            _ => *last_tap = Some(now),
+        }
+    }
+}
+
+/// Turns flycams by how far the device has turned since the last [`DeviceOrientation`]
+fn gyro_look(
+    mut orientations: EventReader<DeviceOrientation>,
+    mut recenter: EventReader<RecenterGyro>,
+    settings: Res<FlyCamGyroSettings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut last_aim: Local<Option<Vec2>>,
+    cameras: Query<(Entity, &FlyCamRotation), With<FlyCam>>,
+    mut actions: EventWriter<FlyCamInput>,
+) {
+    let mut turn = Vec2::ZERO;
+    for orientation in orientations.iter() {
+        let aim = orientation.aim();
+        if let Some(last) = last_aim.replace(aim) {
+            turn += Vec2::new(wrap_angle(aim.x - last.x), aim.y - last.y);
+        }
+    }
+    if blockers.blocked().look {
+        recenter.clear();
+        return;
+    }
+    let turn = turn * settings.sensitivity;
+    if turn != Vec2::ZERO {
+        actions.send(FlyCamInput::all(FlyCamAction::StickLook(turn)));
+    }
+    if recenter.iter().last().is_some() {
+        for (entity, rotation) in cameras.iter() {
+            actions.send(FlyCamInput::camera(
+                entity,
+                FlyCamAction::Rotate(Vec3::new(0., -rotation.pitch - turn.y, 0.)),
+            ));
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod browser {
+    use std::sync::{Arc, Mutex};
+
+    use bevy::ecs::schedule::StageLabelId;
+    use bevy::prelude::*;
+    use wasm_bindgen::JsCast;
+
+    use super::DeviceOrientation;
+
+    /// The latest orientation from the browser, waiting to be sent
+    #[derive(Default)]
+    struct BrowserOrientation(Arc<Mutex<Option<DeviceOrientation>>>);
+
+    pub(super) fn listen(app: &mut App, stage: StageLabelId) {
+        let latest = BrowserOrientation::default();
+        let shared = latest.0.clone();
+        let window = web_sys::window().expect("no global `window` exists");
+        gloo_events::EventListener::new(&window, "deviceorientation", move |event| {
+            let event = match event.dyn_ref::<web_sys::DeviceOrientationEvent>() {
+                Some(event) => event,
+                None => return,
+            };
+            if let (Some(alpha), Some(beta), Some(gamma)) =
+                (event.alpha(), event.beta(), event.gamma())
+            {
+                *shared.lock().unwrap() = Some(DeviceOrientation::from_browser(
+                    alpha as f32,
+                    beta as f32,
+                    gamma as f32,
+                ));
+            }
+        })
+        // It listens for as long as the page is open.
+        .forget();
+        app.insert_resource(latest)
+            .add_system_to_stage(stage, send_orientation.before(super::gyro_look));
+    }
+
+    fn send_orientation(
+        latest: Res<BrowserOrientation>,
+        mut orientations: EventWriter<DeviceOrientation>,
+    ) {
+        if let Some(orientation) = latest.0.lock().unwrap().take() {
+            orientations.send(orientation);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::{
+    cursor_grabbed, get_boost, FlyCam, FlyCamKeys, FlyCamMode, FlyCamRotation, FlyCamVelocity,
+    MovementSettings,
+};
+
+/// Settings for the debug overlay added by [`FlyCamHudPlugin`]
+pub struct FlyCamHudSettings {
+    /// Asset path of the font to draw the overlay with
+    pub font: String,
+    pub font_size: f32,
+    pub color: Color,
+    /// Keys that show and hide the overlay
+    pub toggle: Vec<KeyCode>,
+    /// Whether the overlay is shown when the game starts
+    pub visible: bool,
+}
+
+impl Default for FlyCamHudSettings {
+    fn default() -> Self {
+        Self {
+            font: "fonts/FiraSans-Bold.ttf".into(),
+            font_size: 18.,
+            color: Color::WHITE,
+            toggle: vec![KeyCode::F3],
+            visible: true,
+        }
+    }
+}
+
+/// Marks the overlay's text
+#[derive(Component)]
+struct FlyCamHud;
+
+fn setup_hud(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<FlyCamHudSettings>,
+) {
+    let style = TextStyle {
+        font: asset_server.load(settings.font.as_str()),
+        font_size: settings.font_size,
+        color: settings.color,
+    };
+    commands
+        .spawn_bundle(TextBundle {
+            visibility: Visibility {
+                is_visible: settings.visible,
+            },
+            ..TextBundle::from_section("", style).with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(5.),
+                    left: Val::Px(5.),
+                    ..default()
+                },
+                ..default()
+            })
+        })
+        .insert(FlyCamHud);
+}
+
+/// Toggles the overlay and fills it in from the first flycam
+fn update_hud(
+    keys: Res<FlyCamKeys>,
+    windows: Res<Windows>,
+    settings: Res<MovementSettings>,
+    hud_settings: Res<FlyCamHudSettings>,
+    cameras: Query<(&Transform, &FlyCamMode, &FlyCamRotation, &FlyCamVelocity), With<FlyCam>>,
+    mut hud: Query<(&mut Text, &mut Visibility), With<FlyCamHud>>,
+) {
+    for (mut text, mut visibility) in hud.iter_mut() {
+        if keys.any_just_pressed(hud_settings.toggle.iter().copied()) {
+            visibility.is_visible = !visibility.is_visible;
+        }
+        if !visibility.is_visible {
+            continue;
+        }
+        let (transform, mode, rotation, velocity) = match cameras.iter().next() {
+            Some(camera) => camera,
+            None => continue,
+        };
+        let grabbed = windows.get_primary().is_some_and(cursor_grabbed);
+        let position = transform.translation;
+        text.sections[0].value = format!(
+            "pos: {:.2} {:.2} {:.2}\nyaw: {:.1} pitch: {:.1}\nspeed: {:.2}\nboost: x{}\nmode: {:?}\ncursor: {}",
+            position.x,
+            position.y,
+            position.z,
+            rotation.yaw.to_degrees(),
+            rotation.pitch.to_degrees(),
+            velocity.0.length(),
+            get_boost(&keys, &settings),
+            mode,
+            if grabbed { "grabbed" } else { "free" },
+        );
+    }
+}
+
+/// Adds a debug overlay showing the flycam's position, rotation, speed, boost and cursor grab state.
+/// Add it alongside [`PlayerPlugin`](crate::PlayerPlugin) or [`NoCameraPlayerPlugin`](crate::NoCameraPlayerPlugin).
+pub struct FlyCamHudPlugin;
+impl Plugin for FlyCamHudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlyCamHudSettings>()
+            .add_startup_system(setup_hud)
+            .add_system(update_hud);
+    }
+}
@@ -0,0 +1,71 @@
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::input::touch::TouchInput;
+use bevy::prelude::*;
+
+use crate::{FlyCamGamepadSettings, FlyCamTime};
+
+/// How long it's been since anyone touched the keyboard, mouse, touchscreen or a gamepad,
+/// counting sticks once they're pushed past their deadzone, for the idle behaviours like
+/// [`FlyCamTurntable`](crate::FlyCamTurntable) and [`FlyCamScreensaver`](crate::FlyCamScreensaver)
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FlyCamIdle {
+    seconds: f32,
+    active: bool,
+}
+
+impl FlyCamIdle {
+    /// Seconds on the [`FlyCamTime`] clock since the last input
+    pub fn seconds(&self) -> f32 {
+        self.seconds
+    }
+
+    /// Whether there was input this frame
+    pub fn active(&self) -> bool {
+        self.active
+    }
+}
+
+/// Counts up the time since the last input
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn track_idle(
+    time: Res<FlyCamTime>,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<MouseButton>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    gamepad_settings: Res<FlyCamGamepadSettings>,
+    mut motion: EventReader<MouseMotion>,
+    mut wheel: EventReader<MouseWheel>,
+    mut touches: EventReader<TouchInput>,
+    mut idle: ResMut<FlyCamIdle>,
+) {
+    // Sticks rest a little off center, so only pushes the camera would notice count.
+    let sticks_pushed = gamepads.iter().any(|&gamepad| {
+        let stick = |x, y| {
+            let axis = |axis_type| {
+                axes.get(GamepadAxis::new(gamepad, axis_type))
+                    .unwrap_or_default()
+            };
+            Vec2::new(axis(x), axis(y)).length()
+        };
+        use GamepadAxisType::*;
+        stick(LeftStickX, LeftStickY) > gamepad_settings.move_deadzone
+            || stick(RightStickX, RightStickY) > gamepad_settings.look_deadzone
+    });
+    let active = keys.get_pressed().next().is_some()
+        || buttons.get_pressed().next().is_some()
+        || gamepad_buttons.get_pressed().next().is_some()
+        || sticks_pushed
+        || motion.iter().count() > 0
+        || wheel.iter().count() > 0
+        || touches.iter().count() > 0;
+    *idle = FlyCamIdle {
+        seconds: if active {
+            0.
+        } else {
+            idle.seconds + time.delta_seconds()
+        },
+        active,
+    };
+}
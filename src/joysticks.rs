@@ -0,0 +1,205 @@
+use bevy::input::touch::{TouchInput, TouchPhase};
+use bevy::prelude::*;
+
+use crate::actions::ExternalInput;
+use crate::gamepad::stick_response;
+use crate::touch::ClaimedTouches;
+use crate::{
+    FlyCam, FlyCamAction, FlyCamInput, FlyCamInputBlockers, FlyCamMode, FlyCamSchedule,
+    MovementSettings,
+};
+
+/// How the on-screen joystick added by [`FlyCamJoysticksPlugin`] looks and feels
+pub struct FlyCamJoystickSettings {
+    /// How far the knob moves from the middle of the stick, in pixels
+    pub radius: f32,
+    /// Gap between the stick and the bottom left corner of the window, in pixels
+    pub margin: f32,
+    /// How far the knob has to be pushed before the camera moves, from 0 to 1
+    pub deadzone: f32,
+    pub base_color: Color,
+    pub knob_color: Color,
+}
+
+impl Default for FlyCamJoystickSettings {
+    fn default() -> Self {
+        Self {
+            radius: 60.,
+            margin: 40.,
+            deadzone: 0.1,
+            base_color: Color::rgba(1., 1., 1., 0.15),
+            knob_color: Color::rgba(1., 1., 1., 0.4),
+        }
+    }
+}
+
+/// On-screen controls for touchscreens, so mobile and browser demos can be flown without a
+/// keyboard: a finger that comes down on the left half of the screen drives the joystick in
+/// the bottom left corner, which moves like a gamepad's left stick, and dragging anywhere
+/// else looks around by [`MovementSettings::touch_sensitivity`].
+///
+/// Add it alongside [`PlayerPlugin`](crate::PlayerPlugin) or
+/// [`NoCameraPlayerPlugin`](crate::NoCameraPlayerPlugin).
+pub struct FlyCamJoysticksPlugin;
+impl Plugin for FlyCamJoysticksPlugin {
+    fn build(&self, app: &mut App) {
+        let stage = FlyCamSchedule::stage(app);
+        app.init_resource::<FlyCamJoystickSettings>()
+            .init_resource::<VirtualStick>()
+            .add_startup_system(setup_joystick)
+            .add_system_to_stage(stage, grab_stick.before(crate::touch::touch_look))
+            .add_system_to_stage(
+                stage,
+                stick_move
+                    .after(grab_stick)
+                    .before(crate::actions::apply_actions),
+            )
+            .add_system_to_stage(stage, draw_joystick.after(grab_stick));
+    }
+}
+
+/// The finger driving the joystick, and how far it's pushed the knob, from -1 to 1 each way
+#[derive(Default)]
+struct VirtualStick {
+    finger: Option<u64>,
+    offset: Vec2,
+}
+
+#[derive(Component)]
+struct JoystickBase;
+
+#[derive(Component)]
+struct JoystickKnob;
+
+fn setup_joystick(mut commands: Commands, settings: Res<FlyCamJoystickSettings>) {
+    commands
+        .spawn_bundle(NodeBundle {
+            color: settings.base_color.into(),
+            ..default()
+        })
+        .insert(JoystickBase)
+        .with_children(|base| {
+            base.spawn_bundle(NodeBundle {
+                color: settings.knob_color.into(),
+                ..default()
+            })
+            .insert(JoystickKnob);
+        });
+}
+
+/// `position` of a touch measured from the bottom left of a window `height` pixels high.
+/// Touches are measured from the top everywhere but on mobile, where bevy flips them.
+fn from_bottom_left(position: Vec2, height: f32) -> Vec2 {
+    if cfg!(any(target_os = "android", target_os = "ios")) {
+        position
+    } else {
+        Vec2::new(position.x, height - position.y)
+    }
+}
+
+/// Catches fingers coming down on the left half of the screen for the joystick
+fn grab_stick(
+    mut touches: EventReader<TouchInput>,
+    windows: Res<Windows>,
+    settings: Res<FlyCamJoystickSettings>,
+    mut stick: ResMut<VirtualStick>,
+    mut claimed: ResMut<ClaimedTouches>,
+) {
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+    let center = Vec2::splat(settings.margin + settings.radius);
+    let offset = |position| {
+        let position = from_bottom_left(position, window.height());
+        ((position - center) / settings.radius.max(1.)).clamp_length_max(1.)
+    };
+    for touch in touches.iter() {
+        let held = stick.finger == Some(touch.id);
+        match touch.phase {
+            TouchPhase::Started
+                if stick.finger.is_none() && touch.position.x < window.width() / 2. =>
+            {
+                stick.finger = Some(touch.id);
+                stick.offset = offset(touch.position);
+                claimed.0.insert(touch.id);
+            }
+            TouchPhase::Moved if held => stick.offset = offset(touch.position),
+            TouchPhase::Ended | TouchPhase::Cancelled if held => {
+                *stick = VirtualStick::default();
+                claimed.0.remove(&touch.id);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Turns the joystick into movement actions
+#[allow(clippy::type_complexity)]
+fn stick_move(
+    stick: Res<VirtualStick>,
+    settings: Res<MovementSettings>,
+    joystick_settings: Res<FlyCamJoystickSettings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut actions: EventWriter<FlyCamInput>,
+    query: Query<(Entity, &FlyCamMode), (With<FlyCam>, Without<ExternalInput>)>,
+) {
+    if stick.finger.is_none() || blockers.blocked().movement || settings.stepping {
+        return;
+    }
+    let movement = stick_response(stick.offset, joystick_settings.deadzone, 1.);
+    if movement == Vec2::ZERO {
+        return;
+    }
+    for (entity, mode) in query.iter() {
+        if mode.uses_fly_controls() {
+            actions.send(FlyCamInput::camera(
+                entity,
+                FlyCamAction::MoveForward(movement.y),
+            ));
+            actions.send(FlyCamInput::camera(
+                entity,
+                FlyCamAction::MoveRight(movement.x),
+            ));
+        }
+    }
+}
+
+/// Lays out the joystick, with the knob following the finger
+fn draw_joystick(
+    stick: Res<VirtualStick>,
+    settings: Res<FlyCamJoystickSettings>,
+    mut bases: Query<&mut Style, (With<JoystickBase>, Without<JoystickKnob>)>,
+    mut knobs: Query<&mut Style, With<JoystickKnob>>,
+) {
+    if !stick.is_changed() && !settings.is_changed() {
+        return;
+    }
+    let radius = settings.radius;
+    for mut style in bases.iter_mut() {
+        *style = Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: Val::Px(settings.margin),
+                bottom: Val::Px(settings.margin),
+                ..default()
+            },
+            size: Size::new(Val::Px(radius * 2.), Val::Px(radius * 2.)),
+            ..default()
+        };
+    }
+    // The knob is half the stick's size, centred on the offset.
+    let corner = Vec2::splat(radius / 2.) + stick.offset * radius;
+    for mut style in knobs.iter_mut() {
+        *style = Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: Val::Px(corner.x),
+                bottom: Val::Px(corner.y),
+                ..default()
+            },
+            size: Size::new(Val::Px(radius), Val::Px(radius)),
+            ..default()
+        };
+    }
+}
@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use leafwing_input_manager::user_input::InputKind;
+
+use crate::actions::ExternalInput;
+use crate::{
+    FlyCam, FlyCamAction, FlyCamInput, FlyCamInputBlockers, FlyCamMode, FlyCamSchedule,
+    KeyBindings, MovementSettings,
+};
+
+/// The flycam's controls as a [leafwing-input-manager](leafwing_input_manager) action.
+///
+/// Give a flycam an `ActionState<FlyCamLeafwingAction>` (and an `InputMap` to fill it, e.g.
+/// with an `InputManagerBundle`) and it's driven by that instead of the keyboard. Mouse look
+/// and scrolling still work as usual.
+#[derive(Actionlike, Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FlyCamLeafwingAction {
+    MoveForward,
+    MoveBackward,
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    YawLeft,
+    YawRight,
+    PitchUp,
+    PitchDown,
+    RollLeft,
+    RollRight,
+    /// Look around, from a stick or other dual axis input, in the same units as mouse motion
+    Look,
+    /// Speed up by the multiplier of the first of [`MovementSettings::boost_tiers`]
+    Boost,
+}
+
+impl FlyCamLeafwingAction {
+    /// An input map with the same keys as the default [`KeyBindings`]
+    pub fn default_input_map() -> InputMap<Self> {
+        let bindings = KeyBindings::default();
+        let mut map = InputMap::default();
+        for (keys, action) in [
+            (bindings.move_forward, Self::MoveForward),
+            (bindings.move_backward, Self::MoveBackward),
+            (bindings.move_left, Self::MoveLeft),
+            (bindings.move_right, Self::MoveRight),
+            (bindings.move_ascend, Self::MoveUp),
+            (bindings.move_descend, Self::MoveDown),
+            (bindings.yaw_left, Self::YawLeft),
+            (bindings.yaw_right, Self::YawRight),
+            (bindings.pitch_up, Self::PitchUp),
+            (bindings.pitch_down, Self::PitchDown),
+            (bindings.roll_left, Self::RollLeft),
+            (bindings.roll_right, Self::RollRight),
+        ] {
+            for key in keys {
+                map.insert(InputKind::Keyboard(key), action);
+            }
+        }
+        map.insert(KeyCode::LShift, Self::Boost);
+        map
+    }
+}
+
+/// Drives flycams from `ActionState<FlyCamLeafwingAction>` instead of the keyboard.
+/// Adds leafwing's `InputManagerPlugin` for [`FlyCamLeafwingAction`] too.
+pub struct FlyCamLeafwingPlugin;
+
+impl Plugin for FlyCamLeafwingPlugin {
+    fn build(&self, app: &mut App) {
+        let stage = FlyCamSchedule::stage(app);
+        app.add_plugin(InputManagerPlugin::<FlyCamLeafwingAction>::default())
+            .add_system_to_stage(stage, mark_external_input)
+            .add_system_to_stage(
+                stage,
+                leafwing_actions.before(crate::actions::apply_actions),
+            );
+    }
+}
+
+/// Stops the keyboard driving flycams that have their own action state
+#[allow(clippy::type_complexity)]
+fn mark_external_input(
+    mut commands: Commands,
+    query: Query<
+        Entity,
+        (
+            With<FlyCam>,
+            With<ActionState<FlyCamLeafwingAction>>,
+            Without<ExternalInput>,
+        ),
+    >,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).insert(ExternalInput);
+    }
+}
+
+/// Turns each flycam's action state into [`FlyCamAction`]s
+fn leafwing_actions(
+    settings: Res<MovementSettings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut actions: EventWriter<FlyCamInput>,
+    query: Query<(Entity, &ActionState<FlyCamLeafwingAction>, &FlyCamMode), With<FlyCam>>,
+) {
+    use FlyCamLeafwingAction as A;
+
+    let blocked = blockers.blocked();
+
+    for (entity, state, mode) in query.iter() {
+        if !mode.uses_fly_controls() {
+            continue;
+        }
+        let axis =
+            |positive, negative| state.clamped_value(positive) - state.clamped_value(negative);
+        let mut send = |action: FlyCamAction| actions.send(FlyCamInput::camera(entity, action));
+
+        for (action, amount) in [
+            (
+                FlyCamAction::MoveForward as fn(f32) -> FlyCamAction,
+                axis(A::MoveForward, A::MoveBackward),
+            ),
+            (FlyCamAction::MoveRight, axis(A::MoveRight, A::MoveLeft)),
+            (FlyCamAction::MoveUp, axis(A::MoveUp, A::MoveDown)),
+            (FlyCamAction::Yaw, axis(A::YawLeft, A::YawRight)),
+            (FlyCamAction::Pitch, axis(A::PitchUp, A::PitchDown)),
+            (FlyCamAction::Roll, axis(A::RollLeft, A::RollRight)),
+        ] {
+            if amount != 0. && !blocked.movement {
+                send(action(amount));
+            }
+        }
+        if let Some(look) = state.axis_pair(A::Look) {
+            if look.xy() != Vec2::ZERO && !blocked.look {
+                send(FlyCamAction::Look(look.xy()));
+            }
+        }
+        if state.pressed(A::Boost) && !blocked.movement {
+            if let Some(tier) = settings.boost_tiers.first() {
+                send(FlyCamAction::Boost(tier.multiplier));
+            }
+        }
+    }
+}
@@ -1,45 +1,760 @@
-use bevy::ecs::event::{Events, ManualEventReader};
-use bevy::input::mouse::{MouseMotion, MouseWheel};
+use std::collections::HashMap;
+
+use bevy::ecs::schedule::StageLabelId;
+use bevy::input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::input::InputSystem;
 use bevy::prelude::*;
+use bevy::render::camera::Projection;
+use bevy::time::{FixedTimestep, FixedTimesteps};
+
+mod actions;
+mod auto_speed;
+mod blockers;
+mod clock;
+#[cfg(feature = "ron")]
+mod config;
+mod diagnostics;
+mod dolly;
+mod drone;
+#[cfg(feature = "egui")]
+mod egui_panel;
+mod fluid;
+mod flycam_2d;
+mod focus;
+mod frustum;
+mod gamepad;
+#[cfg(feature = "gestures")]
+mod gestures;
+mod grab;
+#[cfg(feature = "gyro")]
+mod gyro;
+#[cfg(feature = "hud")]
+mod hud;
+mod idle;
+#[cfg(feature = "virtual-joysticks")]
+mod joysticks;
+#[cfg(feature = "leafwing")]
+mod leafwing;
+#[cfg(feature = "debug-lines")]
+mod lines;
+mod minimap;
+mod mode;
+mod mouse_only;
+mod net;
+mod orbit;
+mod origin;
+mod path_playback;
+mod paths;
+mod photo;
+#[cfg(feature = "picking")]
+mod picking;
+mod planet;
+mod pose;
+mod precise;
+/// The plugins, settings, key bindings and modes most apps need, for
+/// `use bevy_flycam::prelude::*;`
+pub mod prelude;
+mod profiles;
+mod ray;
+mod replay;
+mod rig;
+mod rotation;
+mod rts;
+mod screensaver;
+mod sensitivity;
+mod session;
+#[cfg(feature = "spawn-camera")]
+mod spawn;
+mod spectator;
+mod sprint;
+#[cfg(feature = "startup-overrides")]
+mod startup;
+mod target;
+mod terrain;
+#[cfg(feature = "test-harness")]
+mod testing;
+mod toggles;
+mod touch;
+mod tour;
+mod turntable;
+mod validate;
+mod vehicle;
+mod walk;
+mod waypoints;
+pub use actions::{FlyCamAction, FlyCamInput};
+pub use auto_speed::{DepthSource, FlyCamAutoSpeed};
+pub use blockers::{BlockedInput, FlyCamInputBlockers};
+pub use clock::{FlyCamTime, FlyCamTimeSource};
+#[cfg(feature = "ron")]
+pub use config::{FlyCamConfig, FlyCamConfigPlugin};
+pub use diagnostics::FlyCamDiagnosticsPlugin;
+pub use dolly::FlyCamDollyZoom;
+pub use drone::{DroneSettings, DroneState};
+#[cfg(feature = "egui")]
+pub use egui_panel::{FlyCamEguiPlugin, FlyCamEguiSettings};
+pub use fluid::{FlyCamFluid, FlyCamSwimming};
+pub use flycam_2d::{FlyCam2d, FlyCam2dPlugin, FlyCam2dSettings, NoCameraFlyCam2dPlugin};
+pub use focus::{FlyCamAutofocus, FocusDistance};
+pub use frustum::{FlyCamFrozenFrustum, FlyCamFrustumPlugin, FlyCamFrustumSettings};
+pub use gamepad::FlyCamGamepadSettings;
+#[cfg(feature = "gestures")]
+pub use gestures::{FlyCamGestureSettings, FlyCamGesturesPlugin, TrackpadGesture};
+pub use grab::{CursorGrabChanged, FlyCamCursorGrab};
+#[cfg(feature = "gyro")]
+pub use gyro::{DeviceOrientation, FlyCamGyroPlugin, FlyCamGyroSettings, RecenterGyro};
+#[cfg(feature = "hud")]
+pub use hud::{FlyCamHudPlugin, FlyCamHudSettings};
+pub use idle::FlyCamIdle;
+#[cfg(feature = "virtual-joysticks")]
+pub use joysticks::{FlyCamJoystickSettings, FlyCamJoysticksPlugin};
+#[cfg(feature = "leafwing")]
+pub use leafwing::{FlyCamLeafwingAction, FlyCamLeafwingPlugin};
+pub use minimap::{FlyCamMinimap, FlyCamMinimapPlugin, FlyCamMinimapSettings, MinimapCorner};
+pub use mode::{FlyCamMode, ModeChanged, SetFlyCamMode};
+pub use mouse_only::MouseOnlySettings;
+pub use net::{FlyCamNetProxy, FlyCamNetState};
+pub use orbit::{OrbitSettings, OrbitState};
+pub use origin::{
+    FlyCamOrigin, FlyCamOriginPlugin, FlyCamOriginSettings, FlyCamShiftWithOrigin, OriginShift,
+};
+pub use path_playback::{FlyCamPathPlayback, FlyCamPathPlaybackSettings, PathPlaybackFinished};
+pub use paths::{FlyCamPaths, FlyCamPathsPlugin, FlyCamPathsSettings};
+pub use photo::{FlyCamPhotoMode, FlyCamPhotoPlugin, FlyCamPhotoSettings, PhotoTaken};
+#[cfg(feature = "picking")]
+pub use picking::FlyCamPickingPlugin;
+pub use planet::PlanetSettings;
+pub use pose::{decode_pose, encode_pose};
+#[cfg(target_arch = "wasm32")]
+pub use pose::{pose_from_url, set_url_pose};
+pub use precise::FlyCamPrecisePosition;
+pub use profiles::{FlyCamProfile, FlyCamProfiles};
+pub use ray::{FlyCamRay, FlyCamRays};
+pub use replay::{FlyCamPlayback, FlyCamRecorder, FlyCamRecording, RecordedFrame, RecordedMotion};
+pub use rig::FlyCamRig;
+pub use rotation::FlyCamRotation;
+pub use rts::{RtsSettings, RtsState};
+pub use screensaver::FlyCamScreensaver;
+pub use sensitivity::{InputDevice, SensitivityProfiles};
+pub use session::{FlyCamSession, FlyCamSessions};
+#[cfg(feature = "spawn-camera")]
+pub use spawn::{CustomizeCamera, FlyCamSpawnSettings, PlayerPlugin};
+pub use spectator::{FlyCamSpectator, Spectatable};
+#[cfg(feature = "startup-overrides")]
+pub use startup::{FlyCamStartup, POSE_VAR, SPEED_VAR, STARTUP_FILE};
+pub use target::{FlyCamTarget, FlyCamVelocity};
+pub use terrain::{HeightSource, TerrainSettings};
+#[cfg(feature = "test-harness")]
+pub use testing::FlyCamTestApp;
+pub use toggles::FlyCamKeys;
+pub use tour::{FlyCamTour, TourCommand, TourControl, TourStop, TourStopLeft, TourStopReached};
+pub use turntable::FlyCamTurntable;
+pub use validate::{FlyCamPathValidator, PathHit, PathReport, SweepSource};
+pub use vehicle::{VehicleSettings, VehicleState};
+pub use walk::{WalkSettings, WalkState};
+pub use waypoints::{
+    FlyCamWaypoint, FlyCamWaypointEditor, FlyCamWaypointEditorPlugin, FlyCamWaypointEditorSettings,
+    FlyCamWaypoints, WaypointEdit,
+};
 
-/// Keeps track of mouse motion events, pitch, and yaw
+use bevy::transform::TransformSystem;
+
+/// Input the flycam keeps track of between frames
 #[derive(Default)]
 struct InputState {
-    reader_motion: ManualEventReader<MouseMotion>,
+    /// How far into the hold-to-zoom we are, from 0 (not zoomed) to 1 (fully zoomed)
+    zoom: f32,
+    /// Filtered mouse motion when look smoothing is on
+    smoothed_motion: Vec2,
+    /// Keep moving forward without holding the forward key
+    auto_forward: bool,
+    /// Seconds the camera has been moving forward for, for cruising
+    forward_held: f32,
 }
 
 /// Mouse sensitivity and movement speed
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct MovementSettings {
     pub sensitivity: f32,
     pub speed: f32,
 
-    /// How many times faster to move with shift held down?
-    pub boost: f32,
+    /// How many times faster (or slower) to move while keys are held, e.g. shift to run.
+    /// When several tiers match, the one with the most keys wins.
+    pub boost_tiers: Vec<BoostTier>,
+    /// Fastest the camera can move by itself, from keys, scrolling, gamepads or
+    /// [`FlyCamAction::Translate`], in units per second, however much it's boosted. Keeps it
+    /// from ending up so far out that f32 positions fall apart. 0 for no limit.
+    pub max_speed: f32,
+
+    /// Vertical field of view of the flycam's perspective projection, in radians
+    pub fov: f32,
+    /// Near clipping plane distance
+    pub near: f32,
+    /// Far clipping plane distance
+    pub far: f32,
+
+    /// Field of view while the zoom key is held, in radians
+    pub zoom_fov: f32,
+    /// Seconds it takes to zoom fully in or out
+    pub zoom_time: f32,
+    /// Turn slower while zoomed in, by how much narrower the field of view is than `fov`, so
+    /// aiming doesn't get twitchy. Off, every look input turns the same however far zoomed in.
+    pub fov_compensated_look: bool,
+
+    /// Time constant in seconds for smoothing mouse look. 0 uses the raw mouse motion.
+    pub look_smoothing: f32,
+    /// Mouse acceleration: how much faster the camera turns the faster the mouse moves past
+    /// `mouse_acceleration_threshold`, with the motion scaled by
+    /// `(speed / mouse_acceleration_threshold).powf(mouse_acceleration - 1.)`. 1 keeps the raw,
+    /// linear motion.
+    pub mouse_acceleration: f32,
+    /// Mouse speed in pixels per second that mouse acceleration starts at
+    pub mouse_acceleration_threshold: f32,
+    /// How far dragging on a touchscreen turns the camera, in radians per pixel. Separate from
+    /// `sensitivity`, since touches move much further than the mouse does for the same turn.
+    pub touch_sensitivity: f32,
+    /// How fast the [`KeyBindings`] look and tilt keys turn the camera, in degrees per second
+    pub keyboard_look_speed: f32,
+    /// How far scrolling sideways, on trackpads and tilting mouse wheels, strafes the camera,
+    /// like `sensitivity` does for scrolling forwards. 0 ignores sideways scrolling.
+    pub horizontal_scroll_sensitivity: f32,
+    /// Scales scrolling in browsers, where each browser's wheel notch is first evened out to
+    /// one line of a desktop wheel
+    pub web_scroll_sensitivity: f32,
+
+    /// Time constant in seconds for the camera position to catch up with its [`FlyCamTarget`].
+    /// 0 follows it exactly.
+    pub translation_smoothing: f32,
+    /// Time constant in seconds for the camera rotation to catch up with its [`FlyCamTarget`].
+    /// 0 follows it exactly. On its own it gives the look lag of a heavy film camera, easing
+    /// the view after the mouse while movement stays snappy.
+    pub rotation_smoothing: f32,
+
+    /// Speeds the speed up / speed down keys step through, slowest first
+    pub speed_presets: Vec<f32>,
+
+    /// How much each press of the sensitivity keys multiplies or divides the sensitivity by
+    pub sensitivity_step: f32,
+    /// Lowest sensitivity the sensitivity keys go down to
+    pub min_sensitivity: f32,
+    /// Highest sensitivity the sensitivity keys go up to
+    pub max_sensitivity: f32,
+
+    /// Move and turn in fixed steps, once per key press, instead of continuously while held
+    pub stepping: bool,
+    /// How far each movement key press moves the camera when `stepping`
+    pub step_distance: f32,
+    /// How far each rotation key press turns the camera when `stepping`, in radians
+    pub step_angle: f32,
+
+    /// Size of the grid cells the camera position snaps to. 0 disables snapping.
+    pub grid_snap: f32,
+
+    /// Keep flying on a plane, with no movement along `plane_lock_axis`
+    pub plane_lock: bool,
+    /// The axis movement is blocked along while `plane_lock` is on
+    pub plane_lock_axis: Vec3,
+
+    /// Ascend and descend along the camera's up instead of the world's
+    pub vertical_along_view: bool,
+    /// Move exactly where the camera looks, including up and down, instead of level with the
+    /// ground. Strafing follows the camera's right too, so it tilts with any roll.
+    pub fly_along_look: bool,
+
+    /// Most that holding forward for a while speeds the camera up by, as a multiple of
+    /// `speed`, for crossing big worlds. 1 turns cruising off.
+    pub cruise_multiplier: f32,
+    /// Seconds forward has to be held before cruising starts speeding up
+    pub cruise_delay: f32,
+    /// Seconds cruising takes to go from `speed` up to `cruise_multiplier` times it
+    pub cruise_ramp_time: f32,
+
+    /// Longest gap in seconds between two taps of forward that starts sprinting, Minecraft
+    /// style. The sprint lasts until forward is let go. 0 turns it off.
+    pub double_tap_sprint: f32,
+    /// How many times faster sprinting moves
+    pub sprint_multiplier: f32,
+    /// Make the keys of the `boost_tiers` toggles instead of holds, for players who can't hold
+    /// modifiers down
+    pub toggle_boost: bool,
+    /// Make the crouch keys toggles instead of holds while walking. Flying, they still descend
+    /// only while held.
+    pub toggle_crouch: bool,
+
+    /// Seconds it takes to blend from one mode's camera pose to the next when switching modes.
+    /// 0 snaps straight over.
+    pub mode_blend_time: f32,
+
+    /// Which way is up in the world, for levelling movement and turning. Set it to `Vec3::Z`
+    /// for Z-up scenes. Only [`FlyCamMode::Fly`] and [`FlyCamMode::Spectate`] fully follow it;
+    /// the walk, terrain, RTS, drone, vehicle and orbit modes still treat world Y as up.
+    pub up_axis: Vec3,
+
+    /// Largest angle in radians the camera rolls into yaw turns, like an aircraft. 0 disables
+    /// banking. The roll is only visual and doesn't change [`FlyCamRotation`].
+    pub bank_angle: f32,
+    /// Time constant in seconds for the bank to follow the turn rate and level out again
+    pub bank_return: f32,
+
+    /// Which clock moves the camera. [`FlyCamTimeSource::Real`] keeps it flying while the
+    /// game is paused, for a debug camera.
+    pub time_source: FlyCamTimeSource,
+    /// Longest a frame can count as, in seconds, so the frame after a long hitch (loading
+    /// assets, dragging the window) doesn't launch the camera across the map. 0 for no limit.
+    pub max_delta: f32,
+
+    /// Grab the cursor again with a left click after it's been let go, e.g. when the browser
+    /// drops pointer lock on Escape, so there's no need for a button to get back to looking
+    pub recapture_on_click: bool,
+}
+
+/// A speed multiplier that applies while all of its keys are held
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoostTier {
+    pub keys: Vec<KeyCode>,
+    pub multiplier: f32,
+}
+
+impl BoostTier {
+    pub fn new(keys: impl IntoIterator<Item = KeyCode>, multiplier: f32) -> Self {
+        Self {
+            keys: keys.into_iter().collect(),
+            multiplier,
+        }
+    }
+}
+
+impl MovementSettings {
+    /// The field of view `zoom` of the way (0 to 1) from `fov` to `zoom_fov`
+    fn zoomed_fov(&self, zoom: f32) -> f32 {
+        self.fov + (self.zoom_fov - self.fov) * zoom
+    }
+
+    /// How much look input is scaled by `zoom` of the way (0 to 1) into the zoom, see
+    /// `fov_compensated_look`
+    fn zoom_look_scale(&self, zoom: f32) -> f32 {
+        if self.fov_compensated_look && self.fov > 0. {
+            self.zoomed_fov(zoom) / self.fov
+        } else {
+            1.
+        }
+    }
+
+    /// Mouse `delta` over `seconds`, sped up by mouse acceleration
+    fn accelerate_mouse(&self, delta: Vec2, seconds: f32) -> Vec2 {
+        if self.mouse_acceleration == 1. || self.mouse_acceleration_threshold <= 0. || seconds <= 0.
+        {
+            return delta;
+        }
+        let speed = delta.length() / seconds;
+        if speed <= self.mouse_acceleration_threshold {
+            return delta;
+        }
+        delta * (speed / self.mouse_acceleration_threshold).powf(self.mouse_acceleration - 1.)
+    }
+
+    /// How much cruising speeds the camera up after holding forward for `held` seconds
+    fn cruise_boost(&self, held: f32) -> f32 {
+        let ramp = if self.cruise_ramp_time > 0. {
+            ((held - self.cruise_delay) / self.cruise_ramp_time).clamp(0., 1.)
+        } else {
+            (held >= self.cruise_delay) as u8 as f32
+        };
+        1. + (self.cruise_multiplier - 1.) * ramp
+    }
+
+    /// Removes the part of `movement` along the locked axis while `plane_lock` is on
+    fn lock_to_plane(&self, movement: Vec3) -> Vec3 {
+        if self.plane_lock {
+            let axis = self.plane_lock_axis.normalize_or_zero();
+            movement - axis * movement.dot(axis)
+        } else {
+            movement
+        }
+    }
 }
 
 impl Default for MovementSettings {
     fn default() -> Self {
+        let projection = PerspectiveProjection::default();
         Self {
             sensitivity: 0.00012,
             speed: 12.,
-            boost: 4.,
+            boost_tiers: vec![
+                BoostTier::new([KeyCode::LShift], 4.),
+                BoostTier::new([KeyCode::LShift, KeyCode::LControl], 16.),
+                BoostTier::new([KeyCode::LAlt], 0.25),
+                BoostTier::new([KeyCode::O], 0.25), // slow motion mode
+            ],
+            max_speed: 10_000.,
+            fov: projection.fov,
+            near: projection.near,
+            far: projection.far,
+            zoom_fov: projection.fov / 4.,
+            zoom_time: 0.2,
+            fov_compensated_look: true,
+            look_smoothing: 0.,
+            mouse_acceleration: 1.,
+            mouse_acceleration_threshold: 500.,
+            touch_sensitivity: 0.005,
+            keyboard_look_speed: 90.,
+            horizontal_scroll_sensitivity: 0.,
+            web_scroll_sensitivity: 1.,
+            translation_smoothing: 0.,
+            rotation_smoothing: 0.,
+            speed_presets: vec![1., 3., 12., 40., 120.],
+            sensitivity_step: 1.2,
+            min_sensitivity: 0.00001,
+            max_sensitivity: 0.001,
+            stepping: false,
+            step_distance: 1.,
+            step_angle: 15f32.to_radians(),
+            grid_snap: 0.,
+            plane_lock: false,
+            plane_lock_axis: Vec3::Y,
+            vertical_along_view: false,
+            fly_along_look: false,
+            cruise_multiplier: 1.,
+            cruise_delay: 2.,
+            cruise_ramp_time: 4.,
+            double_tap_sprint: 0.,
+            sprint_multiplier: 4.,
+            toggle_boost: false,
+            toggle_crouch: false,
+            mode_blend_time: 0.4,
+            up_axis: Vec3::Y,
+            bank_angle: 0.,
+            bank_return: 0.3,
+            time_source: FlyCamTimeSource::Game,
+            max_delta: 1.,
+            recapture_on_click: false,
         }
     }
 }
 
+/// Which keys drive which movement. Each action can be bound to several keys.
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct KeyBindings {
+    pub move_forward: Vec<KeyCode>,
+    pub move_backward: Vec<KeyCode>,
+    pub move_left: Vec<KeyCode>,
+    pub move_right: Vec<KeyCode>,
+    pub move_ascend: Vec<KeyCode>,
+    pub move_descend: Vec<KeyCode>,
+    pub yaw_left: Vec<KeyCode>,
+    pub yaw_right: Vec<KeyCode>,
+    pub pitch_up: Vec<KeyCode>,
+    pub pitch_down: Vec<KeyCode>,
+    pub roll_left: Vec<KeyCode>,
+    pub roll_right: Vec<KeyCode>,
+    /// Turn left at [`MovementSettings::keyboard_look_speed`], instead of with the mouse.
+    /// Unbound by default; [`KeyBindings::arrow_look`] binds the arrow keys.
+    pub look_left: Vec<KeyCode>,
+    /// Turn right, like `look_left`
+    pub look_right: Vec<KeyCode>,
+    /// Look up, like `look_left`
+    pub look_up: Vec<KeyCode>,
+    /// Look down, like `look_left`
+    pub look_down: Vec<KeyCode>,
+    /// Tilt the view anticlockwise by [`FlyCamRotation::roll_offset`], for a dutch angle, at
+    /// `keyboard_look_speed`. Unbound by default.
+    pub tilt_left: Vec<KeyCode>,
+    /// Tilt the view clockwise, like `tilt_left`
+    pub tilt_right: Vec<KeyCode>,
+    pub toggle_grab_cursor: Vec<KeyCode>,
+    /// Hold to zoom in, like looking through binoculars
+    pub zoom: Vec<KeyCode>,
+    /// Log the camera transform as Rust code
+    pub copy_pose: Vec<KeyCode>,
+    /// Switch to the next of the [`FlyCamProfiles`]
+    pub next_profile: Vec<KeyCode>,
+    /// Step up to the next faster of the speed presets
+    pub speed_up: Vec<KeyCode>,
+    /// Step down to the next slower of the speed presets
+    pub speed_down: Vec<KeyCode>,
+    /// Make mouse look more sensitive
+    pub sensitivity_up: Vec<KeyCode>,
+    /// Make mouse look less sensitive
+    pub sensitivity_down: Vec<KeyCode>,
+    /// Keep flying forward until this is pressed again or the backward key is pressed
+    pub auto_forward: Vec<KeyCode>,
+    /// Turn [`MovementSettings::plane_lock`] on and off
+    pub toggle_plane_lock: Vec<KeyCode>,
+    /// Spectate the next [`Spectatable`], going back to free flight after the last one
+    pub spectate_next: Vec<KeyCode>,
+    /// Spectate the previous [`Spectatable`], going back to free flight before the first one
+    pub spectate_previous: Vec<KeyCode>,
+    /// Jump in [`FlyCamMode::Walk`], see [`WalkSettings`]
+    pub jump: Vec<KeyCode>,
+    /// Crouch while held in [`FlyCamMode::Walk`]
+    pub crouch: Vec<KeyCode>,
+    /// Keys that toggle instead of being held: press once to hold them down, and again to let
+    /// go. Any key the flycam holds for, e.g. movement or zoom, works. See [`FlyCamKeys`].
+    pub toggle_keys: Vec<KeyCode>,
+
+    /// Bindings to use instead of these while a flycam is in a particular mode, e.g. other
+    /// movement keys in [`FlyCamMode::Orbit`]. Overlays cover the keys that move and turn the
+    /// camera; the rest always come from the base bindings.
+    pub mode_overlays: HashMap<FlyCamMode, KeyBindings>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            move_forward: vec![KeyCode::W, KeyCode::Up],
+            move_backward: vec![KeyCode::S, KeyCode::Down],
+            move_left: vec![KeyCode::A, KeyCode::Left],
+            move_right: vec![KeyCode::D, KeyCode::Right],
+            move_ascend: vec![KeyCode::Space, KeyCode::Period],
+            // Note: bevy 0.7 bug: if you press LShift and then Comma no additional key seems to be pressed
+            move_descend: vec![KeyCode::RShift, KeyCode::Comma],
+            yaw_left: vec![KeyCode::Q],
+            yaw_right: vec![KeyCode::E],
+            pitch_up: vec![KeyCode::LBracket],
+            pitch_down: vec![KeyCode::RBracket],
+            roll_left: vec![KeyCode::Z],
+            roll_right: vec![KeyCode::X],
+            look_left: Vec::new(),
+            look_right: Vec::new(),
+            look_up: Vec::new(),
+            look_down: Vec::new(),
+            tilt_left: Vec::new(),
+            tilt_right: Vec::new(),
+            toggle_grab_cursor: vec![KeyCode::Escape],
+            zoom: vec![KeyCode::C],
+            copy_pose: vec![KeyCode::P],
+            next_profile: vec![KeyCode::F6],
+            speed_up: vec![KeyCode::PageUp],
+            speed_down: vec![KeyCode::PageDown],
+            sensitivity_up: vec![KeyCode::Equals, KeyCode::NumpadAdd],
+            sensitivity_down: vec![KeyCode::Minus, KeyCode::NumpadSubtract],
+            auto_forward: vec![KeyCode::F],
+            toggle_plane_lock: vec![KeyCode::L],
+            spectate_next: vec![KeyCode::Tab],
+            spectate_previous: vec![KeyCode::Back],
+            // Walking can't fly up or down, so these share the ascend and descend keys.
+            jump: vec![KeyCode::Space, KeyCode::Period],
+            crouch: vec![KeyCode::RShift, KeyCode::Comma],
+            toggle_keys: Vec::new(),
+            mode_overlays: HashMap::new(),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Bindings for flying with the right hand on the keyboard and the left on the mouse: IJKL
+    /// move, U and O turn and space ascends, with the rest moved within reach. Used by the
+    /// "one-handed" entry in [`FlyCamProfiles`].
+    pub fn one_handed() -> Self {
+        Self {
+            move_forward: vec![KeyCode::I],
+            move_backward: vec![KeyCode::K],
+            move_left: vec![KeyCode::J],
+            move_right: vec![KeyCode::L],
+            move_ascend: vec![KeyCode::Space],
+            move_descend: vec![KeyCode::Semicolon],
+            yaw_left: vec![KeyCode::U],
+            yaw_right: vec![KeyCode::O],
+            roll_left: vec![KeyCode::M],
+            roll_right: vec![KeyCode::Comma],
+            zoom: vec![KeyCode::Period],
+            auto_forward: vec![KeyCode::Y],
+            toggle_plane_lock: vec![KeyCode::Backslash],
+            jump: vec![KeyCode::Space],
+            // Its own key, since the profile latches crouching
+            crouch: vec![KeyCode::Slash],
+            ..default()
+        }
+    }
+
+    /// These bindings with the arrow keys looking around instead of moving, for flying without
+    /// a mouse
+    pub fn arrow_look(mut self) -> Self {
+        let arrows = [KeyCode::Left, KeyCode::Right, KeyCode::Up, KeyCode::Down];
+        for binding in [
+            &mut self.move_forward,
+            &mut self.move_backward,
+            &mut self.move_left,
+            &mut self.move_right,
+        ] {
+            binding.retain(|key| !arrows.contains(key));
+        }
+        self.look_left = vec![KeyCode::Left];
+        self.look_right = vec![KeyCode::Right];
+        self.look_up = vec![KeyCode::Up];
+        self.look_down = vec![KeyCode::Down];
+        self
+    }
+
+    /// The bindings that apply in `mode`: its overlay if it has one, otherwise these
+    pub fn for_mode(&self, mode: FlyCamMode) -> &KeyBindings {
+        self.mode_overlays.get(&mode).unwrap_or(self)
+    }
+
+    /// Every action's name together with its keys
+    #[cfg_attr(not(feature = "egui"), allow(dead_code))]
+    pub(crate) fn actions_mut(&mut self) -> [(&'static str, &mut Vec<KeyCode>); 32] {
+        [
+            ("move forward", &mut self.move_forward),
+            ("move backward", &mut self.move_backward),
+            ("move left", &mut self.move_left),
+            ("move right", &mut self.move_right),
+            ("move ascend", &mut self.move_ascend),
+            ("move descend", &mut self.move_descend),
+            ("yaw left", &mut self.yaw_left),
+            ("yaw right", &mut self.yaw_right),
+            ("pitch up", &mut self.pitch_up),
+            ("pitch down", &mut self.pitch_down),
+            ("roll left", &mut self.roll_left),
+            ("roll right", &mut self.roll_right),
+            ("look left", &mut self.look_left),
+            ("look right", &mut self.look_right),
+            ("look up", &mut self.look_up),
+            ("look down", &mut self.look_down),
+            ("tilt left", &mut self.tilt_left),
+            ("tilt right", &mut self.tilt_right),
+            ("toggle grab cursor", &mut self.toggle_grab_cursor),
+            ("zoom", &mut self.zoom),
+            ("copy pose", &mut self.copy_pose),
+            ("next profile", &mut self.next_profile),
+            ("speed up", &mut self.speed_up),
+            ("speed down", &mut self.speed_down),
+            ("sensitivity up", &mut self.sensitivity_up),
+            ("sensitivity down", &mut self.sensitivity_down),
+            ("auto forward", &mut self.auto_forward),
+            ("toggle plane lock", &mut self.toggle_plane_lock),
+            ("spectate next", &mut self.spectate_next),
+            ("spectate previous", &mut self.spectate_previous),
+            ("jump", &mut self.jump),
+            ("crouch", &mut self.crouch),
+        ]
+    }
+}
+
+/// Is any of the keys bound to an action held down?
+fn pressed(keys: &Input<KeyCode>, binding: &[KeyCode]) -> bool {
+    keys.any_pressed(binding.iter().copied())
+}
+
+/// Direction to scroll in when the cursor is within `margin` pixels of the primary window's edge.
+/// x is right, y is up.
+fn edge_scroll(windows: &Windows, margin: f32) -> Vec2 {
+    let mut direction = Vec2::ZERO;
+    if margin <= 0. {
+        return direction;
+    }
+    if let Some(window) = windows.get_primary() {
+        // Cursor position has its origin at the bottom left of the window.
+        if let Some(position) = window.cursor_position() {
+            if position.x < margin {
+                direction.x -= 1.;
+            } else if position.x > window.width() - margin {
+                direction.x += 1.;
+            }
+            if position.y < margin {
+                direction.y -= 1.;
+            } else if position.y > window.height() - margin {
+                direction.y += 1.;
+            }
+        }
+    }
+    direction
+}
+
 /// A marker component used in queries when you want flycams and not other cameras
 #[derive(Component)]
 pub struct FlyCam;
 
+/// Adds the per-camera state the plugin needs to new flycams, unless the user already has
+#[allow(clippy::type_complexity)]
+fn init_flycam(
+    mut commands: Commands,
+    settings: Res<MovementSettings>,
+    planet: Res<PlanetSettings>,
+    query: Query<
+        (
+            Entity,
+            &Transform,
+            Option<&FlyCamMode>,
+            Option<&FlyCamTarget>,
+            Option<&FlyCamRotation>,
+            Option<&FlyCamVelocity>,
+            Option<&FlyCamSpectator>,
+        ),
+        Added<FlyCam>,
+    >,
+) {
+    for (entity, transform, mode, target, rotation, velocity, spectator) in query.iter() {
+        let mut entity = commands.entity(entity);
+        if mode.is_none() {
+            entity.insert(FlyCamMode::default());
+        }
+        entity.insert(mode::PreviousMode(mode.copied().unwrap_or_default()));
+        entity.insert(target::ModeBlend::default());
+        entity.insert(target::FixedStepStart::default());
+        if spectator.is_none() {
+            entity.insert(FlyCamSpectator::default());
+        }
+        if target.is_none() {
+            entity.insert(FlyCamTarget::from(*transform));
+        }
+        let frame = rotation::FlyCamFrame::new(
+            mode.copied().unwrap_or_default(),
+            transform.translation,
+            &settings,
+            &planet,
+        );
+        let rotation = match rotation {
+            Some(rotation) => *rotation,
+            None => {
+                let rotation = FlyCamRotation::from(frame.0.inverse() * transform.rotation);
+                entity.insert(rotation);
+                rotation
+            }
+        };
+        if velocity.is_none() {
+            entity.insert(FlyCamVelocity::default());
+        }
+        entity.insert(rotation::FlyCamBank::level(rotation.yaw));
+        entity.insert(sprint::DoubleTapSprint::default());
+        entity.insert(frame);
+    }
+}
+
 /// Grabs/ungrabs mouse cursor
-#[cfg(not(target_family = "wasm"))]
+#[cfg(all(feature = "cursor-grab", not(target_family = "wasm")))]
 fn toggle_grab_cursor(window: &mut Window) {
     window.set_cursor_lock_mode(!window.cursor_locked());
     window.set_cursor_visibility(!window.cursor_visible());
 }
 
+/// Is the cursor currently grabbed by the window?
+fn cursor_grabbed(window: &Window) -> bool {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = window;
+        let browser_window = web_sys::window().expect("no global `window` exists");
+        let document = browser_window
+            .document()
+            .expect("should have a document on window");
+        document.pointer_lock_element().is_some()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    window.cursor_locked()
+}
+
 /// Grabs the cursor when game first starts (only works for non-wasm)
-#[cfg(not(target_family = "wasm"))]
+#[cfg(all(feature = "cursor-grab", not(target_family = "wasm")))]
 fn initial_grab_cursor(mut windows: ResMut<Windows>) {
     if let Some(window) = windows.get_primary_mut() {
         toggle_grab_cursor(window);
@@ -48,89 +763,262 @@ fn initial_grab_cursor(mut windows: ResMut<Windows>) {
     }
 }
 
-/// Spawns the `Camera3dBundle` to be controlled
-fn setup_player(mut commands: Commands) {
-    commands
-        .spawn_bundle(Camera3dBundle {
-            transform: Transform::from_xyz(-2.0, 5.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
-            ..Default::default()
-        })
-        .insert(FlyCam);
+/// Applies the fov and clipping planes from [`MovementSettings`] to new flycams,
+/// and to all flycams whenever the settings change
+#[allow(clippy::type_complexity)]
+fn sync_projection(
+    settings: Res<MovementSettings>,
+    state: Res<InputState>,
+    mut query: Query<
+        (&mut Projection, Option<ChangeTrackers<FlyCam>>),
+        Or<(With<FlyCam>, With<rig::FlyCamRigCamera>)>,
+    >,
+) {
+    for (mut projection, tracker) in query.iter_mut() {
+        if !settings.is_changed() && !tracker.is_some_and(|tracker| tracker.is_added()) {
+            continue;
+        }
+        if let Projection::Perspective(perspective) = projection.as_mut() {
+            perspective.fov = settings.zoomed_fov(state.zoom);
+            perspective.near = settings.near;
+            perspective.far = settings.far;
+        }
+    }
 }
 
-/// Returns the amount to boost or slow down by. (shift = run)
-fn get_boost(keys: &Input<KeyCode>, settings: &MovementSettings) -> f32 {
-    let mut boost = 1.;
-    for key in keys.get_pressed() {
-        match key {
-            KeyCode::LShift => boost = settings.boost,
-            KeyCode::O => boost = 1. / settings.boost, // slow motion mode
-            _ => (),
+/// Narrows the field of view while the zoom key is held and widens it again on release
+#[allow(clippy::type_complexity)]
+fn zoom(
+    keys: Res<FlyCamKeys>,
+    time: Res<FlyCamTime>,
+    settings: Res<MovementSettings>,
+    bindings: Res<KeyBindings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut state: ResMut<InputState>,
+    mut query: Query<&mut Projection, Or<(With<FlyCam>, With<rig::FlyCamRigCamera>)>>,
+) {
+    let target = if pressed(&keys, &bindings.zoom) && !blockers.blocked().hotkeys {
+        1.
+    } else {
+        0.
+    };
+    if state.zoom == target {
+        return;
+    }
+    let step = time.delta_seconds() / settings.zoom_time.max(f32::EPSILON);
+    state.zoom = if target > state.zoom {
+        (state.zoom + step).min(target)
+    } else {
+        (state.zoom - step).max(target)
+    };
+
+    let fov = settings.zoomed_fov(state.zoom);
+    for mut projection in query.iter_mut() {
+        if let Projection::Perspective(perspective) = projection.as_mut() {
+            perspective.fov = fov;
         }
     }
-    boost
 }
 
-/// Handles keyboard input and movement
-fn player_move(
+/// Sent when the speed preset keys change [`MovementSettings::speed`]
+pub struct SpeedChanged {
+    pub speed: f32,
+}
+
+/// Steps the speed through [`MovementSettings::speed_presets`]
+fn speed_presets(
+    keys: Res<Input<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut settings: ResMut<MovementSettings>,
+    mut speed_changed: EventWriter<SpeedChanged>,
+) {
+    if blockers.blocked().hotkeys {
+        return;
+    }
+    let current = settings.speed;
+    let speed = if keys.any_just_pressed(bindings.speed_up.iter().copied()) {
+        settings
+            .speed_presets
+            .iter()
+            .copied()
+            .find(|preset| *preset > current)
+    } else if keys.any_just_pressed(bindings.speed_down.iter().copied()) {
+        settings
+            .speed_presets
+            .iter()
+            .copied()
+            .rev()
+            .find(|preset| *preset < current)
+    } else {
+        None
+    };
+    if let Some(speed) = speed {
+        settings.speed = speed;
+        speed_changed.send(SpeedChanged { speed });
+    }
+}
+
+/// Scales the mouse sensitivity up and down with the sensitivity keys
+fn adjust_sensitivity(
+    keys: Res<Input<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut settings: ResMut<MovementSettings>,
+) {
+    if blockers.blocked().hotkeys {
+        return;
+    }
+    let factor = if keys.any_just_pressed(bindings.sensitivity_up.iter().copied()) {
+        settings.sensitivity_step
+    } else if keys.any_just_pressed(bindings.sensitivity_down.iter().copied()) {
+        1. / settings.sensitivity_step
+    } else {
+        return;
+    };
+    settings.sensitivity =
+        (settings.sensitivity * factor).clamp(settings.min_sensitivity, settings.max_sensitivity);
+    info!("Flycam sensitivity: {}", settings.sensitivity);
+}
+
+/// Turns [`MovementSettings::plane_lock`] on and off with its key
+fn toggle_plane_lock(
     keys: Res<Input<KeyCode>>,
-    time: Res<Time>,
+    bindings: Res<KeyBindings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut settings: ResMut<MovementSettings>,
+) {
+    if keys.any_just_pressed(bindings.toggle_plane_lock.iter().copied())
+        && !blockers.blocked().hotkeys
+    {
+        settings.plane_lock = !settings.plane_lock;
+    }
+}
+
+/// Returns the amount to boost or slow down by, from the most specific held [`BoostTier`]
+fn get_boost(keys: &Input<KeyCode>, settings: &MovementSettings) -> f32 {
+    settings
+        .boost_tiers
+        .iter()
+        .filter(|tier| !tier.keys.is_empty() && tier.keys.iter().all(|key| keys.pressed(*key)))
+        .max_by_key(|tier| tier.keys.len())
+        .map_or(1., |tier| tier.multiplier)
+}
+
+/// Turns keyboard input into [`FlyCamAction`]s
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn player_move(
+    keys: Res<FlyCamKeys>,
+    time: Res<FlyCamTime>,
     settings: Res<MovementSettings>,
-    mut query: Query<&mut Transform, With<FlyCam>>,
+    bindings: Res<KeyBindings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut state: ResMut<InputState>,
+    mut actions: EventWriter<FlyCamInput>,
+    mut query: Query<
+        (Entity, &FlyCamMode, Option<&mut sprint::DoubleTapSprint>),
+        (With<FlyCam>, Without<actions::ExternalInput>),
+    >,
 ) {
-    for mut transform in query.iter_mut() {
-        let mut velocity = Vec3::ZERO;
-        let local_z = transform.local_z();
-        let forward = -Vec3::new(local_z.x, 0., local_z.z);
-        let right = Vec3::new(local_z.z, 0., -local_z.x);
-        let boost = get_boost(&keys, &settings);
-        let mut rx = 0.;
-        let mut ry = 0.;
-        let mut rz = 0.;
-
-        for key in keys.get_pressed() {
-            match key {
-                KeyCode::W | KeyCode::Up => velocity += forward,
-                KeyCode::S | KeyCode::Down => velocity -= forward,
-                KeyCode::A | KeyCode::Left => velocity -= right,
-                KeyCode::D | KeyCode::Right => velocity += right,
-                KeyCode::Space | KeyCode::Period => velocity += Vec3::Y,
-                KeyCode::RShift | KeyCode::Comma => velocity -= Vec3::Y,
-                KeyCode::LBracket => {
-                    ry -= time.delta_seconds();
-                } // yaw, pitch, roll.
-                KeyCode::RBracket => {
-                    ry += time.delta_seconds();
-                }
-                KeyCode::Q => {
-                    rx -= time.delta_seconds();
-                } // yaw, pitch, roll.
-                KeyCode::E => {
-                    rx += time.delta_seconds();
-                }
-                KeyCode::Z => {
-                    rz -= time.delta_seconds();
-                } // yaw, pitch, roll.
-                KeyCode::X => {
-                    rz += time.delta_seconds();
-                }
-                // Note: bevy 0.7 bug: if you press LShift and then Comma no additional key seems to be pressed
-                _ => (),
+    if blockers.blocked().movement {
+        return;
+    }
+    // Nothing to send, and nothing to change in `state` so it isn't marked changed either
+    if keys.get_pressed().next().is_none() && !state.auto_forward {
+        if state.forward_held != 0. {
+            state.forward_held = 0.;
+        }
+        for (_, _, sprint) in query.iter_mut() {
+            if let Some(mut sprint) = sprint.filter(|sprint| sprint.sprinting) {
+                sprint.sprinting = false;
             }
         }
+        return;
+    }
+    if keys.any_just_pressed(bindings.auto_forward.iter().copied()) {
+        state.auto_forward = !state.auto_forward;
+    } else if keys.any_just_pressed(bindings.move_backward.iter().copied()) {
+        state.auto_forward = false;
+    }
 
-        velocity = velocity.normalize_or_zero();
+    // Stepping moves once per press, so there's nothing to cruise.
+    if (pressed(&keys, &bindings.move_forward) || state.auto_forward) && !settings.stepping {
+        state.forward_held += time.delta_seconds();
+    } else if state.forward_held != 0. {
+        state.forward_held = 0.;
+    }
 
-        transform.translation += velocity * time.delta_seconds() * settings.speed * boost;
+    let boost = get_boost(&keys, &settings) * settings.cruise_boost(state.forward_held);
+    for (entity, mode, sprint) in query.iter_mut() {
+        if !mode.uses_fly_controls() {
+            continue;
+        }
+        let bindings = bindings.for_mode(*mode);
+        let mut boost = boost;
+        if let Some(mut sprint) = sprint.filter(|_| settings.double_tap_sprint > 0.) {
+            let tapped = keys.any_just_pressed(bindings.move_forward.iter().copied());
+            let held = pressed(&keys, &bindings.move_forward) || state.auto_forward;
+            let now = time.seconds_since_startup();
+            if sprint.update(tapped, held, now, settings.double_tap_sprint) {
+                boost *= settings.sprint_multiplier;
+            }
+        }
+        if boost != 1. {
+            actions.send(FlyCamInput::camera(entity, FlyCamAction::Boost(boost)));
+        }
+        // In stepping mode each press counts once, otherwise keys count for as long as they're held.
+        let active = |bindings: &[KeyCode]| {
+            if settings.stepping {
+                keys.any_just_pressed(bindings.iter().copied())
+            } else {
+                pressed(&keys, bindings)
+            }
+        };
+        let axis = |positive: &[KeyCode], negative: &[KeyCode]| {
+            active(positive) as i8 as f32 - active(negative) as i8 as f32
+        };
 
-        let delta_x = settings.speed * boost * rx / 100. * std::f32::consts::PI * 2.0;
-        let delta_y = settings.speed * boost * ry / 100. * std::f32::consts::PI;
-        let delta_z = settings.speed * boost * rz / 100. * std::f32::consts::PI;
-        let yaw = Quat::from_rotation_y(-delta_x);
-        let pitch = Quat::from_rotation_x(-delta_y);
-        let roll = Quat::from_rotation_z(-delta_z);
-        transform.rotation = yaw * transform.rotation; // rotate around global y axis
-        transform.rotation = transform.rotation * pitch * roll; // rotate around local x axis
+        let mut forward = axis(&bindings.move_forward, &bindings.move_backward);
+        if state.auto_forward && !settings.stepping && !active(&bindings.move_forward) {
+            forward += 1.;
+        }
+        let mut send = |action: fn(f32) -> FlyCamAction, amount: f32| {
+            if amount != 0. {
+                actions.send(FlyCamInput::camera(entity, action(amount)));
+            }
+        };
+        send(FlyCamAction::MoveForward, forward);
+        send(
+            FlyCamAction::MoveRight,
+            axis(&bindings.move_right, &bindings.move_left),
+        );
+        send(
+            FlyCamAction::MoveUp,
+            axis(&bindings.move_ascend, &bindings.move_descend),
+        );
+        #[cfg(feature = "keyboard-rotation")]
+        {
+            send(
+                FlyCamAction::Yaw,
+                axis(&bindings.yaw_left, &bindings.yaw_right),
+            );
+            send(
+                FlyCamAction::Pitch,
+                axis(&bindings.pitch_up, &bindings.pitch_down),
+            );
+            send(
+                FlyCamAction::Roll,
+                axis(&bindings.roll_left, &bindings.roll_right),
+            );
+        }
+        if *mode == FlyCamMode::Walk {
+            if keys.any_just_pressed(bindings.jump.iter().copied()) {
+                actions.send(FlyCamInput::camera(entity, FlyCamAction::Jump));
+            }
+            if pressed(&keys, &bindings.crouch) {
+                actions.send(FlyCamInput::camera(entity, FlyCamAction::Crouch));
+            }
+        }
     }
 }
 
@@ -152,10 +1040,12 @@ impl Default for Scale {
 /// Other controlers might require differnet mappings.
 #[cfg(target_family = "wasm")]
 fn gamepad(
-    keys: Res<Input<KeyCode>>,
+    keys: Res<FlyCamKeys>,
     settings: Res<MovementSettings>,
-    mut query: Query<&mut Transform, With<FlyCam>>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut actions: EventWriter<FlyCamInput>,
 ) {
+    let blocked = blockers.blocked();
     let window = web_sys::window().expect("no global `window` exists");
     let navigator = window.navigator();
     let pads = navigator
@@ -164,110 +1054,149 @@ fn gamepad(
 
     for gp in pads.iter() {
         if !gp.is_null() {
-            for mut transform in query.iter_mut() {
-                let g: web_sys::Gamepad = gp.clone().into();
-                let axes = g.axes();
-
-                let boost = get_boost(&keys, &settings);
-
-                let scale = Scale {
-                    rotate_scale: 0.07,
-                    translate_scale: 2.5,
-                };
-
-                let (x, y, z) = (
-                    axes.at(0).as_f64().unwrap_or_default() as f32 * scale.translate_scale * boost,
-                    axes.at(2).as_f64().unwrap_or_default() as f32
-                        * scale.translate_scale
-                        * boost
-                        * -1.,
-                    axes.at(1).as_f64().unwrap_or_default() as f32
-                        * scale.translate_scale
-                        * boost
-                        * -1.,
-                );
-                // Suggestion to cube inputs to give a nice gain curve:
-                // https://www.chiefdelphi.com/t/paper-joystick-sensitivity-gain-adjustment/107280
-                let (x, y, z) = (x * x * x, y * y * y, z * z * z);
-
-                let forward = transform.forward();
-                let right = transform.right();
-                let up = transform.up();
-
-                transform.translation += x * right + y * up + z * forward;
-
-                let boost = boost.min(1.); // Honestly you don't want faster rotations.
-
-                let rx = axes.at(3).as_f64().unwrap_or_default() as f32;
-                let ry = axes.at(5).as_f64().unwrap_or_default() as f32;
-                let rz = axes.at(4).as_f64().unwrap_or_default() as f32;
-                //let (rx,ry,rz) = (rx*rx*rx, ry*ry*ry, rz*rz*rz);
-                let rot = Quat::from_euler(
-                    EulerRot::XYZ,
-                    rx * scale.rotate_scale * boost,
-                    ry * scale.rotate_scale * boost * -1.,
-                    rz * scale.rotate_scale * boost,
-                );
-                transform.rotation *= rot;
+            let g: web_sys::Gamepad = gp.clone().into();
+            let axes = g.axes();
+
+            let boost = get_boost(&keys, &settings);
+
+            let scale = Scale {
+                rotate_scale: 0.07,
+                translate_scale: 2.5,
+            };
+
+            let (x, y, z) = (
+                axes.at(0).as_f64().unwrap_or_default() as f32 * scale.translate_scale * boost,
+                axes.at(2).as_f64().unwrap_or_default() as f32
+                    * scale.translate_scale
+                    * boost
+                    * -1.,
+                axes.at(1).as_f64().unwrap_or_default() as f32
+                    * scale.translate_scale
+                    * boost
+                    * -1.,
+            );
+            // Suggestion to cube inputs to give a nice gain curve:
+            // https://www.chiefdelphi.com/t/paper-joystick-sensitivity-gain-adjustment/107280
+            let (x, y, z) = (x * x * x, y * y * y, z * z * z);
+
+            if !blocked.movement {
+                actions.send(FlyCamInput::all(FlyCamAction::Translate(Vec3::new(
+                    x, y, -z,
+                ))));
+            }
+            if blocked.look {
+                continue;
             }
+
+            let boost = boost.min(1.); // Honestly you don't want faster rotations.
+
+            let rx = axes.at(3).as_f64().unwrap_or_default() as f32;
+            let ry = axes.at(5).as_f64().unwrap_or_default() as f32;
+            let rz = axes.at(4).as_f64().unwrap_or_default() as f32;
+            //let (rx,ry,rz) = (rx*rx*rx, ry*ry*ry, rz*rz*rz);
+            actions.send(FlyCamInput::all(FlyCamAction::StickLook(Vec2::new(
+                ry * scale.rotate_scale * boost * -1.,
+                rx * scale.rotate_scale * boost,
+            ))));
+            actions.send(FlyCamInput::all(FlyCamAction::Rotate(
+                Vec3::Z * rz * scale.rotate_scale * boost,
+            )));
         }
     }
 }
 
-/// Handles looking around if cursor is locked
+/// Turns mouse motion into look actions while the cursor is grabbed
+#[allow(clippy::too_many_arguments)]
 fn player_look(
-    settings: Res<MovementSettings>,
     windows: Res<Windows>,
+    settings: Res<MovementSettings>,
+    time: Res<FlyCamTime>,
     mut state: ResMut<InputState>,
-    motion: Res<Events<MouseMotion>>,
-    mut query: Query<&mut Transform, With<FlyCam>>,
+    mut motion: EventReader<MouseMotion>,
     buttons: Res<Input<MouseButton>>,
+    blockers: Res<FlyCamInputBlockers>,
+    profiles: Res<SensitivityProfiles>,
+    mut actions: EventWriter<FlyCamInput>,
 ) {
     if let Some(window) = windows.get_primary() {
         let please_move = buttons.pressed(MouseButton::Left) || buttons.pressed(MouseButton::Right);
 
-        #[cfg(target_arch = "wasm32")]
-        {
-            let browser_window = web_sys::window().expect("no global `window` exists");
-            let document = browser_window
-                .document()
-                .expect("should have a document on window");
-            let locked = document.pointer_lock_element().is_some();
-            if !locked && !please_move {
-                return;
+        if blockers.blocked().look || (!cursor_grabbed(window) && !please_move) {
+            if state.smoothed_motion != Vec2::ZERO {
+                state.smoothed_motion = Vec2::ZERO;
             }
+            // Drop it, so it isn't looked with once the cursor is grabbed again.
+            motion.clear();
+            return;
         }
-        #[cfg(not(target_arch = "wasm32"))]
-        if !window.cursor_locked() && !please_move {
+        if motion.is_empty() {
             return;
         }
 
-        for mut transform in query.iter_mut() {
-            for ev in state.reader_motion.iter(&motion) {
-                let window_scale = window.height().min(window.width());
-
-                // Order is important to prevent unintended roll
-                let yaw = Quat::from_rotation_y(
-                    -(settings.sensitivity * ev.delta.x * window_scale).to_radians(),
-                );
-                let pitch = Quat::from_rotation_x(
-                    -(settings.sensitivity * ev.delta.y * window_scale).to_radians(),
-                );
-                transform.rotation = yaw * transform.rotation; // rotate around global y axis
-                transform.rotation *= pitch; // rotate around local x axis
-            }
+        let delta = motion.iter().fold(Vec2::ZERO, |delta, ev| delta + ev.delta);
+        if delta != Vec2::ZERO {
+            let delta = settings.accelerate_mouse(delta, time.delta_seconds())
+                * profiles.get(InputDevice::Mouse);
+            actions.send(FlyCamInput::all(FlyCamAction::Look(delta)));
         }
     } else {
         warn!("Primary window not found for `player_look`!");
     }
 }
 
+/// Turns the view with the look keys, and tilts it with the tilt keys, at a steady rate
+#[allow(clippy::type_complexity)]
+fn keyboard_look(
+    keys: Res<FlyCamKeys>,
+    time: Res<FlyCamTime>,
+    settings: Res<MovementSettings>,
+    bindings: Res<KeyBindings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut actions: EventWriter<FlyCamInput>,
+    mut query: Query<
+        (Entity, &FlyCamMode, &mut FlyCamRotation),
+        (With<FlyCam>, Without<actions::ExternalInput>),
+    >,
+) {
+    if blockers.blocked().look || keys.get_pressed().next().is_none() {
+        return;
+    }
+    let rate = settings.keyboard_look_speed.to_radians() * time.delta_seconds();
+    for (entity, mode, mut rotation) in query.iter_mut() {
+        if !mode.uses_fly_controls() {
+            continue;
+        }
+        let bindings = bindings.for_mode(*mode);
+        let axis = |positive: &[KeyCode], negative: &[KeyCode]| {
+            pressed(&keys, positive) as i8 as f32 - pressed(&keys, negative) as i8 as f32
+        };
+        let turn = Vec2::new(
+            axis(&bindings.look_left, &bindings.look_right),
+            axis(&bindings.look_up, &bindings.look_down),
+        ) * rate;
+        if turn != Vec2::ZERO {
+            actions.send(FlyCamInput::camera(entity, FlyCamAction::StickLook(turn)));
+        }
+        let tilt = axis(&bindings.tilt_left, &bindings.tilt_right) * rate;
+        if tilt != 0. {
+            rotation.roll_offset = rotation::wrap_angle(rotation.roll_offset + tilt);
+        }
+    }
+}
+
 /// Long running processes are not allowed to grab the cursor in wasm - this must be done by
 /// some user activated short lived action. (see index.html)
-#[cfg(not(target_family = "wasm"))]
-fn cursor_grab(keys: Res<Input<KeyCode>>, mut windows: ResMut<Windows>) {
+#[cfg(all(feature = "cursor-grab", not(target_family = "wasm")))]
+fn cursor_grab(
+    keys: Res<Input<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut windows: ResMut<Windows>,
+) {
     if let Some(window) = windows.get_primary_mut() {
-        if keys.just_pressed(KeyCode::Escape) {
+        if keys.any_just_pressed(bindings.toggle_grab_cursor.iter().copied())
+            && !blockers.blocked().grab_toggle
+        {
             toggle_grab_cursor(window);
         }
     } else {
@@ -278,60 +1207,360 @@ fn cursor_grab(keys: Res<Input<KeyCode>>, mut windows: ResMut<Windows>) {
 /// the mouse-scroll does not change the field-of-view of the camera
 /// because if you change that too far the world goes inside out.
 /// Instead scroll moves forwards or backwards.
+/// Scrolling sideways strafes, with [`MovementSettings::horizontal_scroll_sensitivity`].
+#[cfg(feature = "scroll")]
 pub fn scroll(
-    settings: Res<MovementSettings>,
-    keys: Res<Input<KeyCode>>,
     mut mouse_wheel_events: EventReader<MouseWheel>,
-    mut query: Query<&mut Transform, With<FlyCam>>,
+    #[cfg(target_arch = "wasm32")] windows: Res<Windows>,
+    #[cfg(target_arch = "wasm32")] settings: Res<MovementSettings>,
+    blockers: Res<FlyCamInputBlockers>,
+    profiles: Res<SensitivityProfiles>,
+    mut actions: EventWriter<FlyCamInput>,
 ) {
+    if blockers.blocked().scroll {
+        mouse_wheel_events.clear();
+        return;
+    }
+    #[cfg(target_arch = "wasm32")]
+    let scale_factor = primary_scale_factor(&windows);
     for event in mouse_wheel_events.iter() {
-        for mut viewport in query.iter_mut() {
-            // In browser this seems a lot more sensitive!
-            #[cfg(target_arch = "wasm32")]
-            let sensitivity: f32 = settings.sensitivity * 10.0;
-            #[cfg(not(target_arch = "wasm32"))]
-            let sensitivity: f32 = settings.sensitivity * 1024.0;
-            let forward = viewport.forward();
-            viewport.translation += forward * event.y * sensitivity * get_boost(&keys, &settings);
+        #[cfg(target_arch = "wasm32")]
+        let lines = event_notches(event, scale_factor) * settings.web_scroll_sensitivity;
+        #[cfg(not(target_arch = "wasm32"))]
+        let lines = Vec2::new(event.x, event.y);
+        let device = match event.unit {
+            MouseScrollUnit::Line => InputDevice::Mouse,
+            MouseScrollUnit::Pixel => InputDevice::Trackpad,
+        };
+        let step = 1024.0 * profiles.get(device);
+        if lines.y != 0. {
+            actions.send(FlyCamInput::all(FlyCamAction::Scroll(lines.y * step)));
+        }
+        // Positive x moves the content right, so the view goes left.
+        if lines.x != 0. {
+            actions.send(FlyCamInput::all(FlyCamAction::ScrollSideways(
+                -lines.x * step,
+            )));
         }
     }
 }
 
-/// Contains everything needed to add first-person fly camera behavior to your game
-pub struct PlayerPlugin;
-impl Plugin for PlayerPlugin {
-    fn build(&self, app: &mut App) {
-        app.init_resource::<InputState>()
-            .init_resource::<MovementSettings>()
-            .add_startup_system(setup_player)
-            .add_system(player_move)
-            .add_system(player_look)
-            .add_system(scroll);
+/// The primary window's scale factor, or 1 without one
+fn primary_scale_factor(windows: &Windows) -> f32 {
+    windows
+        .get_primary()
+        .map_or(1., |window| window.scale_factor() as f32)
+}
 
-        #[cfg(target_family = "wasm")]
-        app.add_system(gamepad);
+/// A wheel event in notches of a desktop mouse wheel. Trackpads and browsers scroll in pixels,
+/// which winit hands over in physical pixels, so those are taken as 100 logical pixels a notch.
+/// On the web, Firefox scrolls 3 lines a notch while Chrome and Safari scroll 100 pixels; both
+/// are evened out to one notch. Page scrolling isn't passed on by winit at all.
+fn event_notches(event: &MouseWheel, scale_factor: f32) -> Vec2 {
+    #[cfg(target_arch = "wasm32")]
+    const LINES_PER_NOTCH: f32 = 3.;
+    #[cfg(not(target_arch = "wasm32"))]
+    const LINES_PER_NOTCH: f32 = 1.;
+    const PIXELS_PER_NOTCH: f32 = 100.;
+    let delta = Vec2::new(event.x, event.y);
+    match event.unit {
+        MouseScrollUnit::Line => delta / LINES_PER_NOTCH,
+        MouseScrollUnit::Pixel => delta / scale_factor / PIXELS_PER_NOTCH,
+    }
+}
 
-        #[cfg(not(target_family = "wasm"))]
-        app.add_startup_system(initial_grab_cursor)
-            .add_system(cursor_grab);
+/// How far the wheel turned in `events`, in notches, for zooming by a step a notch whatever
+/// the wheel's units
+fn wheel_notches(windows: &Windows, events: &mut EventReader<MouseWheel>) -> Vec2 {
+    let scale_factor = primary_scale_factor(windows);
+    events.iter().fold(Vec2::ZERO, |notches, event| {
+        notches + event_notches(event, scale_factor)
+    })
+}
+
+/// Where [`NoCameraPlayerPlugin`] runs the systems that read input and move the cameras'
+/// targets. Insert it before adding any of the flycam plugins, e.g. to move the camera in
+/// `CoreStage::PreUpdate` before your physics. Easing the camera towards its target always
+/// happens in `CoreStage::PostUpdate`, before transforms are propagated.
+///
+/// The flycam moves by the frame's [`FlyCamTime`], so pick a stage that runs once a frame,
+/// or use [`FlyCamSchedule::fixed`] to move it on a fixed timestep.
+#[derive(Clone, Copy, Debug)]
+pub struct FlyCamSchedule {
+    pub stage: StageLabelId,
+    /// Seconds per step when the stage is [`FlyCamSchedule::fixed`]'s
+    pub fixed_timestep: Option<f64>,
+}
+
+impl Default for FlyCamSchedule {
+    fn default() -> Self {
+        Self::new(CoreStage::Update)
     }
 }
 
+/// The stage [`FlyCamSchedule::fixed`] adds after `CoreStage::Update`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, StageLabel)]
+pub struct FlyCamFixedStage;
+
+/// Label of [`FlyCamFixedStage`]'s timestep in bevy's `FixedTimesteps`
+pub const FIXED_TIMESTEP: &str = "bevy_flycam::fixed";
+
+impl FlyCamSchedule {
+    pub fn new(stage: impl StageLabel) -> Self {
+        Self {
+            stage: stage.as_label(),
+            fixed_timestep: None,
+        }
+    }
+
+    /// Reads input and moves the cameras' targets every `step` seconds of `Time`, in a
+    /// [`FlyCamFixedStage`] after `CoreStage::Update`, e.g. in step with your physics.
+    /// [`FlyCamTime`] has the step's delta there. The camera is still drawn every frame,
+    /// part of the way through the last step, so it moves smoothly for motion blur and TAA
+    /// rather than jumping once a step.
+    ///
+    /// Keep the step close to the frame time: keys pressed in a frame are seen by every step
+    /// run in it, and by none if no step runs.
+    pub fn fixed(step: f64) -> Self {
+        Self {
+            stage: FlyCamFixedStage.as_label(),
+            fixed_timestep: Some(step),
+        }
+    }
+
+    /// The stage in the app's [`FlyCamSchedule`], for plugins adding systems next to the
+    /// flycam's. Adds the fixed stage if it's needed and not there yet.
+    pub(crate) fn stage(app: &mut App) -> StageLabelId {
+        app.init_resource::<FlyCamSchedule>();
+        let schedule = *app.world.resource::<FlyCamSchedule>();
+        if let Some(step) = schedule.fixed_timestep {
+            if app
+                .schedule
+                .get_stage::<SystemStage>(&schedule.stage)
+                .is_none()
+            {
+                app.init_resource::<FixedTimesteps>().add_stage_after(
+                    CoreStage::Update,
+                    schedule.stage,
+                    SystemStage::parallel()
+                        .with_run_criteria(FixedTimestep::step(step).with_label(FIXED_TIMESTEP)),
+                );
+            }
+        }
+        schedule.stage
+    }
+}
+
+/// Labels for ordering your own systems around the flycam's
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, SystemLabel)]
+pub enum FlyCamSystem {
+    /// Reading input and moving the cameras' targets, in [`FlyCamSchedule::stage`]
+    Control,
+}
+
 /// Same as [`PlayerPlugin`] but does not spawn a camera
 pub struct NoCameraPlayerPlugin;
 impl Plugin for NoCameraPlayerPlugin {
     fn build(&self, app: &mut App) {
+        clock::add_clock(app);
+        let stage = FlyCamSchedule::stage(app);
+        toggles::add_key_latching(
+            app,
+            stage,
+            toggles::latch_keys
+                .after(InputSystem)
+                .before(FlyCamSystem::Control),
+        );
         app.init_resource::<InputState>()
             .init_resource::<MovementSettings>()
-            .add_system(player_move)
-            .add_system(player_look)
-            .add_system(scroll);
+            .init_resource::<KeyBindings>()
+            .init_resource::<RtsSettings>()
+            .init_resource::<DroneSettings>()
+            .init_resource::<VehicleSettings>()
+            .init_resource::<PlanetSettings>()
+            .init_resource::<OrbitSettings>()
+            .init_resource::<MouseOnlySettings>()
+            .init_resource::<WalkSettings>()
+            .init_resource::<TerrainSettings>()
+            .init_resource::<FlyCamProfiles>()
+            .init_resource::<FlyCamInputBlockers>()
+            .init_resource::<FlyCamGamepadSettings>()
+            .init_resource::<SensitivityProfiles>()
+            .init_resource::<FlyCamCursorGrab>()
+            .init_resource::<touch::ClaimedTouches>()
+            .init_resource::<FlyCamIdle>()
+            .init_resource::<FlyCamPathPlaybackSettings>()
+            .add_event::<SpeedChanged>()
+            .add_event::<SetFlyCamMode>()
+            .add_event::<ModeChanged>()
+            .add_event::<FlyCamInput>()
+            .add_event::<CursorGrabChanged>()
+            .add_event::<PathPlaybackFinished>()
+            .add_event::<TourControl>()
+            .add_event::<TourStopReached>()
+            .add_event::<TourStopLeft>()
+            .add_system_set_to_stage(
+                stage,
+                SystemSet::new()
+                    .label(FlyCamSystem::Control)
+                    // Input is only up to date after this, which matters in `PreUpdate`.
+                    .after(InputSystem)
+                    .with_system(init_flycam)
+                    .with_system(idle::track_idle)
+                    .with_system(
+                        screensaver::screensaver
+                            .after(idle::track_idle)
+                            .before(actions::apply_actions),
+                    )
+                    .with_system(mode::set_mode)
+                    .with_system(mode::announce_mode_changes.after(mode::set_mode))
+                    .with_system(sync_projection)
+                    .with_system(zoom)
+                    .with_system(pose::copy_pose)
+                    .with_system(speed_presets)
+                    .with_system(adjust_sensitivity)
+                    .with_system(toggle_plane_lock)
+                    .with_system(profiles::cycle_profiles)
+                    .with_system(
+                        profiles::apply_profile
+                            .after(profiles::cycle_profiles)
+                            .before(player_move)
+                            .before(actions::apply_actions),
+                    )
+                    .with_system(player_move.before(actions::apply_actions))
+                    .with_system(player_look.before(actions::apply_actions))
+                    .with_system(keyboard_look.before(actions::apply_actions))
+                    .with_system(gamepad::gamepad_move.before(actions::apply_actions))
+                    .with_system(gamepad::gamepad_look.before(actions::apply_actions))
+                    .with_system(touch::touch_look.before(actions::apply_actions))
+                    .with_system(auto_speed::auto_speed.before(actions::apply_actions))
+                    .with_system(fluid::detect_fluids.before(actions::apply_actions))
+                    .with_system(actions::apply_actions)
+                    .with_system(dolly::dolly_zoom.after(actions::apply_actions))
+                    .with_system(path_playback::play_paths.after(actions::apply_actions))
+                    .with_system(tour::take_tours.after(actions::apply_actions))
+                    .with_system(terrain::follow_terrain.after(actions::apply_actions))
+                    .with_system(
+                        walk::enter_walk
+                            .after(mode::set_mode)
+                            .before(actions::apply_actions),
+                    )
+                    .with_system(rts::enter_rts)
+                    .with_system(rts::rts_camera)
+                    .with_system(drone::enter_drone)
+                    .with_system(drone::drone_camera)
+                    .with_system(vehicle::enter_vehicle)
+                    .with_system(vehicle::vehicle_camera)
+                    .with_system(orbit::enter_orbit)
+                    .with_system(orbit::orbit_camera)
+                    .with_system(mouse_only::mouse_only_camera)
+                    .with_system(
+                        turntable::turntable
+                            .after(idle::track_idle)
+                            .after(actions::apply_actions)
+                            .after(orbit::orbit_camera)
+                            .after(mouse_only::mouse_only_camera),
+                    )
+                    .with_system(spectator::cycle_spectate.before(mode::announce_mode_changes))
+                    .with_system(spectator::spectate_camera.after(spectator::cycle_spectate)),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                rotation::update_frame
+                    .after(FlyCamSystem::Control)
+                    .before(rotation::apply_rotation),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                rotation::apply_rotation.before(target::follow_target),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                target::follow_target
+                    .after(FlyCamSystem::Control)
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                dolly::dolly_zoom_fov.after(target::follow_target),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                focus::autofocus.after(TransformSystem::TransformPropagate),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                precise::sync_precise_position
+                    .after(target::follow_target)
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                net::author_net_state.after(target::follow_target),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                net::apply_net_state.before(TransformSystem::TransformPropagate),
+            )
+            // Last, to catch grabs and releases from anywhere in the frame
+            .add_system_to_stage(CoreStage::Last, grab::track_cursor_grab);
+
+        if app
+            .world
+            .resource::<FlyCamSchedule>()
+            .fixed_timestep
+            .is_some()
+        {
+            app.add_system_to_stage(
+                stage,
+                clock::start_fixed_step
+                    .before(toggles::latch_keys)
+                    .before(FlyCamSystem::Control),
+            )
+            .add_system_to_stage(
+                stage,
+                target::start_fixed_step.before(FlyCamSystem::Control),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                clock::end_fixed_steps
+                    .before(rotation::update_frame)
+                    .before(target::follow_target),
+            );
+        }
+
+        #[cfg(feature = "scroll")]
+        app.add_system_to_stage(
+            stage,
+            scroll
+                .label(FlyCamSystem::Control)
+                .after(InputSystem)
+                .before(actions::apply_actions),
+        );
+
+        // One the app inserted itself wins over the environment.
+        #[cfg(feature = "startup-overrides")]
+        if !app.world.contains_resource::<FlyCamStartup>() {
+            app.insert_resource(FlyCamStartup::from_env());
+        }
+        #[cfg(feature = "startup-overrides")]
+        app.add_system_to_stage(
+            stage,
+            startup::apply_startup
+                .label(FlyCamSystem::Control)
+                .before(init_flycam),
+        );
 
         #[cfg(target_family = "wasm")]
-        app.add_system(gamepad);
+        app.add_system_to_stage(
+            stage,
+            gamepad
+                .label(FlyCamSystem::Control)
+                .before(actions::apply_actions),
+        );
 
-        #[cfg(not(target_family = "wasm"))]
+        #[cfg(all(feature = "cursor-grab", not(target_family = "wasm")))]
         app.add_startup_system(initial_grab_cursor)
-            .add_system(cursor_grab);
+            .add_system_to_stage(stage, cursor_grab.after(InputSystem));
     }
 }
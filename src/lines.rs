@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+use bevy::render::mesh::PrimitiveTopology;
+
+/// A mesh of separate line segments, for drawing debugging aids with the `debug-lines` feature
+pub(crate) fn line_mesh(segments: impl IntoIterator<Item = (Vec3, Vec3)>) -> Mesh {
+    let positions: Vec<[f32; 3]> = segments
+        .into_iter()
+        .flat_map(|(from, to)| [from.to_array(), to.to_array()])
+        .collect();
+    // Unlit, but the pipeline still wants normals.
+    let normals = vec![[0., 1., 0.]; positions.len()];
+    let mut mesh = Mesh::new(PrimitiveTopology::LineList);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh
+}
+
+/// A flat colour for [`line_mesh`]es
+pub(crate) fn line_material(color: Color) -> StandardMaterial {
+    StandardMaterial {
+        base_color: color,
+        unlit: true,
+        ..default()
+    }
+}
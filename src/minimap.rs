@@ -0,0 +1,194 @@
+use bevy::core_pipeline::clear_color::ClearColorConfig;
+use bevy::prelude::*;
+use bevy::render::camera::{Projection, RenderTarget, ScalingMode, Viewport};
+
+use crate::{FlyCam, MovementSettings};
+
+/// Which corner of the window the minimap goes in
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MinimapCorner {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Settings for the minimap added by [`FlyCamMinimapPlugin`]
+pub struct FlyCamMinimapSettings {
+    /// Keys that show and hide the minimap
+    pub toggle: Vec<KeyCode>,
+    /// Whether the minimap is shown
+    pub visible: bool,
+    pub corner: MinimapCorner,
+    /// Width and height of the minimap as a fraction of the window's shorter side
+    pub size: f32,
+    /// Gap between the minimap and the window edges, in logical pixels
+    pub margin: f32,
+    /// How many world units the minimap shows from top to bottom
+    pub extent: f32,
+    /// How far above the flycam the minimap camera hovers
+    pub height: f32,
+    /// Turn the map so the flycam's heading points up, instead of keeping north up
+    pub rotate_with_heading: bool,
+    /// Render into this image instead of a corner of the window, e.g. to show it in the UI.
+    /// Only read when the plugin starts.
+    pub target: Option<Handle<Image>>,
+}
+
+impl Default for FlyCamMinimapSettings {
+    fn default() -> Self {
+        Self {
+            toggle: vec![KeyCode::M],
+            visible: true,
+            corner: MinimapCorner::default(),
+            size: 0.3,
+            margin: 10.,
+            extent: 50.,
+            height: 100.,
+            rotate_with_heading: true,
+            target: None,
+        }
+    }
+}
+
+/// Marks the minimap camera, e.g. to add render layers to it
+#[derive(Component)]
+pub struct FlyCamMinimap;
+
+/// Adds a top-down orthographic camera that follows the first flycam and renders into a
+/// corner of the window, or into an image. Configure it with [`FlyCamMinimapSettings`].
+///
+/// Add it alongside [`PlayerPlugin`](crate::PlayerPlugin) or
+/// [`NoCameraPlayerPlugin`](crate::NoCameraPlayerPlugin).
+pub struct FlyCamMinimapPlugin;
+impl Plugin for FlyCamMinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlyCamMinimapSettings>()
+            .add_startup_system(setup_minimap)
+            .add_system(toggle_minimap)
+            .add_system(update_minimap.after(toggle_minimap));
+    }
+}
+
+fn setup_minimap(mut commands: Commands, settings: Res<FlyCamMinimapSettings>) {
+    let (target, clear_color) = match &settings.target {
+        Some(image) => (
+            RenderTarget::Image(image.clone()),
+            ClearColorConfig::Default,
+        ),
+        // Clearing would wipe the whole window, not just the corner.
+        None => (RenderTarget::default(), ClearColorConfig::None),
+    };
+    commands
+        .spawn_bundle(Camera3dBundle {
+            camera: Camera {
+                priority: 1,
+                is_active: settings.visible,
+                target,
+                ..Default::default()
+            },
+            camera_3d: Camera3d {
+                clear_color,
+                ..Default::default()
+            },
+            projection: Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::FixedVertical(settings.extent),
+                far: settings.height * 2.,
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .insert(FlyCamMinimap);
+}
+
+/// Shows and hides the minimap with its keys
+fn toggle_minimap(keys: Res<Input<KeyCode>>, mut settings: ResMut<FlyCamMinimapSettings>) {
+    if keys.any_just_pressed(settings.toggle.iter().copied()) {
+        settings.visible = !settings.visible;
+    }
+}
+
+/// Keeps the minimap camera above the first flycam and in its corner of the window
+#[allow(clippy::type_complexity)]
+fn update_minimap(
+    windows: Res<Windows>,
+    settings: Res<FlyCamMinimapSettings>,
+    movement: Res<MovementSettings>,
+    flycams: Query<&Transform, (With<FlyCam>, Without<FlyCamMinimap>)>,
+    mut minimaps: Query<(&mut Camera, &mut Transform, &mut Projection), With<FlyCamMinimap>>,
+) {
+    let flycam = flycams.iter().next();
+    for (mut camera, mut transform, mut projection) in minimaps.iter_mut() {
+        if camera.is_active != settings.visible {
+            camera.is_active = settings.visible;
+        }
+        if !settings.visible {
+            continue;
+        }
+
+        if settings.is_changed() {
+            if let Projection::Orthographic(orthographic) = projection.as_mut() {
+                orthographic.scaling_mode = ScalingMode::FixedVertical(settings.extent);
+                orthographic.far = settings.height * 2.;
+            }
+        }
+
+        if settings.target.is_none() {
+            if let Some(window) = windows.get_primary() {
+                let viewport = corner_viewport(window, &settings);
+                let unchanged = camera.viewport.as_ref().is_some_and(|current| {
+                    current.physical_position == viewport.physical_position
+                        && current.physical_size == viewport.physical_size
+                });
+                if !unchanged {
+                    camera.viewport = Some(viewport);
+                }
+            }
+        }
+
+        let flycam = match flycam {
+            Some(flycam) => flycam,
+            None => continue,
+        };
+        let up = movement.up_axis.try_normalize().unwrap_or(Vec3::Y);
+        let forward = flycam.forward();
+        let heading = (forward - up * forward.dot(up)).normalize_or_zero();
+        // The map's top is the heading, or the world's north, -z turned to the up axis.
+        let top = if settings.rotate_with_heading && heading != Vec3::ZERO {
+            heading
+        } else {
+            Quat::from_rotation_arc(Vec3::Y, up) * Vec3::NEG_Z
+        };
+        let new = Transform::from_translation(flycam.translation + up * settings.height)
+            .looking_at(flycam.translation, top);
+        if *transform != new {
+            *transform = new;
+        }
+    }
+}
+
+/// The square viewport in the configured corner of the window
+fn corner_viewport(window: &Window, settings: &FlyCamMinimapSettings) -> Viewport {
+    let width = window.physical_width();
+    let height = window.physical_height();
+    let margin = (settings.margin * window.scale_factor() as f32) as u32;
+    let side = ((width.min(height) as f32 * settings.size) as u32)
+        .min(width.saturating_sub(margin * 2))
+        .min(height.saturating_sub(margin * 2));
+    let left = margin;
+    let right = width.saturating_sub(side + margin);
+    let top = margin;
+    let bottom = height.saturating_sub(side + margin);
+    let physical_position = match settings.corner {
+        MinimapCorner::TopLeft => UVec2::new(left, top),
+        MinimapCorner::TopRight => UVec2::new(right, top),
+        MinimapCorner::BottomLeft => UVec2::new(left, bottom),
+        MinimapCorner::BottomRight => UVec2::new(right, bottom),
+    };
+    Viewport {
+        physical_position,
+        physical_size: UVec2::splat(side),
+        ..Default::default()
+    }
+}
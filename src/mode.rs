@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+
+use crate::target::ModeBlend;
+use crate::{FlyCam, MovementSettings};
+
+/// Which control scheme a flycam is using. Added automatically to every [`FlyCam`];
+/// change it, or send [`SetFlyCamMode`], to switch schemes at runtime.
+/// Every switch is announced with a [`ModeChanged`] event.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum FlyCamMode {
+    /// Free first-person flight
+    #[default]
+    Fly,
+    /// First-person movement that stays level, with no flying up or down
+    Walk,
+    /// Turns around a focus point in front of the camera, see [`OrbitSettings`](crate::OrbitSettings)
+    Orbit,
+    /// Ignores input, but still glides to wherever code moves its
+    /// [`FlyCamTarget`](crate::FlyCamTarget)
+    Cinematic,
+    /// Doesn't move at all
+    Frozen,
+    /// Top-down strategy camera, see [`RtsSettings`](crate::RtsSettings)
+    Rts,
+    /// Quadcopter that leans into its movement, see [`DroneSettings`](crate::DroneSettings)
+    Drone,
+    /// A/D steer and W/S work the throttle, see [`VehicleSettings`](crate::VehicleSettings)
+    Vehicle,
+    /// Free flight around a small planet, with up pointing away from its middle.
+    /// See [`PlanetSettings`](crate::PlanetSettings).
+    Planet,
+    /// Follows a [`Spectatable`](crate::Spectatable) entity around, see
+    /// [`FlyCamSpectator`](crate::FlyCamSpectator)
+    Spectate,
+    /// Level first-person movement that keeps a constant height above the ground, see
+    /// [`TerrainSettings`](crate::TerrainSettings)
+    Terrain,
+    /// Navigated with nothing but a mouse, for kiosks and viewers, see
+    /// [`MouseOnlySettings`](crate::MouseOnlySettings)
+    MouseOnly,
+}
+
+impl FlyCamMode {
+    /// Every mode, in the order they're listed in
+    pub const ALL: [FlyCamMode; 12] = [
+        FlyCamMode::Fly,
+        FlyCamMode::Walk,
+        FlyCamMode::Orbit,
+        FlyCamMode::Cinematic,
+        FlyCamMode::Frozen,
+        FlyCamMode::Rts,
+        FlyCamMode::Drone,
+        FlyCamMode::Vehicle,
+        FlyCamMode::Planet,
+        FlyCamMode::Spectate,
+        FlyCamMode::Terrain,
+        FlyCamMode::MouseOnly,
+    ];
+
+    /// Whether the usual first-person controls drive the camera in this mode
+    pub(crate) fn uses_fly_controls(&self) -> bool {
+        matches!(
+            self,
+            FlyCamMode::Fly | FlyCamMode::Walk | FlyCamMode::Planet | FlyCamMode::Terrain
+        )
+    }
+}
+
+/// Send this to switch flycams to another mode
+#[derive(Clone, Copy, Debug)]
+pub struct SetFlyCamMode {
+    /// The flycam to switch, or `None` for all of them
+    pub camera: Option<Entity>,
+    pub mode: FlyCamMode,
+}
+
+/// Sent whenever a flycam switches from one mode to another, however it was switched
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ModeChanged {
+    pub camera: Entity,
+    pub from: FlyCamMode,
+    pub to: FlyCamMode,
+}
+
+/// The mode a flycam was last seen in, to tell what it switched from
+#[derive(Component)]
+pub(crate) struct PreviousMode(pub(crate) FlyCamMode);
+
+/// Switches modes as asked by [`SetFlyCamMode`] events
+pub(crate) fn set_mode(
+    mut requests: EventReader<SetFlyCamMode>,
+    mut query: Query<(Entity, &mut FlyCamMode), With<FlyCam>>,
+) {
+    for request in requests.iter() {
+        for (entity, mut mode) in query.iter_mut() {
+            if request.camera.is_none_or(|camera| camera == entity) && *mode != request.mode {
+                *mode = request.mode;
+            }
+        }
+    }
+}
+
+/// Sends [`ModeChanged`] for every flycam whose mode changed since it last looked,
+/// and starts blending over to the new mode
+#[allow(clippy::type_complexity)]
+pub(crate) fn announce_mode_changes(
+    settings: Res<MovementSettings>,
+    mut events: EventWriter<ModeChanged>,
+    mut query: Query<
+        (
+            Entity,
+            &Transform,
+            &FlyCamMode,
+            &mut PreviousMode,
+            Option<&mut ModeBlend>,
+        ),
+        Changed<FlyCamMode>,
+    >,
+) {
+    for (camera, transform, mode, mut previous, blend) in query.iter_mut() {
+        if previous.0 != *mode {
+            if let Some(mut blend) = blend {
+                if settings.mode_blend_time > 0. {
+                    blend.start(transform);
+                }
+            }
+            events.send(ModeChanged {
+                camera,
+                from: previous.0,
+                to: *mode,
+            });
+            previous.0 = *mode;
+        }
+    }
+}
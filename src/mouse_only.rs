@@ -0,0 +1,98 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::prelude::*;
+
+use crate::rotation::wrap_angle;
+use crate::{
+    cursor_grabbed, edge_scroll, wheel_notches, FlyCam, FlyCamInputBlockers, FlyCamMode,
+    FlyCamTarget, FlyCamTime, MovementSettings, OrbitSettings, OrbitState,
+};
+
+/// Settings for [`FlyCamMode::MouseOnly`], which needs nothing but a mouse. Distance limits
+/// and scroll zoom come from [`OrbitSettings`].
+pub struct MouseOnlySettings {
+    /// Width in pixels of the window border that pans the camera. 0 disables edge panning.
+    pub edge_scroll_margin: f32,
+    /// Edge panning speed, in multiples of the current distance per second
+    pub pan_speed: f32,
+    /// How much dragging with the right button changes the distance, per pixel
+    pub dolly_sensitivity: f32,
+}
+
+impl Default for MouseOnlySettings {
+    fn default() -> Self {
+        Self {
+            edge_scroll_margin: 20.,
+            pan_speed: 0.5,
+            dolly_sensitivity: 0.005,
+        }
+    }
+}
+
+/// Pans the focus point from the edges of the window, turns around it while the left button is
+/// dragged and dollies towards it while the right one is, for kiosks and viewers with no
+/// keyboard. Scrolling zooms too.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn mouse_only_camera(
+    buttons: Res<Input<MouseButton>>,
+    time: Res<FlyCamTime>,
+    windows: Res<Windows>,
+    settings: Res<MovementSettings>,
+    orbit_settings: Res<OrbitSettings>,
+    mouse_only_settings: Res<MouseOnlySettings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    mut query: Query<(&mut FlyCamTarget, &FlyCamMode, &mut OrbitState), With<FlyCam>>,
+) {
+    let motion: Vec2 = mouse_motion_events.iter().map(|ev| &ev.delta).sum();
+    let scroll = wheel_notches(&windows, &mut mouse_wheel_events).y;
+    let blocked = blockers.blocked();
+    let orbiting = buttons.pressed(MouseButton::Left) && !blocked.look;
+    let dollying = buttons.pressed(MouseButton::Right) && !blocked.movement;
+    let window = windows.get_primary();
+    let window_scale = window.map_or(0., |window| window.height().min(window.width()));
+    // A grabbed cursor isn't anywhere near the edges, and panning mid-drag would fight the drag.
+    let edge = match window {
+        Some(window) if !cursor_grabbed(window) && !orbiting && !dollying && !blocked.movement => {
+            edge_scroll(&windows, mouse_only_settings.edge_scroll_margin)
+        }
+        _ => Vec2::ZERO,
+    };
+    let scroll = if blocked.scroll { 0. } else { scroll };
+    let dt = time.delta_seconds();
+
+    for (mut target, mode, mut state) in query.iter_mut() {
+        if *mode != FlyCamMode::MouseOnly {
+            continue;
+        }
+        if orbiting {
+            state.yaw = wrap_angle(
+                state.yaw - (settings.sensitivity * motion.x * window_scale).to_radians(),
+            );
+            state.pitch = (state.pitch
+                - (settings.sensitivity * motion.y * window_scale).to_radians())
+            .clamp(-FRAC_PI_2 + 0.01, FRAC_PI_2 - 0.01);
+        }
+        let mut zoom = scroll * orbit_settings.zoom_sensitivity;
+        if dollying {
+            // Dragging up dollies in, like pushing the camera forward.
+            zoom -= motion.y * mouse_only_settings.dolly_sensitivity;
+        }
+        state.distance = (state.distance * (1. - zoom))
+            .clamp(orbit_settings.min_distance, orbit_settings.max_distance);
+
+        // Edges pan along the ground, so the camera doesn't sink into it when looking down.
+        let yaw = Quat::from_rotation_y(state.yaw);
+        let pan = (yaw * -Vec3::Z * edge.y + yaw * Vec3::X * edge.x).normalize_or_zero()
+            * mouse_only_settings.pan_speed
+            * state.distance
+            * dt;
+        state.focus += pan;
+
+        let rotation = Quat::from_euler(EulerRot::YXZ, state.yaw, state.pitch, 0.);
+        target.rotation = rotation;
+        target.translation = state.focus + rotation * Vec3::Z * state.distance;
+    }
+}
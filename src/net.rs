@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+
+use crate::target::smoothing_blend;
+use crate::{FlyCam, FlyCamTime, FlyCamVelocity};
+
+/// A flycam's pose and motion in a few floats, for sending over the network.
+///
+/// Add it to a local flycam and it's kept up to date every frame; send it however you like
+/// (it's serde-serializable with the `serialize` feature) and hand it to a [`FlyCamNetProxy`]
+/// on the other end.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlyCamNetState {
+    pub translation: Vec3,
+    /// Rotation around the global y axis, in radians
+    pub yaw: f32,
+    /// Tilt up (positive) or down, in radians
+    pub pitch: f32,
+    /// Units per second, see [`FlyCamVelocity`]
+    pub velocity: Vec3,
+}
+
+impl FlyCamNetState {
+    /// The state of a camera with this transform and velocity. Roll isn't sent.
+    pub fn new(transform: &Transform, velocity: Vec3) -> Self {
+        let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+        Self {
+            translation: transform.translation,
+            yaw,
+            pitch,
+            velocity,
+        }
+    }
+
+    /// The rotation the yaw and pitch describe
+    pub fn rotation(&self) -> Quat {
+        Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, 0.)
+    }
+}
+
+/// Moves an entity, usually a camera or an avatar standing in for somebody else's flycam,
+/// along with the [`FlyCamNetState`]s received for it. Between updates it carries on at the
+/// last known velocity, and it eases towards each new state instead of jumping. Don't put it on
+/// a [`FlyCam`], which steers itself.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct FlyCamNetProxy {
+    /// The latest state received
+    pub state: FlyCamNetState,
+    /// Time constant in seconds for easing towards the state. 0 jumps straight there.
+    pub smoothing: f32,
+    /// Seconds since `state` was received
+    pub age: f32,
+}
+
+impl FlyCamNetProxy {
+    pub fn new(state: FlyCamNetState) -> Self {
+        Self {
+            state,
+            smoothing: 0.1,
+            age: 0.,
+        }
+    }
+
+    /// Takes a newly received state
+    pub fn receive(&mut self, state: FlyCamNetState) {
+        self.state = state;
+        self.age = 0.;
+    }
+
+    /// Where the camera should be by now: the last known position carried on at its velocity
+    pub fn predicted_translation(&self) -> Vec3 {
+        self.state.translation + self.state.velocity * self.age
+    }
+}
+
+/// Copies each local flycam's pose and velocity into its [`FlyCamNetState`]
+pub(crate) fn author_net_state(
+    mut query: Query<(&Transform, Option<&FlyCamVelocity>, &mut FlyCamNetState), With<FlyCam>>,
+) {
+    for (transform, velocity, mut state) in query.iter_mut() {
+        let new = FlyCamNetState::new(transform, velocity.map_or(Vec3::ZERO, |v| v.0));
+        if *state != new {
+            *state = new;
+        }
+    }
+}
+
+/// Eases each [`FlyCamNetProxy`] towards where its camera is predicted to be
+pub(crate) fn apply_net_state(
+    time: Res<FlyCamTime>,
+    mut query: Query<(&mut Transform, &mut FlyCamNetProxy)>,
+) {
+    let dt = time.delta_seconds();
+    for (mut transform, mut proxy) in query.iter_mut() {
+        proxy.age += dt;
+        let blend = smoothing_blend(proxy.smoothing, dt);
+        let translation = transform
+            .translation
+            .lerp(proxy.predicted_translation(), blend);
+        let rotation = transform.rotation.slerp(proxy.state.rotation(), blend);
+        if translation != transform.translation || rotation != transform.rotation {
+            transform.translation = translation;
+            transform.rotation = rotation;
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::prelude::*;
+
+use crate::rotation::wrap_angle;
+use crate::{
+    cursor_grabbed, pressed, wheel_notches, FlyCam, FlyCamInputBlockers, FlyCamKeys, FlyCamMode,
+    FlyCamTarget, FlyCamTime, KeyBindings, MovementSettings,
+};
+
+/// Settings for [`FlyCamMode::Orbit`], which turns the camera around a focus point
+/// with the mouse like a model viewer
+pub struct OrbitSettings {
+    /// How far in front of the camera the focus point is picked when switching to orbit mode
+    pub distance: f32,
+    /// Closest the camera can get to its focus point
+    pub min_distance: f32,
+    /// Furthest the camera can get from its focus point
+    pub max_distance: f32,
+    /// How much each scroll step changes the distance
+    pub zoom_sensitivity: f32,
+    /// Panning speed, in multiples of the current distance per second
+    pub pan_speed: f32,
+    /// Turn with a virtual trackball instead of yaw and pitch: dragging rolls a ball under the
+    /// cursor, so the view can tumble freely, roll included, like in model viewers
+    pub trackball: bool,
+}
+
+impl Default for OrbitSettings {
+    fn default() -> Self {
+        Self {
+            distance: 10.,
+            min_distance: 0.5,
+            max_distance: 1000.,
+            zoom_sensitivity: 0.1,
+            pan_speed: 1.,
+            trackball: false,
+        }
+    }
+}
+
+/// What an orbit camera is turning around. Added when a flycam switches to [`FlyCamMode::Orbit`]
+/// or [`FlyCamMode::MouseOnly`].
+#[derive(Component, Clone, Copy, Debug)]
+pub struct OrbitState {
+    /// The point the camera looks at and turns around
+    pub focus: Vec3,
+    /// Distance from the focus point
+    pub distance: f32,
+    /// Rotation around the global y axis, in radians
+    pub yaw: f32,
+    /// Tilt in radians, negative to look down on the focus point from above
+    pub pitch: f32,
+    /// Roll in radians, only ever changed by [`OrbitSettings::trackball`]
+    pub roll: f32,
+}
+
+/// Picks a focus point in front of the camera when orbit or mouse-only mode is switched on
+#[allow(clippy::type_complexity)]
+pub(crate) fn enter_orbit(
+    mut commands: Commands,
+    settings: Res<OrbitSettings>,
+    query: Query<(Entity, &Transform, &FlyCamMode), (With<FlyCam>, Changed<FlyCamMode>)>,
+) {
+    for (entity, transform, mode) in query.iter() {
+        if !matches!(mode, FlyCamMode::Orbit | FlyCamMode::MouseOnly) {
+            continue;
+        }
+        let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+        commands.entity(entity).insert(OrbitState {
+            focus: transform.translation + transform.forward() * settings.distance,
+            distance: settings.distance,
+            yaw,
+            pitch,
+            roll: 0.,
+        });
+    }
+}
+
+/// Where the cursor at `position` touches a ball filling the middle of a window `size` pixels
+/// big, in view space. Past the edge of the ball it slides onto a hyperbola, so dragging around
+/// the outside rolls the view smoothly.
+fn trackball_point(position: Vec2, size: Vec2) -> Vec3 {
+    let point = (position - size / 2.) / (size.min_element() / 2.).max(1.);
+    let squared = point.length_squared();
+    let z = if squared <= 0.5 {
+        (1. - squared).sqrt()
+    } else {
+        0.5 / squared.sqrt()
+    };
+    point.extend(z).normalize()
+}
+
+/// How far the camera turns for the cursor moving by `motion` in a window, with the virtual
+/// trackball. A grabbed cursor counts as staying in the middle.
+fn trackball_turn(window: &Window, motion: Vec2, grabbed: bool) -> Quat {
+    let size = Vec2::new(window.width(), window.height());
+    let end = match window.cursor_position() {
+        Some(position) if !grabbed => position,
+        _ => size / 2.,
+    };
+    // Mouse motion is measured downwards, cursor positions upwards.
+    let start = end - Vec2::new(motion.x, -motion.y);
+    // The ball turns the scene along with the cursor, so the camera turns the other way.
+    Quat::from_rotation_arc(trackball_point(start, size), trackball_point(end, size)).inverse()
+}
+
+/// Turns around the focus point with the mouse, zooms with scroll and pans the focus point
+/// with the movement keys
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn orbit_camera(
+    keys: Res<FlyCamKeys>,
+    buttons: Res<Input<MouseButton>>,
+    time: Res<FlyCamTime>,
+    windows: Res<Windows>,
+    settings: Res<MovementSettings>,
+    orbit_settings: Res<OrbitSettings>,
+    bindings: Res<KeyBindings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    mut query: Query<(&mut FlyCamTarget, &FlyCamMode, &mut OrbitState), With<FlyCam>>,
+) {
+    let blocked = blockers.blocked();
+    let mut motion = if blocked.look {
+        mouse_motion_events.clear();
+        Vec2::ZERO
+    } else {
+        mouse_motion_events.iter().map(|ev| &ev.delta).sum()
+    };
+    let scroll = if blocked.scroll {
+        mouse_wheel_events.clear();
+        0.
+    } else {
+        wheel_notches(&windows, &mut mouse_wheel_events).y
+    };
+    let mut trackball = None;
+    let window_scale = match windows.get_primary() {
+        Some(window) => {
+            let please_move =
+                buttons.pressed(MouseButton::Left) || buttons.pressed(MouseButton::Right);
+            let grabbed = cursor_grabbed(window);
+            if !grabbed && !please_move {
+                motion = Vec2::ZERO;
+            }
+            if orbit_settings.trackball && motion != Vec2::ZERO {
+                trackball = Some(trackball_turn(window, motion, grabbed));
+            }
+            window.height().min(window.width())
+        }
+        None => 0.,
+    };
+    let dt = time.delta_seconds();
+    let bindings = bindings.for_mode(FlyCamMode::Orbit);
+
+    let mut direction = Vec3::ZERO;
+    if pressed(&keys, &bindings.move_left) {
+        direction.x -= 1.;
+    }
+    if pressed(&keys, &bindings.move_right) {
+        direction.x += 1.;
+    }
+    if pressed(&keys, &bindings.move_ascend) {
+        direction.y += 1.;
+    }
+    if pressed(&keys, &bindings.move_descend) {
+        direction.y -= 1.;
+    }
+    if pressed(&keys, &bindings.move_forward) {
+        direction.z -= 1.;
+    }
+    if pressed(&keys, &bindings.move_backward) {
+        direction.z += 1.;
+    }
+    if blocked.movement {
+        direction = Vec3::ZERO;
+    }
+    let direction = direction.normalize_or_zero();
+
+    for (mut target, mode, mut state) in query.iter_mut() {
+        if *mode != FlyCamMode::Orbit {
+            continue;
+        }
+        if let Some(turn) = trackball {
+            let rotation =
+                Quat::from_euler(EulerRot::YXZ, state.yaw, state.pitch, state.roll) * turn;
+            (state.yaw, state.pitch, state.roll) = rotation.normalize().to_euler(EulerRot::YXZ);
+        } else if !orbit_settings.trackball {
+            state.yaw = wrap_angle(
+                state.yaw - (settings.sensitivity * motion.x * window_scale).to_radians(),
+            );
+            state.pitch = (state.pitch
+                - (settings.sensitivity * motion.y * window_scale).to_radians())
+            .clamp(-FRAC_PI_2 + 0.01, FRAC_PI_2 - 0.01);
+        }
+        state.distance = (state.distance * (1. - scroll * orbit_settings.zoom_sensitivity))
+            .clamp(orbit_settings.min_distance, orbit_settings.max_distance);
+
+        let rotation = Quat::from_euler(EulerRot::YXZ, state.yaw, state.pitch, state.roll);
+        let pan = rotation * direction * orbit_settings.pan_speed * state.distance * dt;
+        state.focus += pan;
+
+        target.rotation = rotation;
+        target.translation = state.focus + rotation * Vec3::Z * state.distance;
+    }
+}
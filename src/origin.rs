@@ -0,0 +1,128 @@
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+use crate::target::ModeBlend;
+use crate::{
+    FlyCam, FlyCamPrecisePosition, FlyCamTarget, OrbitState, PlanetSettings, RtsState, WalkState,
+};
+
+/// Settings for the floating origin added by [`FlyCamOriginPlugin`]
+pub struct FlyCamOriginSettings {
+    /// How far a flycam can get from the origin before the world is moved back under it
+    pub threshold: f32,
+}
+
+impl Default for FlyCamOriginSettings {
+    fn default() -> Self {
+        Self { threshold: 5_000. }
+    }
+}
+
+/// Where the current origin is in the coordinates the world started out in, adding up every
+/// [`OriginShift`] so far. Add it to a position to get back to the original coordinates.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FlyCamOrigin {
+    pub offset: DVec3,
+}
+
+/// Sent when the world is moved back under a flycam: every position should have this
+/// taken away from it. Flycams and entities marked with [`FlyCamShiftWithOrigin`] have
+/// already been moved; anything else that keeps world positions should listen for it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OriginShift(pub Vec3);
+
+/// Marks entities that move along with the origin, so they stay put relative to the flycam.
+/// Only mark entities without a parent; children move with their parents.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct FlyCamShiftWithOrigin;
+
+/// Keeps positions near the origin in huge worlds, where f32 loses precision further out and
+/// the view starts to jitter. Once a flycam strays [`FlyCamOriginSettings::threshold`] from
+/// the origin, it's moved back there together with every entity marked
+/// [`FlyCamShiftWithOrigin`], and an [`OriginShift`] is sent.
+///
+/// Add it alongside [`PlayerPlugin`](crate::PlayerPlugin) or
+/// [`NoCameraPlayerPlugin`](crate::NoCameraPlayerPlugin).
+pub struct FlyCamOriginPlugin;
+impl Plugin for FlyCamOriginPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlyCamOriginSettings>()
+            .init_resource::<FlyCamOrigin>()
+            .add_event::<OriginShift>()
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                shift_origin
+                    .after(crate::target::follow_target)
+                    .after(crate::precise::sync_precise_position)
+                    .before(crate::net::author_net_state)
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// Moves the world back under the first flycam that's gone past the threshold
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn shift_origin(
+    settings: Res<FlyCamOriginSettings>,
+    mut origin: ResMut<FlyCamOrigin>,
+    mut planet: ResMut<PlanetSettings>,
+    mut shifts: EventWriter<OriginShift>,
+    mut flycams: Query<
+        (
+            &mut Transform,
+            &mut FlyCamTarget,
+            Option<&mut ModeBlend>,
+            Option<&mut OrbitState>,
+            Option<&mut RtsState>,
+            Option<&mut WalkState>,
+            Option<&mut FlyCamPrecisePosition>,
+        ),
+        With<FlyCam>,
+    >,
+    mut marked: Query<
+        &mut Transform,
+        (
+            With<FlyCamShiftWithOrigin>,
+            Without<FlyCam>,
+            Without<Parent>,
+        ),
+    >,
+) {
+    let shift = match flycams
+        .iter()
+        .map(|(transform, ..)| transform.translation)
+        .find(|translation| translation.length() > settings.threshold)
+    {
+        Some(shift) => shift,
+        None => return,
+    };
+
+    for (mut transform, mut target, blend, orbit, rts, walk, precise) in flycams.iter_mut() {
+        transform.translation -= shift;
+        target.shift(shift);
+        if let Some(mut blend) = blend {
+            blend.shift(shift);
+        }
+        if let Some(mut orbit) = orbit {
+            orbit.focus -= shift;
+        }
+        if let Some(mut rts) = rts {
+            rts.focus -= shift;
+        }
+        if let Some(mut walk) = walk {
+            if let Some(floor) = walk.floor.as_mut() {
+                *floor -= shift.y;
+            }
+        }
+        if let Some(mut precise) = precise {
+            precise.shift(shift);
+        }
+    }
+    for mut transform in marked.iter_mut() {
+        transform.translation -= shift;
+    }
+    planet.center -= shift;
+    origin.offset += shift.as_dvec3();
+    shifts.send(OriginShift(shift));
+}
@@ -0,0 +1,192 @@
+use bevy::prelude::*;
+
+use crate::rotation::FlyCamFrame;
+use crate::{FlyCamKeys, FlyCamRotation, FlyCamTarget, FlyCamTime, FlyCamWaypoints};
+
+/// Add to a flycam to fly it along the smooth path through [`FlyCamWaypoints`], from the first
+/// waypoint to the last over [`duration`](Self::duration) seconds, looking where they look.
+/// It ignores other input until it's done, then leaves the camera at the end and sends a
+/// [`PathPlaybackFinished`].
+///
+/// Pause it, change its rate and scrub through it from code, or with the keys in
+/// [`FlyCamPathPlaybackSettings`], to build simple sequences on top.
+#[derive(Component, Clone, Debug)]
+pub struct FlyCamPathPlayback {
+    pub waypoints: FlyCamWaypoints,
+    /// Seconds from the first waypoint to the last at a rate of 1
+    pub duration: f32,
+    /// How fast it plays, e.g. 0.5 for half speed. Negative plays it backwards.
+    pub rate: f32,
+    /// Whether it starts again from the other end instead of finishing
+    pub looping: bool,
+    time: f32,
+    paused: bool,
+    finished: bool,
+}
+
+impl FlyCamPathPlayback {
+    pub fn new(waypoints: FlyCamWaypoints, duration: f32) -> Self {
+        Self {
+            waypoints,
+            duration,
+            rate: 1.,
+            looping: false,
+            time: 0.,
+            paused: false,
+            finished: false,
+        }
+    }
+
+    /// Seconds into the path it's got to
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// How far along the path it's got to, from 0 to 1
+    pub fn progress(&self) -> f32 {
+        if self.duration > 0. {
+            self.time / self.duration
+        } else {
+            1.
+        }
+    }
+
+    /// Jumps to `time` seconds into the path, carrying on from there even if it had finished
+    pub fn seek(&mut self, time: f32) {
+        self.time = time.clamp(0., self.duration.max(0.));
+        self.finished = false;
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Carries on after [`FlyCamPathPlayback::pause`]
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Whether it's reached the end, and has let go of the camera
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// The pose at the current time
+    fn pose(&self) -> Option<(Vec3, Quat)> {
+        let segments = self.waypoints.waypoints.len().saturating_sub(1) as f32;
+        let waypoint = self.waypoints.sample(self.progress() * segments)?;
+        Some((waypoint.translation, waypoint.rotation))
+    }
+}
+
+/// Sent when a [`FlyCamPathPlayback`] reaches the end of its path
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PathPlaybackFinished {
+    pub camera: Entity,
+}
+
+/// Keys that control every [`FlyCamPathPlayback`], all unbound by default
+pub struct FlyCamPathPlaybackSettings {
+    /// Keys that pause playback, and carry on again
+    pub toggle_pause: Vec<KeyCode>,
+    /// Keys that multiply the rate by `rate_step`
+    pub faster: Vec<KeyCode>,
+    /// Keys that divide the rate by `rate_step`
+    pub slower: Vec<KeyCode>,
+    pub rate_step: f32,
+    /// Keys that scrub forwards through the path while held, paused or not
+    pub scrub_forward: Vec<KeyCode>,
+    /// Keys that scrub backwards through the path while held, paused or not
+    pub scrub_back: Vec<KeyCode>,
+    /// Seconds of path scrubbed per second the scrub keys are held
+    pub scrub_speed: f32,
+}
+
+impl Default for FlyCamPathPlaybackSettings {
+    fn default() -> Self {
+        Self {
+            toggle_pause: Vec::new(),
+            faster: Vec::new(),
+            slower: Vec::new(),
+            rate_step: 2.,
+            scrub_forward: Vec::new(),
+            scrub_back: Vec::new(),
+            scrub_speed: 4.,
+        }
+    }
+}
+
+/// Moves each path playback along its path, taking over the camera's target
+pub(crate) fn play_paths(
+    keys: Res<FlyCamKeys>,
+    time: Res<FlyCamTime>,
+    settings: Res<FlyCamPathPlaybackSettings>,
+    mut finished: EventWriter<PathPlaybackFinished>,
+    mut query: Query<(
+        Entity,
+        &mut FlyCamPathPlayback,
+        &mut FlyCamTarget,
+        &mut FlyCamRotation,
+        Option<&FlyCamFrame>,
+    )>,
+) {
+    let dt = time.delta_seconds();
+    let toggle_pause = keys.any_just_pressed(settings.toggle_pause.iter().copied());
+    let mut rate_change = 1.;
+    if keys.any_just_pressed(settings.faster.iter().copied()) {
+        rate_change *= settings.rate_step;
+    }
+    if keys.any_just_pressed(settings.slower.iter().copied()) && settings.rate_step != 0. {
+        rate_change /= settings.rate_step;
+    }
+    let mut scrub = 0.;
+    if keys.any_pressed(settings.scrub_forward.iter().copied()) {
+        scrub += settings.scrub_speed * dt;
+    }
+    if keys.any_pressed(settings.scrub_back.iter().copied()) {
+        scrub -= settings.scrub_speed * dt;
+    }
+
+    for (camera, mut playback, mut target, mut rotation, frame) in query.iter_mut() {
+        if toggle_pause {
+            playback.paused = !playback.paused;
+        }
+        if rate_change != 1. {
+            playback.rate *= rate_change;
+        }
+        if scrub != 0. {
+            let time = playback.time + scrub;
+            playback.seek(time);
+        }
+        if playback.finished {
+            continue;
+        }
+        let duration = playback.duration.max(0.);
+        if !playback.paused {
+            let mut time = playback.time + dt * playback.rate;
+            let past_end = if playback.rate >= 0. {
+                time >= duration
+            } else {
+                time <= 0.
+            };
+            if past_end && playback.looping && duration > 0. {
+                time = time.rem_euclid(duration);
+            } else if past_end {
+                time = time.clamp(0., duration);
+                playback.finished = true;
+                finished.send(PathPlaybackFinished { camera });
+            }
+            playback.time = time;
+        }
+        if let Some((translation, view)) = playback.pose() {
+            target.translation = translation;
+            target.rotation = view;
+            let frame = frame.map_or(Quat::IDENTITY, |frame| frame.0);
+            rotation.set_view(frame.inverse() * view);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+
+use crate::{FlyCamKeys, FlyCamRecorder, FlyCamSchedule, FlyCamSystem, FlyCamTarget};
+
+/// Settings for the paths drawn by [`FlyCamPathsPlugin`]
+pub struct FlyCamPathsSettings {
+    /// Keys that show and hide the paths
+    pub toggle: Vec<KeyCode>,
+    /// Whether the paths are shown when the game starts
+    pub visible: bool,
+    /// Colour of the paths, with the `debug-lines` feature
+    pub color: Color,
+}
+
+impl Default for FlyCamPathsSettings {
+    fn default() -> Self {
+        Self {
+            toggle: vec![KeyCode::F5],
+            visible: true,
+            color: Color::CYAN,
+        }
+    }
+}
+
+/// Camera paths to show in the world while authoring them, each a line through a list of
+/// points under a name. [`FlyCamPathsPlugin`] keeps the path of every flycam that's being
+/// recorded here, as `"recording <entity>"`; add your own rails and boundaries alongside.
+#[derive(Clone, Debug, Default)]
+pub struct FlyCamPaths {
+    paths: BTreeMap<String, Vec<Vec3>>,
+    visible: bool,
+}
+
+impl FlyCamPaths {
+    /// Shows a line through `points` as `name`, replacing any path already called that
+    pub fn set(&mut self, name: impl Into<String>, points: Vec<Vec3>) {
+        self.paths.insert(name.into(), points);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Vec<Vec3>> {
+        self.paths.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&[Vec3]> {
+        self.paths.get(name).map(Vec::as_slice)
+    }
+
+    /// Every path, by name
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[Vec3])> {
+        self.paths
+            .iter()
+            .map(|(name, points)| (name.as_str(), points.as_slice()))
+    }
+
+    /// Whether the toggle keys have the paths showing
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Every segment of every path, for drawing them with your own lines
+    pub fn segments(&self) -> impl Iterator<Item = (Vec3, Vec3)> + '_ {
+        self.paths
+            .values()
+            .flat_map(|points| points.windows(2).map(|pair| (pair[0], pair[1])))
+    }
+}
+
+/// Adds [`FlyCamPaths`], for seeing camera paths in the world while authoring them rather than
+/// in some other tool. It traces each flycam with a [`FlyCamRecorder`] as it records, and the
+/// toggle key shows and hides every path.
+///
+/// With the `debug-lines` feature it draws the paths too. Otherwise draw
+/// [`FlyCamPaths::segments`] with whatever lines you use while they're visible.
+///
+/// Add it alongside [`PlayerPlugin`](crate::PlayerPlugin) or
+/// [`NoCameraPlayerPlugin`](crate::NoCameraPlayerPlugin).
+pub struct FlyCamPathsPlugin;
+impl Plugin for FlyCamPathsPlugin {
+    fn build(&self, app: &mut App) {
+        let stage = FlyCamSchedule::stage(app);
+        app.init_resource::<FlyCamPathsSettings>();
+        let visible = app.world.resource::<FlyCamPathsSettings>().visible;
+        app.insert_resource(FlyCamPaths {
+            visible,
+            ..default()
+        })
+        .add_system_to_stage(stage, toggle_paths.after(FlyCamSystem::Control))
+        .add_system_to_stage(stage, trace_recordings.after(FlyCamSystem::Control));
+        #[cfg(feature = "debug-lines")]
+        app.add_system_to_stage(CoreStage::PostUpdate, draw_paths);
+    }
+}
+
+/// Shows or hides the paths
+fn toggle_paths(
+    keys: Res<FlyCamKeys>,
+    settings: Res<FlyCamPathsSettings>,
+    mut paths: ResMut<FlyCamPaths>,
+) {
+    if keys.any_just_pressed(settings.toggle.iter().copied()) {
+        paths.visible = !paths.visible;
+    }
+}
+
+/// Extends the path of each recording flycam to where it's got to
+fn trace_recordings(
+    mut paths: ResMut<FlyCamPaths>,
+    query: Query<(Entity, &FlyCamTarget, ChangeTrackers<FlyCamRecorder>), With<FlyCamRecorder>>,
+) {
+    for (entity, target, recorder) in query.iter() {
+        let name = format!("recording {entity:?}");
+        if recorder.is_added() {
+            paths.set(name, vec![target.translation]);
+        } else if paths.get(&name).and_then(|points| points.last()) != Some(&target.translation) {
+            paths
+                .paths
+                .entry(name)
+                .or_default()
+                .push(target.translation);
+        }
+    }
+}
+
+/// Redraws the paths whenever they change
+#[cfg(feature = "debug-lines")]
+fn draw_paths(
+    mut commands: Commands,
+    paths: Res<FlyCamPaths>,
+    settings: Res<FlyCamPathsSettings>,
+    meshes: Option<ResMut<Assets<Mesh>>>,
+    materials: Option<ResMut<Assets<StandardMaterial>>>,
+    mut drawn: Local<Option<Entity>>,
+) {
+    // Only apps that render have meshes to draw with.
+    let (mut meshes, mut materials) = match (meshes, materials) {
+        (Some(meshes), Some(materials)) => (meshes, materials),
+        _ => return,
+    };
+    if !paths.is_changed() {
+        return;
+    }
+    if let Some(lines) = drawn.take() {
+        commands.entity(lines).despawn();
+    }
+    if paths.visible {
+        let lines = commands
+            .spawn_bundle(PbrBundle {
+                mesh: meshes.add(crate::lines::line_mesh(paths.segments())),
+                material: materials.add(crate::lines::line_material(settings.color)),
+                ..default()
+            })
+            .id();
+        *drawn = Some(lines);
+    }
+}
@@ -0,0 +1,238 @@
+use bevy::core_pipeline::core_3d::Camera3d;
+use bevy::ecs::schedule::ShouldRun;
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+
+use crate::{
+    FlyCam, FlyCamAutofocus, FlyCamInputBlockers, FlyCamMinimap, FlyCamMode, FlyCamSchedule,
+    FlyCamTarget, FlyCamTime, FocusDistance,
+};
+
+/// Settings for the photo mode added by [`FlyCamPhotoPlugin`]. Angles are in radians.
+pub struct FlyCamPhotoSettings {
+    /// Keys that go into and out of photo mode
+    pub toggle: Vec<KeyCode>,
+    /// Keys that take the shot, sending [`PhotoTaken`]
+    pub confirm: Vec<KeyCode>,
+    /// Keys that widen the field of view while held
+    pub fov_wider: Vec<KeyCode>,
+    /// Keys that narrow the field of view while held
+    pub fov_narrower: Vec<KeyCode>,
+    /// How fast the field of view keys change it, in radians per second
+    pub fov_speed: f32,
+    pub min_fov: f32,
+    pub max_fov: f32,
+    /// Furthest the photo camera can get from where photo mode started
+    pub max_distance: f32,
+}
+
+impl Default for FlyCamPhotoSettings {
+    fn default() -> Self {
+        Self {
+            toggle: vec![KeyCode::F12],
+            confirm: vec![KeyCode::Return],
+            fov_wider: vec![KeyCode::Key9],
+            fov_narrower: vec![KeyCode::Key0],
+            fov_speed: 0.5,
+            min_fov: 10f32.to_radians(),
+            max_fov: 100f32.to_radians(),
+            max_distance: 20.,
+        }
+    }
+}
+
+/// Whether photo mode is on, and which camera it's using
+#[derive(Default)]
+pub struct FlyCamPhotoMode {
+    camera: Option<Entity>,
+    detached_from: Option<(Entity, Option<FlyCamMode>)>,
+}
+
+impl FlyCamPhotoMode {
+    pub fn is_active(&self) -> bool {
+        self.camera.is_some()
+    }
+
+    /// The temporary flycam photo mode is looking through
+    pub fn camera(&self) -> Option<Entity> {
+        self.camera
+    }
+
+    /// Run criterion that stops systems while photo mode is on. Put it on your gameplay
+    /// systems to freeze the game while the player lines up a shot.
+    pub fn run_if_inactive(photo: Res<FlyCamPhotoMode>) -> ShouldRun {
+        if photo.is_active() {
+            ShouldRun::No
+        } else {
+            ShouldRun::Yes
+        }
+    }
+}
+
+/// Sent when the player takes a shot in photo mode
+#[derive(Clone, Copy, Debug)]
+pub struct PhotoTaken {
+    /// The photo mode camera
+    pub camera: Entity,
+    pub transform: Transform,
+    /// Vertical field of view of the shot, in radians
+    pub fov: f32,
+    /// How far away it's focused, if the camera it started from has a
+    /// [`FlyCamAutofocus`]
+    pub focus_distance: Option<f32>,
+}
+
+/// Marks the temporary photo mode flycam
+#[derive(Component)]
+struct PhotoCamera {
+    /// Where photo mode started
+    origin: Vec3,
+}
+
+/// Adds photo mode: the toggle key swaps the active 3D camera for a temporary flycam at the
+/// same view, which flies with the usual controls but can't stray further than
+/// [`FlyCamPhotoSettings::max_distance`]. Roll with the roll keys, change the field of view
+/// with the fov keys and take the shot with the confirm key. Toggling again brings the
+/// original camera back.
+///
+/// Add it alongside [`PlayerPlugin`](crate::PlayerPlugin) or
+/// [`NoCameraPlayerPlugin`](crate::NoCameraPlayerPlugin).
+pub struct FlyCamPhotoPlugin;
+impl Plugin for FlyCamPhotoPlugin {
+    fn build(&self, app: &mut App) {
+        let stage = FlyCamSchedule::stage(app);
+        app.init_resource::<FlyCamPhotoSettings>()
+            .init_resource::<FlyCamPhotoMode>()
+            .add_event::<PhotoTaken>()
+            .add_system_to_stage(stage, toggle_photo_mode)
+            .add_system_to_stage(stage, photo_controls.after(crate::actions::apply_actions));
+    }
+}
+
+/// Detaches a photo flycam from the active 3D camera, or puts the original back
+#[allow(clippy::type_complexity)]
+fn toggle_photo_mode(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    settings: Res<FlyCamPhotoSettings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut photo: ResMut<FlyCamPhotoMode>,
+    mut cameras: Query<
+        (
+            Entity,
+            &mut Camera,
+            &Camera3d,
+            &GlobalTransform,
+            &Projection,
+            Option<&mut FlyCamMode>,
+            Option<&FlyCamAutofocus>,
+        ),
+        (Without<PhotoCamera>, Without<FlyCamMinimap>),
+    >,
+) {
+    if !keys.any_just_pressed(settings.toggle.iter().copied()) || blockers.blocked().hotkeys {
+        return;
+    }
+
+    if let Some(camera) = photo.camera.take() {
+        commands.entity(camera).despawn_recursive();
+        if let Some((original, mode)) = photo.detached_from.take() {
+            if let Ok((_, mut camera, _, _, _, current_mode, _)) = cameras.get_mut(original) {
+                camera.is_active = true;
+                if let (Some(mode), Some(mut current_mode)) = (mode, current_mode) {
+                    *current_mode = mode;
+                }
+            }
+        }
+        return;
+    }
+
+    let original = cameras
+        .iter_mut()
+        .filter(|(_, camera, ..)| camera.is_active)
+        .max_by_key(|(_, camera, ..)| camera.priority);
+    let (entity, mut camera, camera_3d, global, projection, mode, autofocus) = match original {
+        Some(original) => original,
+        None => {
+            warn!("No active 3D camera to start photo mode from");
+            return;
+        }
+    };
+    let transform = global.compute_transform();
+    let mut photo_camera = commands.spawn_bundle(Camera3dBundle {
+        camera: camera.clone(),
+        camera_3d: camera_3d.clone(),
+        projection: projection.clone(),
+        transform,
+        ..Default::default()
+    });
+    photo_camera.insert(FlyCam).insert(PhotoCamera {
+        origin: transform.translation,
+    });
+    if let Some(autofocus) = autofocus {
+        photo_camera.insert(autofocus.clone());
+    }
+    let photo_camera = photo_camera.id();
+
+    camera.is_active = false;
+    // A flycam being detached from would otherwise move along with the photo camera.
+    let mode = mode.map(|mut mode| {
+        let previous = *mode;
+        *mode = FlyCamMode::Frozen;
+        previous
+    });
+    photo.camera = Some(photo_camera);
+    photo.detached_from = Some((entity, mode));
+}
+
+/// Keeps the photo camera in range, changes its field of view and takes the shot
+#[allow(clippy::type_complexity)]
+fn photo_controls(
+    keys: Res<Input<KeyCode>>,
+    time: Res<FlyCamTime>,
+    settings: Res<FlyCamPhotoSettings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut taken: EventWriter<PhotoTaken>,
+    mut query: Query<(
+        Entity,
+        &PhotoCamera,
+        &Transform,
+        &mut FlyCamTarget,
+        &mut Projection,
+        Option<&FocusDistance>,
+    )>,
+) {
+    // Blocked, the camera's still kept in range, but the keys do nothing.
+    let blocked = blockers.blocked().hotkeys;
+    let mut fov_change = 0.;
+    if !blocked && keys.any_pressed(settings.fov_wider.iter().copied()) {
+        fov_change += settings.fov_speed * time.delta_seconds();
+    }
+    if !blocked && keys.any_pressed(settings.fov_narrower.iter().copied()) {
+        fov_change -= settings.fov_speed * time.delta_seconds();
+    }
+
+    for (camera, photo_camera, transform, mut target, mut projection, focus) in query.iter_mut() {
+        let offset = target.translation - photo_camera.origin;
+        if offset.length() > settings.max_distance {
+            target.translation = photo_camera.origin + offset.normalize() * settings.max_distance;
+        }
+
+        let mut fov = 0.;
+        if let Projection::Perspective(perspective) = projection.as_mut() {
+            if fov_change != 0. {
+                perspective.fov = (perspective.fov + fov_change)
+                    .clamp(settings.min_fov, settings.max_fov.max(settings.min_fov));
+            }
+            fov = perspective.fov;
+        }
+        if !blocked && keys.any_just_pressed(settings.confirm.iter().copied()) {
+            taken.send(PhotoTaken {
+                camera,
+                transform: *transform,
+                fov,
+                focus_distance: focus.map(|focus| focus.0),
+            });
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+use bevy_mod_picking::{PausedForBlockers, PickableMesh};
+
+use crate::{BlockedInput, FlyCamInputBlockers, FlyCamSchedule, FlyCamSystem};
+
+/// Holds flycams still while [bevy_mod_picking](bevy_mod_picking) is busy with the mouse:
+/// while a pickable mesh is being clicked or dragged, e.g. a transform gizmo, and while picking
+/// is paused over a `PickingBlocker` such as a UI panel. Mouse look and the movement keys are
+/// ignored until it's done, so dragging doesn't spin the camera too.
+///
+/// Add it alongside [`PlayerPlugin`](crate::PlayerPlugin) or
+/// [`NoCameraPlayerPlugin`](crate::NoCameraPlayerPlugin) and the picking plugins.
+pub struct FlyCamPickingPlugin;
+impl Plugin for FlyCamPickingPlugin {
+    fn build(&self, app: &mut App) {
+        let stage = FlyCamSchedule::stage(app);
+        // Before every mode's controls, not just first-person flight
+        app.add_system_to_stage(stage, block_while_picking.before(FlyCamSystem::Control));
+    }
+}
+
+const SOURCE: &str = "bevy_mod_picking";
+
+/// Blocks look and movement while picking has the mouse
+fn block_while_picking(
+    mut blockers: ResMut<FlyCamInputBlockers>,
+    paused: Option<Res<PausedForBlockers>>,
+    pickables: Query<&Interaction, With<PickableMesh>>,
+) {
+    let picking = paused.is_some_and(|paused| paused.is_paused())
+        || pickables
+            .iter()
+            .any(|interaction| *interaction == Interaction::Clicked);
+    let blocked = BlockedInput {
+        look: picking,
+        movement: picking,
+        ..default()
+    };
+    if blockers.blocked_by(SOURCE) != blocked {
+        blockers.block(SOURCE, blocked);
+    }
+}
@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+
+/// Settings for [`FlyCamMode::Planet`](crate::FlyCamMode::Planet), where up points away
+/// from the middle of a planet so the horizon stays level all the way around it
+#[derive(Clone, Debug, Default)]
+pub struct PlanetSettings {
+    /// Middle of the planet
+    pub center: Vec3,
+}
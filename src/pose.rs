@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+
+use crate::{FlyCam, FlyCamInputBlockers, KeyBindings};
+
+/// Rust code that recreates the pose, ready to paste into a spawn call
+fn transform_snippet(transform: &Transform) -> String {
+    let position = transform.translation;
+    let target = position + transform.forward();
+    format!(
+        "Transform::from_xyz({:.3}, {:.3}, {:.3}).looking_at(Vec3::new({:.3}, {:.3}, {:.3}), Vec3::Y)",
+        position.x, position.y, position.z, target.x, target.y, target.z
+    )
+}
+
+/// `value` with at most `decimals` places and no trailing zeros
+fn short_number(value: f32, decimals: usize) -> String {
+    let text = format!("{value:.decimals$}");
+    let trimmed = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+    match trimmed {
+        "-0" => "0".into(),
+        text => text.into(),
+    }
+}
+
+/// A camera pose as a short string that's safe to put in a URL, for sharing links to an exact
+/// spot in a web demo, e.g. `3.5_2_-10_45_-12.5`: the position to the millimetre, then the
+/// yaw, pitch and roll (left out when it's 0) in degrees to a hundredth of one
+pub fn encode_pose(transform: &Transform) -> String {
+    let (yaw, pitch, roll) = transform.rotation.to_euler(EulerRot::YXZ);
+    let position = transform.translation.to_array().map(|x| short_number(x, 3));
+    let mut angles = [yaw, pitch, roll].map(|angle| short_number(angle.to_degrees(), 2));
+    let angles = match angles[2].as_str() {
+        "0" => &mut angles[..2],
+        _ => &mut angles[..],
+    };
+    [&position[..], angles].concat().join("_")
+}
+
+/// Reads a pose written by [`encode_pose`], or `None` if it isn't one
+pub fn decode_pose(encoded: &str) -> Option<Transform> {
+    let numbers = encoded
+        .split('_')
+        .map(|number| {
+            number
+                .parse::<f32>()
+                .ok()
+                .filter(|number| number.is_finite())
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let (position, angles) = match numbers.as_slice() {
+        [x, y, z, angles @ ..] if (2..=3).contains(&angles.len()) => {
+            (Vec3::new(*x, *y, *z), angles)
+        }
+        _ => return None,
+    };
+    let roll = angles.get(2).copied().unwrap_or_default();
+    Some(Transform {
+        translation: position,
+        rotation: Quat::from_euler(
+            EulerRot::YXZ,
+            angles[0].to_radians(),
+            angles[1].to_radians(),
+            roll.to_radians(),
+        ),
+        ..default()
+    })
+}
+
+/// The pose in the page URL's hash, as in `https://example.com/demo#3.5_2_-10_45_-12.5`, for
+/// spawning the camera where a shared link points
+#[cfg(target_arch = "wasm32")]
+pub fn pose_from_url() -> Option<Transform> {
+    let hash = web_sys::window()?.location().hash().ok()?;
+    decode_pose(hash.trim_start_matches('#'))
+}
+
+/// Puts a pose in the page URL's hash, so the address bar links to it
+#[cfg(target_arch = "wasm32")]
+pub fn set_url_pose(transform: &Transform) {
+    if let Some(window) = web_sys::window() {
+        if window.location().set_hash(&encode_pose(transform)).is_err() {
+            warn!("Could not put the camera pose in the page URL");
+        }
+    }
+}
+
+/// Logs the flycam's transform as Rust code when the copy pose key is pressed.
+/// With the `clipboard` feature it is copied to the clipboard too, and on the web the page URL
+/// is changed to link to it.
+pub(crate) fn copy_pose(
+    keys: Res<Input<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    blockers: Res<FlyCamInputBlockers>,
+    query: Query<&Transform, With<FlyCam>>,
+) {
+    if !keys.any_just_pressed(bindings.copy_pose.iter().copied()) || blockers.blocked().hotkeys {
+        return;
+    }
+    for transform in query.iter() {
+        let snippet = transform_snippet(transform);
+        info!("{}", snippet);
+        #[cfg(feature = "startup-overrides")]
+        info!("{}={}", crate::POSE_VAR, encode_pose(transform));
+
+        #[cfg(target_arch = "wasm32")]
+        set_url_pose(transform);
+
+        #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+        if let Err(err) = arboard::Clipboard::new().and_then(|mut c| c.set_text(snippet)) {
+            warn!("Could not copy the camera pose to the clipboard: {}", err);
+        }
+    }
+}
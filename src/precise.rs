@@ -0,0 +1,74 @@
+use bevy::math::DVec3;
+use bevy::prelude::*;
+
+use crate::{FlyCam, FlyCamOrigin, FlyCamTarget};
+
+/// A flycam's position in f64, for planet-scale scenes. Add it to a flycam and it becomes the
+/// authoritative position: every frame's movement is added to it in f64, and the `Transform`
+/// is written back as the f32 offset from [`FlyCamOrigin`]. Set `position` to move the camera
+/// there.
+///
+/// Use it together with [`FlyCamOriginPlugin`](crate::FlyCamOriginPlugin), which keeps that
+/// offset small enough for f32 to stay precise.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct FlyCamPrecisePosition {
+    pub position: DVec3,
+    /// The position and translation written last frame, to tell movement from teleports
+    written: Option<(DVec3, Vec3)>,
+}
+
+impl FlyCamPrecisePosition {
+    pub fn new(position: DVec3) -> Self {
+        Self {
+            position,
+            written: None,
+        }
+    }
+
+    /// Follows an origin shift of `offset` that has already moved the `Transform`
+    pub(crate) fn shift(&mut self, offset: Vec3) {
+        if let Some((_, translation)) = self.written.as_mut() {
+            *translation -= offset;
+        }
+    }
+}
+
+/// Adds each frame's movement to the precise position, and writes the position back to the
+/// `Transform` relative to the origin
+pub(crate) fn sync_precise_position(
+    origin: Option<Res<FlyCamOrigin>>,
+    mut query: Query<
+        (
+            &mut Transform,
+            &mut FlyCamTarget,
+            &mut FlyCamPrecisePosition,
+        ),
+        With<FlyCam>,
+    >,
+) {
+    let offset = origin.map_or(DVec3::ZERO, |origin| origin.offset);
+    for (mut transform, mut target, mut precise) in query.iter_mut() {
+        match precise.written {
+            // Nothing has moved the camera or the position
+            Some((position, translation))
+                if position == precise.position && translation == transform.translation =>
+            {
+                continue;
+            }
+            Some((position, translation)) if position == precise.position => {
+                let moved = transform.translation - translation;
+                precise.position += moved.as_dvec3();
+            }
+            // The position was set, or this is the first frame: it goes where the position says.
+            _ => {}
+        }
+
+        let translation = (precise.position - offset).as_vec3();
+        if translation != transform.translation {
+            // Keep the target the same distance ahead, so smoothing doesn't notice the rounding.
+            target.shift(transform.translation - translation);
+            transform.translation = translation;
+        }
+        precise.written = Some((precise.position, translation));
+    }
+}
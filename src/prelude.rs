@@ -0,0 +1,6 @@
+#[cfg(feature = "spawn-camera")]
+pub use crate::PlayerPlugin;
+pub use crate::{
+    BoostTier, FlyCam, FlyCamAction, FlyCamInput, FlyCamMode, KeyBindings, ModeChanged,
+    MovementSettings, NoCameraPlayerPlugin, SetFlyCamMode,
+};
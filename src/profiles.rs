@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+
+use crate::{BoostTier, FlyCamInputBlockers, KeyBindings, MovementSettings};
+
+/// A named set of [`MovementSettings`], and optionally [`KeyBindings`]
+#[derive(Clone)]
+pub struct FlyCamProfile {
+    pub name: String,
+    pub settings: MovementSettings,
+    /// Bindings to use while the profile is active, or `None` to keep the usual ones
+    pub bindings: Option<KeyBindings>,
+}
+
+/// Named [`MovementSettings`] profiles to switch between at runtime, by code or with the
+/// next profile key. While a profile is active it replaces `MovementSettings`, and
+/// `KeyBindings` if it has its own; deactivating brings back the settings and bindings from
+/// before the first switch.
+#[derive(Clone)]
+pub struct FlyCamProfiles {
+    pub profiles: Vec<FlyCamProfile>,
+    active: Option<usize>,
+}
+
+impl Default for FlyCamProfiles {
+    fn default() -> Self {
+        let defaults = MovementSettings::default();
+        Self {
+            profiles: vec![
+                FlyCamProfile {
+                    name: "slow inspect".into(),
+                    settings: MovementSettings {
+                        speed: 2.,
+                        sensitivity: defaults.sensitivity / 2.,
+                        ..defaults.clone()
+                    },
+                    bindings: None,
+                },
+                FlyCamProfile {
+                    name: "fast traverse".into(),
+                    settings: MovementSettings {
+                        speed: 60.,
+                        boost_tiers: vec![
+                            BoostTier::new([KeyCode::LShift], 8.),
+                            BoostTier::new([KeyCode::LShift, KeyCode::LControl], 64.),
+                            BoostTier::new([KeyCode::LAlt], 0.125),
+                        ],
+                        ..defaults.clone()
+                    },
+                    bindings: None,
+                },
+                FlyCamProfile {
+                    name: "cinematic".into(),
+                    settings: MovementSettings {
+                        speed: 6.,
+                        look_smoothing: 0.3,
+                        translation_smoothing: 0.5,
+                        rotation_smoothing: 0.4,
+                        ..defaults.clone()
+                    },
+                    bindings: None,
+                },
+                // Modifiers are toggles, since the one hand on the keyboard is busy moving.
+                FlyCamProfile {
+                    name: "one-handed".into(),
+                    settings: MovementSettings {
+                        boost_tiers: vec![
+                            BoostTier::new([KeyCode::RShift], 4.),
+                            BoostTier::new([KeyCode::RShift, KeyCode::RControl], 16.),
+                            BoostTier::new([KeyCode::RAlt], 0.25),
+                        ],
+                        toggle_boost: true,
+                        toggle_crouch: true,
+                        ..defaults
+                    },
+                    bindings: Some(KeyBindings::one_handed()),
+                },
+            ],
+            active: None,
+        }
+    }
+}
+
+impl FlyCamProfiles {
+    /// Adds a profile, replacing any existing one with the same name
+    pub fn insert(&mut self, name: impl Into<String>, settings: MovementSettings) {
+        self.insert_profile(FlyCamProfile {
+            name: name.into(),
+            settings,
+            bindings: None,
+        });
+    }
+
+    /// Adds a profile with its own key bindings, replacing any existing one with the same name
+    pub fn insert_with_bindings(
+        &mut self,
+        name: impl Into<String>,
+        settings: MovementSettings,
+        bindings: KeyBindings,
+    ) {
+        self.insert_profile(FlyCamProfile {
+            name: name.into(),
+            settings,
+            bindings: Some(bindings),
+        });
+    }
+
+    fn insert_profile(&mut self, profile: FlyCamProfile) {
+        match self.profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+    }
+
+    /// Switches to the named profile. Returns false if there is no profile with that name.
+    pub fn activate(&mut self, name: &str) -> bool {
+        match self.profiles.iter().position(|p| p.name == name) {
+            Some(index) => {
+                self.active = Some(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Goes back to the settings from before any profile was activated
+    pub fn deactivate(&mut self) {
+        self.active = None;
+    }
+
+    /// Switches to the next profile, and after the last one back to no profile
+    pub fn cycle(&mut self) {
+        self.active = match self.active {
+            None if !self.profiles.is_empty() => Some(0),
+            Some(index) if index + 1 < self.profiles.len() => Some(index + 1),
+            _ => None,
+        };
+    }
+
+    /// The profile currently in use, if any
+    pub fn active(&self) -> Option<&FlyCamProfile> {
+        self.active.and_then(|index| self.profiles.get(index))
+    }
+}
+
+/// Cycles profiles with the next profile key
+pub(crate) fn cycle_profiles(
+    keys: Res<Input<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut profiles: ResMut<FlyCamProfiles>,
+) {
+    if keys.any_just_pressed(bindings.next_profile.iter().copied()) && !blockers.blocked().hotkeys {
+        profiles.cycle();
+    }
+}
+
+/// Copies the active profile into [`MovementSettings`] and [`KeyBindings`] whenever it is
+/// switched
+pub(crate) fn apply_profile(
+    profiles: Res<FlyCamProfiles>,
+    mut settings: ResMut<MovementSettings>,
+    mut bindings: ResMut<KeyBindings>,
+    mut stashed: Local<Option<(MovementSettings, KeyBindings)>>,
+) {
+    if !profiles.is_changed() {
+        return;
+    }
+    match profiles.active() {
+        Some(profile) => {
+            let (_, usual_bindings) =
+                stashed.get_or_insert_with(|| (settings.clone(), bindings.clone()));
+            info!("Flycam profile: {}", profile.name);
+            *settings = profile.settings.clone();
+            *bindings = profile
+                .bindings
+                .clone()
+                .unwrap_or_else(|| usual_bindings.clone());
+        }
+        None => {
+            if let Some((previous_settings, previous_bindings)) = stashed.take() {
+                info!("Flycam profile: none");
+                *settings = previous_settings;
+                *bindings = previous_bindings;
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::render::camera::{CameraProjection, Projection, RenderTarget};
+
+use crate::{FlyCam, FlyCamRig};
+
+/// A half-line in world space, e.g. from a camera through the cursor
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FlyCamRay {
+    pub origin: Vec3,
+    /// Unit vector the ray points along
+    pub direction: Vec3,
+}
+
+impl FlyCamRay {
+    /// The point `distance` along the ray
+    pub fn point_at(&self, distance: f32) -> Vec3 {
+        self.origin + self.direction * distance
+    }
+
+    /// The ray from a camera with `projection` and `transform` through `position` in a
+    /// viewport of `size`, both in logical pixels from the bottom left. It starts on the near
+    /// plane.
+    pub fn through_viewport(
+        projection: &Projection,
+        transform: &GlobalTransform,
+        size: Vec2,
+        position: Vec2,
+    ) -> Option<Self> {
+        if size.cmple(Vec2::ZERO).any() {
+            return None;
+        }
+        let mut projection = projection.clone();
+        projection.update(size.x, size.y);
+        let ndc = position / size * 2. - Vec2::ONE;
+        let ndc_to_world =
+            transform.compute_matrix() * projection.get_projection_matrix().inverse();
+        // Depth is reversed, with the near plane at 1.
+        let near = ndc_to_world.project_point3(ndc.extend(1.));
+        let further = ndc_to_world.project_point3(ndc.extend(0.5));
+        Some(Self {
+            origin: near,
+            direction: (further - near).try_normalize()?,
+        })
+    }
+}
+
+/// Builds rays from cameras through the cursor, for picking and placing things without redoing
+/// the projection maths. Use it as a system parameter.
+#[derive(SystemParam)]
+pub struct FlyCamRays<'w, 's> {
+    windows: Res<'w, Windows>,
+    flycams: Query<'w, 's, (Entity, Option<&'static FlyCamRig>), With<FlyCam>>,
+    cameras: Query<
+        'w,
+        's,
+        (
+            &'static Camera,
+            &'static Projection,
+            &'static GlobalTransform,
+        ),
+    >,
+}
+
+impl<'w, 's> FlyCamRays<'w, 's> {
+    /// The ray from `camera` through the cursor, or through the middle of the view while the
+    /// cursor is grabbed or outside the camera's viewport. `camera` can be a flycam, including
+    /// a [`FlyCamRig`], or any other 3D camera.
+    pub fn cursor_ray(&self, camera: Entity) -> Option<FlyCamRay> {
+        self.ray(camera, true)
+    }
+
+    /// The ray through the middle of `camera`'s view, wherever the cursor is
+    pub fn center_ray(&self, camera: Entity) -> Option<FlyCamRay> {
+        self.ray(camera, false)
+    }
+
+    /// [`FlyCamRays::cursor_ray`] for the first flycam
+    pub fn flycam_cursor_ray(&self) -> Option<FlyCamRay> {
+        let (flycam, _) = self.flycams.iter().next()?;
+        self.cursor_ray(flycam)
+    }
+
+    fn ray(&self, camera: Entity, through_cursor: bool) -> Option<FlyCamRay> {
+        // A rig's camera is its pitching child.
+        let camera = match self.flycams.get(camera) {
+            Ok((_, Some(rig))) => rig.camera,
+            _ => camera,
+        };
+        let (camera, projection, transform) = self.cameras.get(camera).ok()?;
+        let window = match camera.target {
+            RenderTarget::Window(id) => self.windows.get(id),
+            RenderTarget::Image(_) => None,
+        };
+        // Cameras that haven't been rendered yet don't know their size, but windows do.
+        let size = camera
+            .logical_viewport_size()
+            .or_else(|| window.map(|window| Vec2::new(window.width(), window.height())))?;
+        let cursor = window
+            .filter(|window| through_cursor && !window.cursor_locked())
+            .and_then(|window| {
+                let cursor = window.cursor_position()?;
+                // The cursor is measured from the bottom left, viewports from the top left.
+                let (min, _) = camera.logical_viewport_rect().unwrap_or((Vec2::ZERO, size));
+                let position = Vec2::new(
+                    cursor.x - min.x,
+                    cursor.y - (window.height() - min.y - size.y),
+                );
+                (position.cmpge(Vec2::ZERO).all() && position.cmple(size).all()).then_some(position)
+            });
+        FlyCamRay::through_viewport(projection, transform, size, cursor.unwrap_or(size / 2.))
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::{FlyCamAction, FlyCamRotation};
+
+/// A recorded stretch of first-person flight: where the camera started and the
+/// [`FlyCamAction`]s it took each frame. Played back with [`FlyCamPlayback`], it reproduces
+/// the same motion however long the frames take and whatever the window size, settings and
+/// zoom, since each recorded frame moves and turns the camera as far as it did while recording.
+///
+/// With the `serialize` feature it can be saved with any serde format, and with `ron` there's
+/// `FlyCamRecording::to_ron` for attaching traces to bug reports.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlyCamRecording {
+    /// Where the camera was headed for when recording started, or `None` for an empty recording
+    pub start: Option<(Vec3, FlyCamRotation)>,
+    pub frames: Vec<RecordedFrame>,
+}
+
+/// One frame of a [`FlyCamRecording`]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordedFrame {
+    /// How long the frame took, in seconds
+    pub delta: f32,
+    /// Everything that moved or turned the camera this frame, with any look smoothing
+    /// already applied
+    pub actions: Vec<FlyCamAction>,
+    /// What the actions came to while recording, which playback uses instead of working them
+    /// out again. `None` for frames written by hand, which play back from `actions`.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub motion: Option<RecordedMotion>,
+}
+
+/// How far a [`RecordedFrame`] moved and turned the camera
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordedMotion {
+    /// How far the camera moved, in world space
+    pub offset: Vec3,
+    /// How far it turned, in radians of yaw, pitch and roll
+    pub turn: Vec3,
+}
+
+impl FlyCamRecording {
+    /// How long the recording lasts, in seconds
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.delta).sum()
+    }
+
+    /// The recording as compact RON
+    #[cfg(feature = "ron")]
+    pub fn to_ron(&self) -> ron::Result<String> {
+        ron::to_string(self)
+    }
+
+    /// Reads a recording saved with [`FlyCamRecording::to_ron`]
+    #[cfg(feature = "ron")]
+    pub fn from_ron(ron: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(ron)
+    }
+}
+
+/// Add to a flycam to record its first-person movement into [`FlyCamRecorder::recording`].
+/// Remove it, or take the recording, when you're done.
+#[derive(Component, Clone, Debug, Default)]
+pub struct FlyCamRecorder {
+    pub recording: FlyCamRecording,
+}
+
+/// Add to a flycam to play a [`FlyCamRecording`] on it, one recorded frame per frame.
+/// The camera jumps to where the recording starts and ignores other input until it's done.
+#[derive(Component, Clone, Debug)]
+pub struct FlyCamPlayback {
+    pub recording: FlyCamRecording,
+    next_frame: usize,
+}
+
+impl FlyCamPlayback {
+    pub fn new(recording: FlyCamRecording) -> Self {
+        Self {
+            recording,
+            next_frame: 0,
+        }
+    }
+
+    /// Whether every recorded frame has been played
+    pub fn is_finished(&self) -> bool {
+        self.next_frame >= self.recording.frames.len()
+    }
+
+    /// Skips the rest of the recording
+    pub(crate) fn stop(&mut self) {
+        self.next_frame = self.recording.frames.len();
+    }
+
+    /// Where the recording starts, if it's the first frame, and the frame to play this frame.
+    /// `None` once it's finished.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn next(&mut self) -> Option<(Option<(Vec3, FlyCamRotation)>, &RecordedFrame)> {
+        let start = if self.next_frame == 0 {
+            self.recording.start
+        } else {
+            None
+        };
+        let frame = self.recording.frames.get(self.next_frame)?;
+        self.next_frame += 1;
+        Some((start, frame))
+    }
+}
@@ -0,0 +1,26 @@
+use bevy::prelude::*;
+
+/// A first-person rig, like a standard FPS controller: this entity is the [`FlyCam`](crate::FlyCam)
+/// and only turns with the heading, while its child `camera` tilts up and down (and rolls).
+/// Attach a body mesh or weapon viewmodel to whichever of the two should follow it.
+///
+/// [`PlayerPlugin`](crate::PlayerPlugin) spawns one when [`FlyCamSpawnSettings::rig`](crate::FlyCamSpawnSettings)
+/// is set. To build your own, add this to the flycam and make `camera` its child.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct FlyCamRig {
+    /// The child entity that pitches, usually the camera
+    pub camera: Entity,
+}
+
+/// Marks the pitching child of a [`FlyCamRig`] spawned by the plugin, so it gets the flycam's
+/// projection settings
+#[derive(Component)]
+pub(crate) struct FlyCamRigCamera;
+
+/// Splits a rotation in `frame` into the heading for the rig's yaw entity and what's left over
+/// for its child
+pub(crate) fn split(frame: Quat, rotation: Quat) -> (Quat, Quat) {
+    let (yaw, _, _) = (frame.inverse() * rotation).to_euler(EulerRot::YXZ);
+    let heading = frame * Quat::from_rotation_y(yaw);
+    (heading, heading.inverse() * rotation)
+}
@@ -0,0 +1,203 @@
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use bevy::prelude::*;
+
+use crate::target::smoothing_blend;
+use crate::{FlyCamMode, FlyCamTarget, FlyCamTime, MovementSettings, PlanetSettings};
+
+/// The flycam's orientation as angles in radians. Look and keyboard rotation change these and the
+/// rotation quaternion is rebuilt from them, so no drift or stray roll can build up.
+///
+/// Added automatically to every [`FlyCam`](crate::FlyCam); write to it to turn the camera from code.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlyCamRotation {
+    /// Rotation around the global y axis
+    pub yaw: f32,
+    /// Rotation around the local x axis, kept between straight down and straight up
+    pub pitch: f32,
+    /// Rotation around the local z axis
+    pub roll: f32,
+    /// Extra roll applied on top of the others, e.g. a dutch tilt for cinematics. Looking
+    /// around turns as if it weren't there, and it stays put when the camera is levelled or
+    /// switches modes.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub roll_offset: f32,
+}
+
+impl FlyCamRotation {
+    /// The rotation these angles describe
+    pub fn to_quat(&self) -> Quat {
+        Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, self.roll)
+            * Quat::from_rotation_z(self.roll_offset)
+    }
+
+    /// Takes the angles from `rotation`, a view with the roll offset already applied, keeping
+    /// the offset
+    pub(crate) fn set_view(&mut self, rotation: Quat) {
+        let roll_offset = self.roll_offset;
+        *self = Self {
+            roll_offset,
+            ..Self::from(rotation * Quat::from_rotation_z(-roll_offset))
+        };
+    }
+
+    /// Turns by the given angles, keeping the pitch in range and the yaw and roll within half a
+    /// turn either way
+    pub(crate) fn rotate(&mut self, yaw: f32, pitch: f32, roll: f32) {
+        self.yaw = wrap_angle(self.yaw + yaw);
+        self.pitch = (self.pitch + pitch).clamp(-FRAC_PI_2, FRAC_PI_2);
+        self.roll = wrap_angle(self.roll + roll);
+    }
+}
+
+impl From<Quat> for FlyCamRotation {
+    fn from(rotation: Quat) -> Self {
+        let (yaw, pitch, roll) = rotation.to_euler(EulerRot::YXZ);
+        Self {
+            yaw,
+            pitch,
+            roll,
+            roll_offset: 0.,
+        }
+    }
+}
+
+/// `angle` brought within half a turn of 0. Angles that keep adding up lose precision after
+/// enough spinning, which rounds every turn a little differently.
+pub(crate) fn wrap_angle(angle: f32) -> f32 {
+    if (-PI..=PI).contains(&angle) {
+        angle
+    } else {
+        (angle + PI).rem_euclid(TAU) - PI
+    }
+}
+
+/// The extra roll a flycam banks into turns by, see [`MovementSettings::bank_angle`]
+#[derive(Component)]
+pub(crate) struct FlyCamBank {
+    angle: f32,
+    last_yaw: f32,
+}
+
+impl FlyCamBank {
+    /// No bank, starting from the given yaw
+    pub(crate) fn level(yaw: f32) -> Self {
+        Self {
+            angle: 0.,
+            last_yaw: yaw,
+        }
+    }
+}
+
+/// The frame a flycam's [`FlyCamRotation`] is measured in. Yaw turns around the frame's up
+/// and pitch is measured from its horizon.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub(crate) struct FlyCamFrame(pub(crate) Quat);
+
+impl FlyCamFrame {
+    /// The upright frame for a camera at `translation` in `mode`
+    pub(crate) fn new(
+        mode: FlyCamMode,
+        translation: Vec3,
+        settings: &MovementSettings,
+        planet: &PlanetSettings,
+    ) -> Self {
+        let up = if mode == FlyCamMode::Planet {
+            translation - planet.center
+        } else {
+            settings.up_axis
+        };
+        Self(upright(up))
+    }
+
+    /// Which way is up for the camera
+    pub(crate) fn up(&self) -> Vec3 {
+        self.0 * Vec3::Y
+    }
+}
+
+/// The frame with its up along `up`, or the plain world frame if `up` is zero
+fn upright(up: Vec3) -> Quat {
+    let up = up.normalize_or_zero();
+    if up == Vec3::ZERO {
+        Quat::IDENTITY
+    } else {
+        Quat::from_rotation_arc(Vec3::Y, up)
+    }
+}
+
+/// Keeps each flycam's frame upright: along [`MovementSettings::up_axis`], or pointing away
+/// from the planet in [`FlyCamMode::Planet`]
+pub(crate) fn update_frame(
+    settings: Res<MovementSettings>,
+    planet: Res<PlanetSettings>,
+    mut query: Query<(&mut FlyCamFrame, &FlyCamTarget, &FlyCamMode)>,
+) {
+    for (mut frame, target, mode) in query.iter_mut() {
+        let goal = if *mode == FlyCamMode::Planet {
+            let up = (target.translation - planet.center).normalize_or_zero();
+            if up == Vec3::ZERO {
+                continue;
+            }
+            // Turn the old frame the least amount needed, so the heading carries on smoothly.
+            (Quat::from_rotation_arc(frame.up(), up) * frame.0).normalize()
+        } else {
+            upright(settings.up_axis)
+        };
+        if goal.angle_between(frame.0) > 1e-6 {
+            frame.0 = goal;
+        }
+    }
+}
+
+/// Rebuilds the target rotation of flying cameras from their angles and frame, plus any banking
+#[allow(clippy::type_complexity)]
+pub(crate) fn apply_rotation(
+    time: Res<FlyCamTime>,
+    settings: Res<MovementSettings>,
+    mut query: Query<(
+        &mut FlyCamTarget,
+        &mut FlyCamRotation,
+        &mut FlyCamBank,
+        &FlyCamFrame,
+        ChangeTrackers<FlyCamFrame>,
+        &FlyCamMode,
+        ChangeTrackers<FlyCamMode>,
+    )>,
+) {
+    let dt = time.delta_seconds();
+    for (mut target, mut rotation, mut bank, frame, frame_tracker, mode, mode_tracker) in
+        query.iter_mut()
+    {
+        if !mode.uses_fly_controls() {
+            continue;
+        }
+        if mode_tracker.is_changed() && !mode_tracker.is_added() {
+            // Back from another mode: carry on from wherever it left the camera.
+            rotation.set_view(frame.0.inverse() * target.rotation);
+            *bank = FlyCamBank::level(rotation.yaw);
+            continue;
+        }
+
+        // Full bank at half a turn per second.
+        let yaw_rate = if dt > 0. {
+            wrap_angle(rotation.yaw - bank.last_yaw) / dt
+        } else {
+            0.
+        };
+        let goal = (yaw_rate / PI).clamp(-1., 1.) * settings.bank_angle.max(0.);
+        let mut angle =
+            bank.angle + (goal - bank.angle) * smoothing_blend(settings.bank_return, dt);
+        if (angle - goal).abs() < 1e-5 {
+            angle = goal;
+        }
+        if bank.last_yaw != rotation.yaw {
+            bank.last_yaw = rotation.yaw;
+        }
+        if rotation.is_changed() || frame_tracker.is_changed() || angle != bank.angle {
+            bank.angle = angle;
+            target.rotation = frame.0 * rotation.to_quat() * Quat::from_rotation_z(angle);
+        }
+    }
+}
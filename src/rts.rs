@@ -0,0 +1,169 @@
+use bevy::input::mouse::MouseWheel;
+use bevy::prelude::*;
+
+use crate::rotation::wrap_angle;
+use crate::{
+    edge_scroll, get_boost, pressed, wheel_notches, FlyCam, FlyCamInputBlockers, FlyCamKeys,
+    FlyCamMode, FlyCamTarget, FlyCamTime, KeyBindings, MovementSettings,
+};
+
+/// Settings for [`FlyCamMode::Rts`], the top-down strategy camera.
+/// Pitch angles are in radians below the horizon.
+pub struct RtsSettings {
+    /// Pitch when fully zoomed in
+    pub min_pitch: f32,
+    /// Pitch when fully zoomed out
+    pub max_pitch: f32,
+    /// Closest the camera can get to its focus point
+    pub min_distance: f32,
+    /// Furthest the camera can get from its focus point
+    pub max_distance: f32,
+    /// Panning speed, in multiples of the current distance per second
+    pub pan_speed: f32,
+    /// Radians per second that Q/E rotate around the focus point
+    pub rotate_speed: f32,
+    /// How much each scroll step changes the distance
+    pub zoom_sensitivity: f32,
+    /// Width in pixels of the window border that triggers edge scrolling. 0 disables it.
+    pub edge_scroll_margin: f32,
+}
+
+impl Default for RtsSettings {
+    fn default() -> Self {
+        Self {
+            min_pitch: 30f32.to_radians(),
+            max_pitch: 75f32.to_radians(),
+            min_distance: 5.,
+            max_distance: 100.,
+            pan_speed: 1.,
+            rotate_speed: 1.5,
+            zoom_sensitivity: 0.1,
+            edge_scroll_margin: 0.,
+        }
+    }
+}
+
+/// Where an RTS camera is looking. Added when a flycam switches to [`FlyCamMode::Rts`].
+#[derive(Component, Clone, Copy, Debug)]
+pub struct RtsState {
+    /// The point on the ground plane (y = 0) the camera orbits around
+    pub focus: Vec3,
+    /// Distance from the focus point
+    pub distance: f32,
+    /// Rotation around the focus point, in radians
+    pub yaw: f32,
+}
+
+/// Picks up the RTS focus point from wherever the camera is looking when the mode is switched on
+#[allow(clippy::type_complexity)]
+pub(crate) fn enter_rts(
+    mut commands: Commands,
+    settings: Res<RtsSettings>,
+    query: Query<(Entity, &Transform, &FlyCamMode), (With<FlyCam>, Changed<FlyCamMode>)>,
+) {
+    for (entity, transform, mode) in query.iter() {
+        if *mode != FlyCamMode::Rts {
+            continue;
+        }
+        let forward = transform.forward();
+        let focus = if forward.y < -f32::EPSILON {
+            transform.translation + forward * (transform.translation.y / -forward.y)
+        } else {
+            Vec3::new(transform.translation.x, 0., transform.translation.z)
+        };
+        let distance = transform
+            .translation
+            .distance(focus)
+            .clamp(settings.min_distance, settings.max_distance);
+        let yaw = f32::atan2(-forward.x, -forward.z);
+        commands.entity(entity).insert(RtsState {
+            focus,
+            distance,
+            yaw,
+        });
+    }
+}
+
+/// Pans parallel to the ground, rotates around the focus point and zooms in and out,
+/// tilting towards top-down the further out the camera is
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn rts_camera(
+    keys: Res<FlyCamKeys>,
+    time: Res<FlyCamTime>,
+    windows: Res<Windows>,
+    settings: Res<MovementSettings>,
+    rts_settings: Res<RtsSettings>,
+    bindings: Res<KeyBindings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    mut query: Query<(&mut FlyCamTarget, &FlyCamMode, &mut RtsState), With<FlyCam>>,
+) {
+    let blocked = blockers.blocked();
+    let scroll = if blocked.scroll {
+        mouse_wheel_events.clear();
+        0.
+    } else {
+        wheel_notches(&windows, &mut mouse_wheel_events).y
+    };
+    let dt = time.delta_seconds();
+    let bindings = bindings.for_mode(FlyCamMode::Rts);
+
+    let mut direction = Vec2::ZERO;
+    if pressed(&keys, &bindings.move_forward) {
+        direction.y += 1.;
+    }
+    if pressed(&keys, &bindings.move_backward) {
+        direction.y -= 1.;
+    }
+    if pressed(&keys, &bindings.move_left) {
+        direction.x -= 1.;
+    }
+    if pressed(&keys, &bindings.move_right) {
+        direction.x += 1.;
+    }
+    direction += edge_scroll(&windows, rts_settings.edge_scroll_margin);
+    if blocked.movement {
+        direction = Vec2::ZERO;
+    }
+    let direction = direction.normalize_or_zero();
+
+    let mut rotate = 0.;
+    if pressed(&keys, &bindings.yaw_left) {
+        rotate += 1.;
+    }
+    if pressed(&keys, &bindings.yaw_right) {
+        rotate -= 1.;
+    }
+    if blocked.look {
+        rotate = 0.;
+    }
+
+    let boost = get_boost(&keys, &settings);
+    for (mut transform, mode, mut state) in query.iter_mut() {
+        if *mode != FlyCamMode::Rts {
+            continue;
+        }
+        state.yaw = wrap_angle(state.yaw + rotate * rts_settings.rotate_speed * dt);
+        let yaw = Quat::from_rotation_y(state.yaw);
+
+        let forward = yaw * -Vec3::Z;
+        let right = yaw * Vec3::X;
+        let pan = (forward * direction.y + right * direction.x)
+            * rts_settings.pan_speed
+            * state.distance
+            * boost
+            * dt;
+        state.focus += pan;
+
+        state.distance = (state.distance * (1. - scroll * rts_settings.zoom_sensitivity))
+            .clamp(rts_settings.min_distance, rts_settings.max_distance);
+
+        let zoom = (state.distance - rts_settings.min_distance)
+            / (rts_settings.max_distance - rts_settings.min_distance).max(f32::EPSILON);
+        let pitch =
+            rts_settings.min_pitch + (rts_settings.max_pitch - rts_settings.min_pitch) * zoom;
+
+        transform.rotation = yaw * Quat::from_rotation_x(-pitch);
+        transform.translation = state.focus + transform.rotation * Vec3::Z * state.distance;
+    }
+}
@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+
+use crate::{FlyCam, FlyCamIdle, FlyCamPlayback, FlyCamRecording};
+
+/// Plays a [`FlyCamRecording`] on a flycam once nobody has touched the input for a while, like
+/// a screensaver for demo kiosks, and hands control straight back from wherever the recording
+/// has got to as soon as anyone does. Add it to a flycam to turn it on.
+#[derive(Component, Clone, Debug)]
+pub struct FlyCamScreensaver {
+    pub recording: FlyCamRecording,
+    /// Seconds without input before the recording starts
+    pub idle_time: f32,
+    /// Start the recording over once it's done, for as long as nobody touches the input
+    pub looping: bool,
+    playing: bool,
+}
+
+impl FlyCamScreensaver {
+    /// Plays `recording`, over and over, after `idle_time` seconds without input
+    pub fn new(recording: FlyCamRecording, idle_time: f32) -> Self {
+        Self {
+            recording,
+            idle_time,
+            looping: true,
+            playing: false,
+        }
+    }
+
+    /// Whether the recording is playing at the moment
+    pub fn playing(&self) -> bool {
+        self.playing
+    }
+}
+
+/// Starts and stops screensaver playback
+pub(crate) fn screensaver(
+    mut commands: Commands,
+    idle: Res<FlyCamIdle>,
+    mut query: Query<(Entity, &mut FlyCamScreensaver, Option<&mut FlyCamPlayback>), With<FlyCam>>,
+) {
+    for (entity, mut screensaver, playback) in query.iter_mut() {
+        if screensaver.playing {
+            if idle.active() {
+                // Stopped here as well, so live input takes over this very frame.
+                if let Some(mut playback) = playback {
+                    playback.stop();
+                }
+                commands.entity(entity).remove::<FlyCamPlayback>();
+                screensaver.playing = false;
+            } else {
+                match playback {
+                    Some(playback) if playback.is_finished() && !screensaver.looping => {
+                        commands.entity(entity).remove::<FlyCamPlayback>();
+                    }
+                    Some(playback) if !playback.is_finished() => {}
+                    None if !screensaver.looping => {}
+                    _ => {
+                        commands
+                            .entity(entity)
+                            .insert(FlyCamPlayback::new(screensaver.recording.clone()));
+                    }
+                }
+            }
+        } else if idle.seconds() >= screensaver.idle_time && !idle.active() && playback.is_none() {
+            commands
+                .entity(entity)
+                .insert(FlyCamPlayback::new(screensaver.recording.clone()));
+            screensaver.playing = true;
+        }
+    }
+}
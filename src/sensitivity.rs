@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+/// The kinds of device that turn and scroll the camera
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputDevice {
+    /// Mouse motion, and wheels that scroll in lines
+    Mouse,
+    /// Dragging on a touchscreen
+    Touch,
+    /// Gamepad sticks
+    Gamepad,
+    /// Trackpads and other devices that scroll in pixels
+    Trackpad,
+}
+
+/// Scales how fast each kind of device turns and scrolls the camera, on top of
+/// [`MovementSettings::sensitivity`](crate::MovementSettings::sensitivity) and
+/// [`FlyCamGamepadSettings::look_sensitivity`](crate::FlyCamGamepadSettings::look_sensitivity),
+/// so a game can tune each one without checking which is in use. Every device starts at 1.
+///
+/// ```
+/// # use bevy_flycam::{InputDevice, SensitivityProfiles};
+/// let profiles = SensitivityProfiles::default().with(InputDevice::Trackpad, 0.5);
+/// assert_eq!(profiles.get(InputDevice::Trackpad), 0.5);
+/// assert_eq!(profiles.get(InputDevice::Mouse), 1.);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SensitivityProfiles {
+    multipliers: HashMap<InputDevice, f32>,
+}
+
+impl SensitivityProfiles {
+    /// How much `device`'s input is scaled by
+    pub fn get(&self, device: InputDevice) -> f32 {
+        self.multipliers.get(&device).copied().unwrap_or(1.)
+    }
+
+    /// Scales `device`'s input by `multiplier`
+    pub fn set(&mut self, device: InputDevice, multiplier: f32) {
+        self.multipliers.insert(device, multiplier);
+    }
+
+    /// These profiles with `device` scaled by `multiplier`
+    pub fn with(mut self, device: InputDevice, multiplier: f32) -> Self {
+        self.set(device, multiplier);
+        self
+    }
+}
@@ -0,0 +1,111 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::{
+    rig, FlyCam, FlyCamMode, FlyCamRig, FlyCamRotation, FlyCamTarget, FlyCamWaypoints, KeyBindings,
+    MovementSettings,
+};
+
+/// Everything needed to put a flycam back the way the viewer left it: where it is and which
+/// way it faces, its mode, the settings and key bindings, and the waypoints being edited. Take
+/// one with [`FlyCamSessions::save`] and hand it to [`FlyCamSessions::restore`], in the same run
+/// or, since it's serde-serializable with the `serialize` feature, the next time the app starts.
+#[derive(Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlyCamSession {
+    pub translation: Vec3,
+    pub rotation: FlyCamRotation,
+    pub mode: FlyCamMode,
+    pub settings: MovementSettings,
+    pub bindings: KeyBindings,
+    /// The [`FlyCamWaypoints`] resource, if there is one
+    pub waypoints: Option<FlyCamWaypoints>,
+}
+
+impl FlyCamSession {
+    /// The session as RON, for saving to a file
+    #[cfg(feature = "ron")]
+    pub fn to_ron(&self) -> ron::Result<String> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    /// Reads a session saved with [`FlyCamSession::to_ron`]
+    #[cfg(feature = "ron")]
+    pub fn from_ron(ron: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(ron)
+    }
+}
+
+/// Saves and restores [`FlyCamSession`]s, for editors that keep the viewer's camera between
+/// launches. Use it as a system parameter.
+#[derive(SystemParam)]
+pub struct FlyCamSessions<'w, 's> {
+    settings: ResMut<'w, MovementSettings>,
+    bindings: ResMut<'w, KeyBindings>,
+    waypoints: Option<ResMut<'w, FlyCamWaypoints>>,
+    #[allow(clippy::type_complexity)]
+    flycams: Query<
+        'w,
+        's,
+        (
+            &'static mut Transform,
+            &'static mut FlyCamTarget,
+            &'static mut FlyCamRotation,
+            &'static mut FlyCamMode,
+            Option<&'static FlyCamRig>,
+        ),
+        With<FlyCam>,
+    >,
+    rig_cameras: Query<'w, 's, &'static mut Transform, Without<FlyCam>>,
+}
+
+impl<'w, 's> FlyCamSessions<'w, 's> {
+    /// Everything about `camera` and the settings, or `None` if it isn't a flycam that's been
+    /// set up yet. The pose is where the camera is headed, where it'll come to rest.
+    pub fn save(&self, camera: Entity) -> Option<FlyCamSession> {
+        let (_, target, rotation, mode, _) = self.flycams.get(camera).ok()?;
+        Some(FlyCamSession {
+            translation: target.translation,
+            rotation: *rotation,
+            mode: *mode,
+            settings: self.settings.clone(),
+            bindings: self.bindings.clone(),
+            waypoints: self.waypoints.as_deref().cloned(),
+        })
+    }
+
+    /// Puts the settings back the way they were in `session`, and jumps `camera` straight to
+    /// its pose and mode. Returns whether `camera` is a flycam that's been set up, leaving the
+    /// settings alone if it isn't.
+    pub fn restore(&mut self, camera: Entity, session: &FlyCamSession) -> bool {
+        let (mut transform, mut target, mut rotation, mut mode, flycam_rig) =
+            match self.flycams.get_mut(camera) {
+                Ok(flycam) => flycam,
+                Err(_) => return false,
+            };
+        // The frame the angles are measured in, e.g. upright on a planet
+        let frame = target.rotation * rotation.to_quat().inverse();
+        let view = frame * session.rotation.to_quat();
+        target.translation = session.translation;
+        target.rotation = view;
+        *rotation = session.rotation;
+        *mode = session.mode;
+        // Moving the `Transform` too makes it a jump rather than a glide.
+        transform.translation = session.translation;
+        match flycam_rig.and_then(|flycam_rig| self.rig_cameras.get_mut(flycam_rig.camera).ok()) {
+            Some(mut rig_camera) => {
+                let (yaw, pitch) = rig::split(frame, view);
+                transform.rotation = yaw;
+                rig_camera.rotation = pitch;
+            }
+            None => transform.rotation = view,
+        }
+
+        *self.settings = session.settings.clone();
+        *self.bindings = session.bindings.clone();
+        if let (Some(waypoints), Some(saved)) = (self.waypoints.as_mut(), &session.waypoints) {
+            **waypoints = saved.clone();
+        }
+        true
+    }
+}
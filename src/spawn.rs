@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy::render::camera::{Projection, RenderTarget, Viewport};
+
+use crate::{rig, FlyCam, FlyCamRig, MovementSettings, NoCameraPlayerPlugin};
+
+/// Contains everything needed to add first-person fly camera behavior to your game
+pub struct PlayerPlugin;
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlyCamSpawnSettings>()
+            .add_startup_system(setup_player)
+            .add_plugin(NoCameraPlayerPlugin);
+    }
+}
+
+/// Where and in what order the camera spawned by [`PlayerPlugin`] renders. Insert it before
+/// the app starts, e.g. to render the flycam into a texture or a picture-in-picture viewport.
+#[derive(Clone, Default)]
+pub struct FlyCamSpawnSettings {
+    pub target: RenderTarget,
+    /// The part of the target to render to, or `None` for all of it
+    pub viewport: Option<Viewport>,
+    /// Cameras with a higher priority render on top of ones with a lower priority
+    pub priority: isize,
+    /// Run on the spawned camera after it's set up, to add your own components to it
+    pub customize: Vec<CustomizeCamera>,
+    /// Spawn a [`FlyCamRig`]: a flycam that only turns with the heading, with the camera as
+    /// a child that tilts up and down
+    pub rig: bool,
+}
+
+/// Adds components to a spawned camera, see [`FlyCamSpawnSettings::customize`]
+pub type CustomizeCamera = Arc<dyn Fn(&mut EntityCommands) + Send + Sync>;
+
+impl FlyCamSpawnSettings {
+    /// Runs `customize` on the spawned camera, e.g. to insert render layers or a marker
+    pub fn with(mut self, customize: impl Fn(&mut EntityCommands) + Send + Sync + 'static) -> Self {
+        self.customize.push(Arc::new(customize));
+        self
+    }
+
+    /// Inserts `bundle` on the spawned camera
+    pub fn with_bundle(self, bundle: impl Bundle + Clone) -> Self {
+        self.with(move |camera| {
+            camera.insert_bundle(bundle.clone());
+        })
+    }
+}
+
+/// Spawns the `Camera3dBundle` to be controlled
+fn setup_player(
+    mut commands: Commands,
+    settings: Res<MovementSettings>,
+    spawn: Res<FlyCamSpawnSettings>,
+) {
+    let transform = Transform::from_xyz(-2.0, 5.0, 5.0).looking_at(Vec3::ZERO, settings.up_axis);
+    let mut camera = commands.spawn_bundle(Camera3dBundle {
+        camera: Camera {
+            target: spawn.target.clone(),
+            viewport: spawn.viewport.clone(),
+            priority: spawn.priority,
+            ..Default::default()
+        },
+        // In a rig the camera sits at its parent and the parent gets the pose.
+        transform: if spawn.rig {
+            Transform::identity()
+        } else {
+            transform
+        },
+        projection: Projection::Perspective(PerspectiveProjection {
+            fov: settings.fov,
+            near: settings.near,
+            far: settings.far,
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+    for customize in &spawn.customize {
+        customize(&mut camera);
+    }
+    if !spawn.rig {
+        camera.insert(FlyCam);
+        return;
+    }
+    camera.insert(rig::FlyCamRigCamera);
+    let camera = camera.id();
+    commands
+        .spawn_bundle(SpatialBundle {
+            transform,
+            ..Default::default()
+        })
+        .insert(FlyCam)
+        .insert(FlyCamRig { camera })
+        .add_child(camera);
+}
@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+
+use crate::target::ModeBlend;
+use crate::{FlyCam, FlyCamInputBlockers, FlyCamMode, FlyCamTarget, KeyBindings, MovementSettings};
+
+/// Marks an entity, e.g. a player, that flycams can spectate in [`FlyCamMode::Spectate`]
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Spectatable {
+    /// Where the camera sits relative to the entity, turned with its heading.
+    /// Negative z is in front of it.
+    pub offset: Vec3,
+}
+
+impl Default for Spectatable {
+    fn default() -> Self {
+        Self {
+            offset: Vec3::new(0., 2., 6.),
+        }
+    }
+}
+
+/// Which [`Spectatable`] a flycam is watching in [`FlyCamMode::Spectate`]. Added automatically
+/// to every [`FlyCam`]; `None` watches the first one there is.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FlyCamSpectator {
+    pub watching: Option<Entity>,
+}
+
+/// Cycles flycams from free flight through each [`Spectatable`] in turn and back to free flight
+/// with the spectate keys
+pub(crate) fn cycle_spectate(
+    keys: Res<Input<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    blockers: Res<FlyCamInputBlockers>,
+    spectatable: Query<Entity, With<Spectatable>>,
+    mut cameras: Query<
+        (
+            &Transform,
+            &mut FlyCamMode,
+            &mut FlyCamSpectator,
+            &mut ModeBlend,
+        ),
+        With<FlyCam>,
+    >,
+) {
+    let step = if blockers.blocked().hotkeys {
+        return;
+    } else if keys.any_just_pressed(bindings.spectate_next.iter().copied()) {
+        1
+    } else if keys.any_just_pressed(bindings.spectate_previous.iter().copied()) {
+        -1
+    } else {
+        return;
+    };
+    let mut targets: Vec<Entity> = spectatable.iter().collect();
+    targets.sort();
+
+    for (transform, mut mode, mut spectator, mut blend) in cameras.iter_mut() {
+        // Free flight sits at index 0, before the first spectatable.
+        let current = match (*mode, spectator.watching) {
+            (FlyCamMode::Spectate, Some(watching)) => targets
+                .iter()
+                .position(|target| *target == watching)
+                .map_or(0, |index| index + 1),
+            (FlyCamMode::Spectate, None) => 1,
+            _ => 0,
+        } as isize;
+        let next = (current + step).rem_euclid(targets.len() as isize + 1) as usize;
+        match next.checked_sub(1).map(|index| targets[index]) {
+            Some(target) => {
+                if *mode == FlyCamMode::Spectate {
+                    // Mode changes blend by themselves, so only switching players needs this.
+                    blend.start(transform);
+                } else {
+                    *mode = FlyCamMode::Spectate;
+                }
+                spectator.watching = Some(target);
+            }
+            None => {
+                if *mode == FlyCamMode::Spectate {
+                    *mode = FlyCamMode::Fly;
+                }
+            }
+        }
+    }
+}
+
+/// Keeps spectating flycams behind the entity they're watching, looking at it
+pub(crate) fn spectate_camera(
+    settings: Res<MovementSettings>,
+    spectatable: Query<(Entity, &GlobalTransform, &Spectatable)>,
+    mut cameras: Query<(&mut FlyCamTarget, &FlyCamMode, &mut FlyCamSpectator), With<FlyCam>>,
+) {
+    for (mut target, mode, mut spectator) in cameras.iter_mut() {
+        if *mode != FlyCamMode::Spectate {
+            continue;
+        }
+        let first = || spectatable.iter().min_by_key(|(entity, ..)| *entity);
+        let watched = match spectator.watching {
+            // Move on to someone else if the watched entity has gone.
+            Some(entity) => spectatable.get(entity).ok().or_else(first),
+            None => first(),
+        };
+        let (entity, global, spectatable) = match watched {
+            Some(watched) => watched,
+            None => continue,
+        };
+        if spectator.watching != Some(entity) {
+            spectator.watching = Some(entity);
+        }
+
+        let watched = global.compute_transform();
+        let mut up = settings.up_axis.normalize_or_zero();
+        if up == Vec3::ZERO {
+            up = Vec3::Y;
+        }
+        // Only follow the heading, so the camera doesn't tumble with the entity.
+        let forward = watched.forward();
+        let level = forward - up * forward.dot(up);
+        let heading = if level.length_squared() > 1e-6 {
+            Transform::default().looking_at(level, up).rotation
+        } else {
+            watched.rotation
+        };
+        let translation = watched.translation + heading * spectatable.offset;
+        target.translation = translation;
+        target.rotation = Transform::from_translation(translation)
+            .looking_at(watched.translation, up)
+            .rotation;
+    }
+}
@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+
+/// A flycam's Minecraft-style sprint, started by tapping forward twice, see
+/// [`MovementSettings::double_tap_sprint`](crate::MovementSettings::double_tap_sprint)
+#[derive(Component, Default)]
+pub(crate) struct DoubleTapSprint {
+    /// When forward was last tapped, on the [`FlyCamTime`](crate::FlyCamTime) clock
+    last_tap: Option<f64>,
+    pub(crate) sprinting: bool,
+}
+
+impl DoubleTapSprint {
+    /// Starts sprinting when forward is `tapped` within `window` seconds of the last tap, and
+    /// stops once it's no longer `held`. Returns whether it's sprinting.
+    pub(crate) fn update(&mut self, tapped: bool, held: bool, now: f64, window: f32) -> bool {
+        if tapped {
+            if self
+                .last_tap
+                .is_some_and(|last| now - last <= window as f64)
+            {
+                self.sprinting = true;
+                self.last_tap = None;
+            } else {
+                self.last_tap = Some(now);
+            }
+        }
+        if !held && self.sprinting {
+            self.sprinting = false;
+        }
+        self.sprinting
+    }
+}
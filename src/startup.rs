@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+use crate::{decode_pose, encode_pose, FlyCam, MovementSettings};
+
+/// Environment variable with the pose to start at, in the form [`encode_pose`](crate::encode_pose)
+/// writes
+pub const POSE_VAR: &str = "FLYCAM_POSE";
+/// Environment variable with the speed to start at
+pub const SPEED_VAR: &str = "FLYCAM_SPEED";
+/// File next to the app's `assets` folder that's read for the same variables, one `NAME=value`
+/// a line, when they aren't set in the environment
+pub const STARTUP_FILE: &str = "flycam-start.env";
+
+/// Where the first flycam starts and how fast, overriding the app's own spawn point and
+/// settings, so repeated debugging sessions start right where the bug is. Read from
+/// `FLYCAM_POSE` and `FLYCAM_SPEED` in the environment or `flycam-start.env` with the
+/// `startup-overrides` feature, unless the app inserts its own before adding the plugin, and
+/// used up when the first flycam appears.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FlyCamStartup {
+    pub pose: Option<Transform>,
+    pub speed: Option<f32>,
+}
+
+impl FlyCamStartup {
+    /// The overrides in the environment, falling back to [`STARTUP_FILE`] for each one that
+    /// isn't set there
+    pub fn from_env() -> Self {
+        let file = read_file();
+        let var = |name: &str| {
+            std::env::var(name).ok().or_else(|| {
+                file.lines()
+                    .filter_map(|line| line.trim().split_once('='))
+                    .find(|(key, _)| key.trim() == name)
+                    .map(|(_, value)| value.trim().to_owned())
+            })
+        };
+        let pose = var(POSE_VAR).and_then(|pose| {
+            let decoded = decode_pose(&pose);
+            if decoded.is_none() {
+                warn!("Ignoring {}, which isn't a pose: {}", POSE_VAR, pose);
+            }
+            decoded
+        });
+        let speed = var(SPEED_VAR).and_then(|speed| match speed.parse::<f32>() {
+            Ok(parsed) if parsed.is_finite() => Some(parsed),
+            _ => {
+                warn!("Ignoring {}, which isn't a number: {}", SPEED_VAR, speed);
+                None
+            }
+        });
+        Self { pose, speed }
+    }
+}
+
+/// The contents of [`STARTUP_FILE`], or nothing if there isn't one
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+fn read_file() -> String {
+    let file = bevy::asset::FileAssetIo::get_base_path().join(STARTUP_FILE);
+    std::fs::read_to_string(file).unwrap_or_default()
+}
+
+#[cfg(any(target_arch = "wasm32", target_os = "android"))]
+fn read_file() -> String {
+    String::new()
+}
+
+/// Moves the first flycam to the startup pose and sets the startup speed, before the flycam is
+/// set up so it starts there rather than gliding over
+pub(crate) fn apply_startup(
+    mut startup: ResMut<FlyCamStartup>,
+    mut settings: ResMut<MovementSettings>,
+    mut flycams: Query<&mut Transform, Added<FlyCam>>,
+) {
+    if *startup == FlyCamStartup::default() {
+        return;
+    }
+    let mut transform = match flycams.iter_mut().next() {
+        Some(transform) => transform,
+        None => return,
+    };
+    if let Some(pose) = startup.pose.take() {
+        info!(
+            "Starting the flycam at {} from {}",
+            encode_pose(&pose),
+            POSE_VAR
+        );
+        *transform = Transform {
+            scale: transform.scale,
+            ..pose
+        };
+    }
+    if let Some(speed) = startup.speed.take() {
+        info!("Starting the flycam at speed {} from {}", speed, SPEED_VAR);
+        settings.speed = speed;
+    }
+}
@@ -0,0 +1,241 @@
+use bevy::prelude::*;
+use bevy::time::FixedTimesteps;
+
+use crate::rig::{self, FlyCamRig};
+use crate::rotation::FlyCamFrame;
+use crate::{
+    FlyCamMode, FlyCamRotation, FlyCamSchedule, FlyCamTime, MovementSettings, FIXED_TIMESTEP,
+};
+
+/// The pose the flycam's input systems steer towards. A separate system then eases the
+/// camera's actual `Transform` towards it, using `translation_smoothing` and
+/// `rotation_smoothing` from [`MovementSettings`].
+///
+/// Added automatically to every [`FlyCam`](crate::FlyCam). Write to this rather than
+/// the `Transform` to move the camera from code and have it glide there; changes made to
+/// the `Transform` directly are picked up as the new target.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct FlyCamTarget {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    /// What was last written to the `Transform`, to notice when something else moved it
+    applied: Transform,
+}
+
+impl From<Transform> for FlyCamTarget {
+    fn from(transform: Transform) -> Self {
+        Self {
+            translation: transform.translation,
+            rotation: transform.rotation,
+            applied: transform,
+        }
+    }
+}
+
+impl FlyCamTarget {
+    /// Same as [`Transform::local_z`] for the target rotation
+    pub fn local_z(&self) -> Vec3 {
+        self.rotation * Vec3::Z
+    }
+
+    /// Same as [`Transform::forward`] for the target rotation
+    pub fn forward(&self) -> Vec3 {
+        -self.local_z()
+    }
+
+    /// Same as [`Transform::right`] for the target rotation
+    pub fn right(&self) -> Vec3 {
+        self.rotation * Vec3::X
+    }
+
+    /// Same as [`Transform::up`] for the target rotation
+    pub fn up(&self) -> Vec3 {
+        self.rotation * Vec3::Y
+    }
+
+    /// Moves the target by `-offset` without counting it as the camera being teleported
+    pub(crate) fn shift(&mut self, offset: Vec3) {
+        self.translation -= offset;
+        self.applied.translation -= offset;
+    }
+}
+
+/// How fast the flycam is moving, in units per second. Measured from how far its `Transform`
+/// actually moved each frame, so it includes scrolling, smoothing and every control mode.
+/// Jumps made by moving the `Transform` directly don't count.
+///
+/// Added automatically to every [`FlyCam`](crate::FlyCam).
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct FlyCamVelocity(pub Vec3);
+
+/// A blend in progress from the pose a flycam had when it switched modes, see
+/// [`MovementSettings::mode_blend_time`]
+#[derive(Component, Default)]
+pub(crate) struct ModeBlend {
+    from: Option<(Vec3, Quat)>,
+    elapsed: f32,
+}
+
+impl ModeBlend {
+    /// Starts blending from the given pose
+    pub(crate) fn start(&mut self, from: &Transform) {
+        self.from = Some((from.translation, from.rotation));
+        self.elapsed = 0.;
+    }
+
+    /// Moves the pose being blended from by `-offset`
+    pub(crate) fn shift(&mut self, offset: Vec3) {
+        if let Some((from, _)) = self.from.as_mut() {
+            *from -= offset;
+        }
+    }
+}
+
+/// Where a flycam's target was before the last fixed step, so the camera can be drawn part of
+/// the way from there between steps, see [`FlyCamSchedule::fixed`](crate::FlyCamSchedule::fixed)
+#[derive(Component, Default)]
+pub(crate) struct FixedStepStart(Option<(Vec3, Quat)>);
+
+/// Remembers where each flycam's target is before a fixed step moves it
+pub(crate) fn start_fixed_step(mut query: Query<(&FlyCamTarget, &mut FixedStepStart)>) {
+    for (target, mut start) in query.iter_mut() {
+        start.0 = Some((target.translation, target.rotation));
+    }
+}
+
+/// How far to move towards a target this frame for exponential smoothing with the given
+/// time constant. A time constant of 0 (or less) jumps straight there.
+pub(crate) fn smoothing_blend(time_constant: f32, dt: f32) -> f32 {
+    if time_constant <= 0. {
+        1.
+    } else {
+        1. - (-dt / time_constant).exp()
+    }
+}
+
+/// Eases each flycam's `Transform` towards its [`FlyCamTarget`]
+#[allow(clippy::type_complexity)]
+pub(crate) fn follow_target(
+    time: Res<FlyCamTime>,
+    settings: Res<MovementSettings>,
+    schedule: Res<FlyCamSchedule>,
+    fixed_timesteps: Option<Res<FixedTimesteps>>,
+    mut query: Query<(
+        &mut Transform,
+        &mut FlyCamTarget,
+        Option<&mut FlyCamRotation>,
+        Option<&FlyCamFrame>,
+        Option<&mut FlyCamVelocity>,
+        Option<&FlyCamMode>,
+        Option<&mut ModeBlend>,
+        Option<&FlyCamRig>,
+        Option<&mut FixedStepStart>,
+    )>,
+    mut rig_cameras: Query<&mut Transform, Without<FlyCamTarget>>,
+) {
+    let dt = time.delta_seconds();
+    let translation_blend = smoothing_blend(settings.translation_smoothing, dt);
+    let rotation_blend = smoothing_blend(settings.rotation_smoothing, dt);
+    // How far the clock has got towards the next fixed step
+    let overstep = schedule
+        .fixed_timestep
+        .and_then(|_| {
+            fixed_timesteps?
+                .get(FIXED_TIMESTEP)
+                .map(|state| state.overstep_percentage())
+        })
+        .map(|overstep| overstep.min(1.) as f32);
+
+    for (mut transform, mut target, rotation, frame, velocity, mode, blend, rig, step_start) in
+        query.iter_mut()
+    {
+        if mode == Some(&FlyCamMode::Frozen) {
+            continue;
+        }
+        let frame = frame.map_or(Quat::IDENTITY, |frame| frame.0);
+        // A rig splits the rotation between the yaw entity and its pitch child.
+        let mut rig_camera = rig.and_then(|rig| rig_cameras.get_mut(rig.camera).ok());
+        let current_rotation = match &rig_camera {
+            Some(camera) => transform.rotation * camera.rotation,
+            None => transform.rotation,
+        };
+
+        let teleported = *transform != target.applied;
+        if teleported {
+            // Something else moved the camera, so go along with it.
+            target.translation = transform.translation;
+            target.rotation = current_rotation;
+            if let Some(mut rotation) = rotation {
+                rotation.set_view(frame.inverse() * current_rotation);
+            }
+        }
+
+        // Between fixed steps, draw the camera part of the way through the last one, so it
+        // moves a little every frame instead of jumping once a step.
+        let (mut goal, mut goal_rotation) = (target.translation, target.rotation);
+        if let Some(mut step_start) = step_start {
+            if teleported {
+                step_start.0 = None;
+            }
+            if let (Some((from, from_rotation)), Some(overstep)) = (step_start.0, overstep) {
+                goal = from.lerp(goal, overstep);
+                goal_rotation = from_rotation.slerp(goal_rotation, overstep);
+            }
+        }
+        // The target keeps moving freely so slow movement adds up, only the camera snaps.
+        if settings.grid_snap > 0. {
+            goal = (goal / settings.grid_snap).round() * settings.grid_snap;
+        }
+        if let Some(mut blend) = blend {
+            if let Some((from, from_rotation)) = blend.from {
+                blend.elapsed += dt;
+                let t = blend.elapsed / settings.mode_blend_time;
+                if teleported || settings.mode_blend_time <= 0. || t >= 1. {
+                    blend.from = None;
+                } else {
+                    // Ease in and out between the old mode's pose and the new mode's target.
+                    let t = t * t * (3. - 2. * t);
+                    goal = from.lerp(goal, t);
+                    goal_rotation = from_rotation.slerp(goal_rotation, t);
+                }
+            }
+        }
+
+        let mut translation = transform.translation.lerp(goal, translation_blend);
+        if translation.distance_squared(goal) < 1e-8 {
+            translation = goal;
+        }
+        // Renormalized so rounding can't build up over a long session and skew the view.
+        let mut rotation = current_rotation
+            .slerp(goal_rotation, rotation_blend)
+            .normalize();
+        if rotation.angle_between(goal_rotation) < 1e-5 {
+            rotation = goal_rotation;
+        }
+
+        if let Some(camera) = &mut rig_camera {
+            let (yaw, pitch) = rig::split(frame, rotation);
+            if camera.rotation != pitch {
+                camera.rotation = pitch;
+            }
+            rotation = yaw;
+        }
+        if translation != transform.translation || rotation != transform.rotation {
+            transform.translation = translation;
+            transform.rotation = rotation;
+        }
+        if let Some(mut velocity) = velocity {
+            let moved = if teleported || dt <= 0. {
+                Vec3::ZERO
+            } else {
+                (transform.translation - target.applied.translation) / dt
+            };
+            if velocity.0 != moved {
+                velocity.0 = moved;
+            }
+        }
+        if target.applied != *transform {
+            target.applied = *transform;
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use crate::target::smoothing_blend;
+use crate::{FlyCam, FlyCamMode, FlyCamSwimming, FlyCamTarget, FlyCamTime};
+
+/// Where the ground is, for [`FlyCamMode::Terrain`]. Implement it on a heightmap, or on
+/// anything that can cast a ray down, e.g. a handle to your physics world.
+///
+/// Closures taking the position work too.
+pub trait HeightSource: Send + Sync + 'static {
+    /// Height of the ground along the global y axis at `position`, which is x and z in the
+    /// world. `None` where there's no ground, e.g. off the edge of the map.
+    fn height_at(&self, position: Vec2) -> Option<f32>;
+}
+
+impl<F> HeightSource for F
+where
+    F: Fn(Vec2) -> Option<f32> + Send + Sync + 'static,
+{
+    fn height_at(&self, position: Vec2) -> Option<f32> {
+        self(position)
+    }
+}
+
+/// Settings for [`FlyCamMode::Terrain`], which moves like [`FlyCamMode::Walk`] but rises and
+/// falls to stay `clearance` above the ground
+#[derive(Clone)]
+pub struct TerrainSettings {
+    /// Where the ground is. Without one the camera just stays level.
+    pub source: Option<Arc<dyn HeightSource>>,
+    /// How far above the ground the camera stays
+    pub clearance: f32,
+    /// Time constant in seconds for easing over bumps. 0 follows the ground exactly.
+    pub smoothing: f32,
+}
+
+impl Default for TerrainSettings {
+    fn default() -> Self {
+        Self {
+            source: None,
+            clearance: 2.,
+            smoothing: 0.2,
+        }
+    }
+}
+
+impl TerrainSettings {
+    /// Follows the ground given by `source`
+    pub fn with_source(mut self, source: impl HeightSource) -> Self {
+        self.source = Some(Arc::new(source));
+        self
+    }
+}
+
+/// Keeps terrain-following cameras at their clearance above the ground
+#[allow(clippy::type_complexity)]
+pub(crate) fn follow_terrain(
+    time: Res<FlyCamTime>,
+    settings: Res<TerrainSettings>,
+    mut query: Query<(&mut FlyCamTarget, &FlyCamMode), (With<FlyCam>, Without<FlyCamSwimming>)>,
+) {
+    let source = match &settings.source {
+        Some(source) => source,
+        None => return,
+    };
+    let blend = smoothing_blend(settings.smoothing, time.delta_seconds());
+    for (mut target, mode) in query.iter_mut() {
+        if *mode != FlyCamMode::Terrain {
+            continue;
+        }
+        let position = Vec2::new(target.translation.x, target.translation.z);
+        let ground = match source.height_at(position) {
+            Some(ground) => ground,
+            None => continue,
+        };
+        let height =
+            target.translation.y + (ground + settings.clearance - target.translation.y) * blend;
+        if height != target.translation.y {
+            target.translation.y = height;
+        }
+    }
+}
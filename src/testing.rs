@@ -0,0 +1,304 @@
+use bevy::ecs::event::Event;
+use bevy::hierarchy::HierarchyPlugin;
+use bevy::input::gamepad::{GamepadEventRaw, GamepadEventType};
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::mouse::{MouseButtonInput, MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::input::touch::{TouchInput, TouchPhase};
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::prelude::*;
+use bevy::transform::TransformPlugin;
+use bevy::utils::{Duration, Instant};
+use bevy::window::{WindowId, WindowPlugin, WindowSettings};
+use raw_window_handle::{RawWindowHandle, WebHandle};
+
+use crate::{
+    FlyCam, FlyCamPlayback, FlyCamRecording, FlyCamRotation, FlyCamSchedule, FlyCamTime,
+    MovementSettings, NoCameraPlayerPlugin,
+};
+
+/// A headless [`App`] with the flycam plugin, for tests that drive it with synthetic input.
+///
+/// There's no renderer or window backend, just a pretend primary window of
+/// [`WINDOW_WIDTH`](Self::WINDOW_WIDTH) by [`WINDOW_HEIGHT`](Self::WINDOW_HEIGHT) pixels with
+/// its cursor grabbed. Time only moves when [`FlyCamTestApp::advance`]
+/// says so, so results don't depend on how fast the test runs.
+///
+/// ```ignore
+/// let mut test = FlyCamTestApp::new();
+/// let camera = test.spawn_camera(Transform::default());
+/// test.press(KeyCode::W);
+/// test.advance(0.5);
+/// test.assert_translation(camera, Vec3::new(0., 0., -6.));
+/// ```
+pub struct FlyCamTestApp {
+    pub app: App,
+    /// The pretend wall clock
+    now: Instant,
+    /// The pretend game clock, which stands still while the game is paused
+    game_now: Instant,
+}
+
+impl FlyCamTestApp {
+    /// Width of the pretend window, in pixels
+    pub const WINDOW_WIDTH: f32 = 1280.;
+    /// Height of the pretend window, in pixels
+    pub const WINDOW_HEIGHT: f32 = 720.;
+
+    /// An app with [`NoCameraPlayerPlugin`] and default settings
+    pub fn new() -> Self {
+        Self::with_settings(MovementSettings::default())
+    }
+
+    /// An app with [`NoCameraPlayerPlugin`] and the given settings
+    pub fn with_settings(settings: MovementSettings) -> Self {
+        Self::build(settings, FlyCamSchedule::default())
+    }
+
+    /// An app with [`NoCameraPlayerPlugin`] running in the given stage
+    pub fn with_schedule(schedule: FlyCamSchedule) -> Self {
+        Self::build(MovementSettings::default(), schedule)
+    }
+
+    fn build(settings: MovementSettings, schedule: FlyCamSchedule) -> Self {
+        let mut app = App::new();
+        // No `TimePlugin`, which would set the time from the real clock.
+        app.init_resource::<Time>()
+            .add_plugin(InputPlugin)
+            .insert_resource(WindowSettings {
+                add_primary_window: false,
+                exit_on_all_closed: false,
+                close_when_requested: false,
+            })
+            .add_plugin(WindowPlugin)
+            .add_plugin(TransformPlugin)
+            .add_plugin(HierarchyPlugin)
+            .insert_resource(settings)
+            .insert_resource(schedule)
+            .add_plugin(NoCameraPlayerPlugin);
+
+        app.world.resource_mut::<Windows>().add(Window::new(
+            WindowId::primary(),
+            &WindowDescriptor {
+                width: Self::WINDOW_WIDTH,
+                height: Self::WINDOW_HEIGHT,
+                ..Default::default()
+            },
+            Self::WINDOW_WIDTH as u32,
+            Self::WINDOW_HEIGHT as u32,
+            1.,
+            None,
+            RawWindowHandle::Web(WebHandle::empty()),
+        ));
+
+        let now = Instant::now();
+        let mut test = Self {
+            app,
+            now,
+            game_now: now,
+        };
+        // Run the startup systems, which grab the cursor, with no time passing.
+        test.advance(0.);
+        test
+    }
+
+    /// Spawns a flycam at `transform` and runs a frame with no time passing so it's ready for
+    /// input
+    pub fn spawn_camera(&mut self, transform: Transform) -> Entity {
+        let camera = self
+            .app
+            .world
+            .spawn()
+            .insert_bundle(TransformBundle::from_transform(transform))
+            .insert(FlyCam)
+            .id();
+        self.advance(0.);
+        camera
+    }
+
+    /// Holds a key down until [`FlyCamTestApp::release`]
+    pub fn press(&mut self, key: KeyCode) {
+        self.send(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(key),
+            state: ButtonState::Pressed,
+        });
+    }
+
+    /// Plugs in a gamepad, which takes effect next frame
+    pub fn connect_gamepad(&mut self, id: usize) -> Gamepad {
+        let gamepad = Gamepad::new(id);
+        self.send(GamepadEventRaw::new(gamepad, GamepadEventType::Connected));
+        gamepad
+    }
+
+    /// Moves one of a gamepad's sticks along `axis` to `value`, from -1 to 1
+    pub fn set_gamepad_axis(&mut self, gamepad: Gamepad, axis: GamepadAxisType, value: f32) {
+        self.send(GamepadEventRaw::new(
+            gamepad,
+            GamepadEventType::AxisChanged(axis, value),
+        ));
+    }
+
+    /// Pushes a gamepad's trigger or other analog button to `value`, from 0 to 1
+    pub fn set_gamepad_button(&mut self, gamepad: Gamepad, button: GamepadButtonType, value: f32) {
+        self.send(GamepadEventRaw::new(
+            gamepad,
+            GamepadEventType::ButtonChanged(button, value),
+        ));
+    }
+
+    /// Lets go of a key
+    pub fn release(&mut self, key: KeyCode) {
+        self.send(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(key),
+            state: ButtonState::Released,
+        });
+    }
+
+    /// Holds a mouse button down until [`FlyCamTestApp::release_button`]
+    pub fn press_button(&mut self, button: MouseButton) {
+        self.send(MouseButtonInput {
+            button,
+            state: ButtonState::Pressed,
+        });
+    }
+
+    /// Lets go of a mouse button
+    pub fn release_button(&mut self, button: MouseButton) {
+        self.send(MouseButtonInput {
+            button,
+            state: ButtonState::Released,
+        });
+    }
+
+    /// Moves the mouse by `delta` pixels during the next frame
+    pub fn move_mouse(&mut self, delta: Vec2) {
+        self.send(MouseMotion { delta });
+    }
+
+    /// Puts the cursor at `position` in the primary window, in pixels from the bottom left, or
+    /// takes it out of the window. Only shows while the cursor isn't grabbed.
+    pub fn set_cursor(&mut self, position: Option<Vec2>) {
+        let mut windows = self.app.world.resource_mut::<Windows>();
+        if let Some(window) = windows.get_primary_mut() {
+            window.update_cursor_physical_position_from_backend(
+                position.map(|position| position.as_dvec2()),
+            );
+        }
+    }
+
+    /// Starts, moves or lifts finger `id` at `position` on the touchscreen, in pixels from the
+    /// top left, during the next frame
+    pub fn touch(&mut self, id: u64, phase: TouchPhase, position: Vec2) {
+        self.send(TouchInput {
+            phase,
+            position,
+            force: None,
+            id,
+        });
+    }
+
+    /// Turns the scroll wheel by `lines` during the next frame
+    pub fn scroll(&mut self, lines: f32) {
+        self.send(MouseWheel {
+            unit: MouseScrollUnit::Line,
+            x: 0.,
+            y: lines,
+        });
+    }
+
+    /// Sends any event, e.g. a [`FlyCamInput`](crate::FlyCamInput) or [`SetFlyCamMode`](crate::SetFlyCamMode),
+    /// to be handled during the next frame
+    pub fn send<E: Event>(&mut self, event: E) {
+        self.app.world.resource_mut::<Events<E>>().send(event);
+    }
+
+    /// The flycam settings, to change between frames
+    pub fn settings_mut(&mut self) -> Mut<'_, MovementSettings> {
+        self.app.world.resource_mut::<MovementSettings>()
+    }
+
+    /// Runs one frame that takes `seconds`
+    pub fn advance(&mut self, seconds: f32) {
+        self.game_now += Duration::from_secs_f32(seconds);
+        self.advance_paused(seconds);
+    }
+
+    /// Runs one frame that takes `seconds` on the wall clock while the game is paused, so
+    /// `Time` stands still
+    pub fn advance_paused(&mut self, seconds: f32) {
+        self.now += Duration::from_secs_f32(seconds);
+        let (now, game_now) = (self.now, self.game_now);
+        self.app.world.resource_mut::<FlyCamTime>().fake_now = Some(now);
+        self.app
+            .world
+            .resource_mut::<Time>()
+            .update_with_instant(game_now);
+        self.app.update();
+    }
+
+    /// Runs `frames` frames that each take `seconds`
+    pub fn advance_frames(&mut self, frames: usize, seconds: f32) {
+        for _ in 0..frames {
+            self.advance(seconds);
+        }
+    }
+
+    /// Plays `recording` on the camera, advancing each frame by as long as it took when it was
+    /// recorded so the motion comes out the same
+    pub fn play(&mut self, camera: Entity, recording: FlyCamRecording) {
+        let deltas: Vec<f32> = recording.frames.iter().map(|frame| frame.delta).collect();
+        self.app
+            .world
+            .entity_mut(camera)
+            .insert(FlyCamPlayback::new(recording));
+        for delta in deltas {
+            self.advance(delta);
+        }
+    }
+
+    /// The camera's current `Transform`
+    pub fn transform(&self, camera: Entity) -> Transform {
+        *self
+            .app
+            .world
+            .get::<Transform>(camera)
+            .expect("not a flycam spawned by this app")
+    }
+
+    /// The camera's current [`FlyCamRotation`]
+    pub fn rotation(&self, camera: Entity) -> FlyCamRotation {
+        *self
+            .app
+            .world
+            .get::<FlyCamRotation>(camera)
+            .expect("not a flycam spawned by this app")
+    }
+
+    /// Panics unless the camera is within a thousandth of a unit of `expected`
+    #[track_caller]
+    pub fn assert_translation(&self, camera: Entity, expected: Vec3) {
+        let translation = self.transform(camera).translation;
+        assert!(
+            translation.abs_diff_eq(expected, 1e-3),
+            "flycam is at {translation}, expected {expected}"
+        );
+    }
+
+    /// Panics unless the camera is facing within a thousandth of a radian of `expected`
+    #[track_caller]
+    pub fn assert_rotation(&self, camera: Entity, expected: Quat) {
+        let rotation = self.transform(camera).rotation;
+        assert!(
+            rotation.angle_between(expected) < 1e-3,
+            "flycam rotation is {rotation}, expected {expected}"
+        );
+    }
+}
+
+impl Default for FlyCamTestApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
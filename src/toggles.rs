@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+use std::ops::Deref;
+
+use bevy::ecs::schedule::{IntoSystemDescriptor, StageLabel};
+use bevy::prelude::*;
+
+use crate::{FlyCam, FlyCamMode, KeyBindings, MovementSettings};
+
+/// The keyboard as the flycam sees it: bevy's `Input<KeyCode>`, except that toggle keys stay
+/// held from one press until the next, for players who can't hold keys down. See
+/// [`KeyBindings::toggle_keys`], [`MovementSettings::toggle_boost`] and
+/// [`MovementSettings::toggle_crouch`], which only latches while a flycam is walking since the
+/// crouch keys descend when flying.
+///
+/// Read it instead of `Input<KeyCode>` in your own camera systems to honour the toggles.
+#[derive(Clone, Debug, Default)]
+pub struct FlyCamKeys {
+    keys: Input<KeyCode>,
+    latched: HashSet<KeyCode>,
+}
+
+impl Deref for FlyCamKeys {
+    type Target = Input<KeyCode>;
+
+    fn deref(&self) -> &Input<KeyCode> {
+        &self.keys
+    }
+}
+
+impl FlyCamKeys {
+    /// Whether `key` is a toggle key that's latched on
+    pub fn latched(&self, key: KeyCode) -> bool {
+        self.latched.contains(&key)
+    }
+}
+
+/// Every key that toggles instead of being held, with the crouch keys only when `walking`
+fn toggle_keys(
+    settings: &MovementSettings,
+    bindings: &KeyBindings,
+    walking: bool,
+) -> HashSet<KeyCode> {
+    let mut toggles: HashSet<KeyCode> = bindings.toggle_keys.iter().copied().collect();
+    if settings.toggle_boost {
+        toggles.extend(
+            settings
+                .boost_tiers
+                .iter()
+                .flat_map(|tier| tier.keys.iter()),
+        );
+    }
+    if settings.toggle_crouch && walking {
+        toggles.extend(bindings.for_mode(FlyCamMode::Walk).crouch.iter());
+    }
+    toggles
+}
+
+/// Adds [`FlyCamKeys`] and `latching`, [`latch_keys`] as the plugin orders it, once, for both
+/// the 3D and 2D plugins
+pub(crate) fn add_key_latching<Params>(
+    app: &mut App,
+    stage: impl StageLabel,
+    latching: impl IntoSystemDescriptor<Params>,
+) {
+    if app.world.contains_resource::<FlyCamKeys>() {
+        return;
+    }
+    app.init_resource::<FlyCamKeys>()
+        .add_system_to_stage(stage, latching);
+}
+
+/// Copies the keyboard into [`FlyCamKeys`], latching the toggle keys
+pub(crate) fn latch_keys(
+    keys: Res<Input<KeyCode>>,
+    settings: Res<MovementSettings>,
+    bindings: Res<KeyBindings>,
+    mut flycam_keys: ResMut<FlyCamKeys>,
+    flycams: Query<&FlyCamMode, With<FlyCam>>,
+) {
+    let walking = flycams.iter().any(|mode| *mode == FlyCamMode::Walk);
+    let toggles = toggle_keys(&settings, &bindings, walking);
+    let FlyCamKeys {
+        keys: latched_keys,
+        latched,
+    } = &mut *flycam_keys;
+    *latched_keys = keys.clone();
+    latched.retain(|key| toggles.contains(key));
+    for &key in &toggles {
+        let was_latched = latched.contains(&key);
+        if keys.just_pressed(key) && !latched.remove(&key) {
+            latched.insert(key);
+        }
+        latched_keys.reset(key);
+        match (was_latched, latched.contains(&key)) {
+            (false, true) => latched_keys.press(key),
+            (true, true) => {
+                latched_keys.press(key);
+                latched_keys.clear_just_pressed(key);
+            }
+            (true, false) => {
+                latched_keys.press(key);
+                latched_keys.release(key);
+                latched_keys.clear_just_pressed(key);
+            }
+            (false, false) => {}
+        }
+    }
+}
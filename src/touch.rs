@@ -0,0 +1,58 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::input::touch::{TouchInput, TouchPhase};
+use bevy::prelude::*;
+
+use crate::{
+    FlyCamAction, FlyCamInput, FlyCamInputBlockers, InputDevice, MovementSettings,
+    SensitivityProfiles,
+};
+
+/// Fingers taken by other touch controls, such as the virtual joysticks, which touch look
+/// leaves alone
+#[derive(Default)]
+pub(crate) struct ClaimedTouches(pub(crate) HashSet<u64>);
+
+/// Turns dragging on a touchscreen into turning, by [`MovementSettings::touch_sensitivity`].
+/// With several fingers down it follows their average, so pinching in place doesn't turn.
+pub(crate) fn touch_look(
+    mut touches: EventReader<TouchInput>,
+    // Where each finger on the screen was last seen
+    mut fingers: Local<HashMap<u64, Vec2>>,
+    claimed: Res<ClaimedTouches>,
+    settings: Res<MovementSettings>,
+    profiles: Res<SensitivityProfiles>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut actions: EventWriter<FlyCamInput>,
+) {
+    let mut drag = Vec2::ZERO;
+    for touch in touches
+        .iter()
+        .filter(|touch| !claimed.0.contains(&touch.id))
+    {
+        match touch.phase {
+            TouchPhase::Started => {
+                fingers.insert(touch.id, touch.position);
+            }
+            TouchPhase::Moved => {
+                if let Some(last) = fingers.insert(touch.id, touch.position) {
+                    drag += touch.position - last;
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                fingers.remove(&touch.id);
+            }
+        }
+    }
+    if drag == Vec2::ZERO || blockers.blocked().look {
+        return;
+    }
+    let mut drag = drag / fingers.len().max(1) as f32;
+    // Touches are measured from the top everywhere but on mobile, where bevy flips them.
+    if cfg!(any(target_os = "android", target_os = "ios")) {
+        drag.y = -drag.y;
+    }
+    // Like the mouse, dragging right turns right and dragging down looks down.
+    let turn = -drag * settings.touch_sensitivity * profiles.get(InputDevice::Touch);
+    actions.send(FlyCamInput::all(FlyCamAction::StickLook(turn)));
+}
@@ -0,0 +1,319 @@
+use bevy::prelude::*;
+
+use crate::rotation::FlyCamFrame;
+use crate::{FlyCamRotation, FlyCamTarget, FlyCamTime};
+
+/// A named place on a [`FlyCamTour`] for the camera to stop and show
+#[derive(Clone, Debug, PartialEq)]
+pub struct TourStop {
+    pub name: String,
+    pub translation: Vec3,
+    /// Which way the camera faces at the stop, unless it has a `look_at`
+    pub rotation: Quat,
+    /// Seconds it waits at the stop before flying on by itself, with
+    /// [`autoplay`](FlyCamTour::autoplay)
+    pub dwell: f32,
+    /// A point for the camera to face at the stop instead of `rotation`
+    pub look_at: Option<Vec3>,
+}
+
+impl TourStop {
+    /// A stop with the pose of `transform` that the camera doesn't wait at
+    pub fn new(name: impl Into<String>, transform: Transform) -> Self {
+        Self {
+            name: name.into(),
+            translation: transform.translation,
+            rotation: transform.rotation,
+            dwell: 0.,
+            look_at: None,
+        }
+    }
+
+    /// Waits `dwell` seconds at the stop
+    pub fn with_dwell(mut self, dwell: f32) -> Self {
+        self.dwell = dwell;
+        self
+    }
+
+    /// Faces `target` at the stop
+    pub fn looking_at(mut self, target: Vec3) -> Self {
+        self.look_at = Some(target);
+        self
+    }
+
+    /// Which way the camera faces at the stop
+    fn view(&self) -> Quat {
+        match self.look_at {
+            Some(target) if target.distance_squared(self.translation) > f32::EPSILON => {
+                Transform::from_translation(self.translation)
+                    .looking_at(target, Vec3::Y)
+                    .rotation
+            }
+            _ => self.rotation,
+        }
+    }
+}
+
+/// Add to a flycam to take it on a tour of [`stops`](Self::stops), like a walkthrough of a
+/// building: it flies from wherever it is to the first stop, waits there, then flies on to each
+/// of the others in turn, easing in and out of every stop. It ignores other input while it's on
+/// the flycam.
+///
+/// Send [`TourControl`]s, or call [`next`](Self::next), [`previous`](Self::previous) and
+/// [`goto`](Self::goto), to move through it at the presenter's pace. It sends a
+/// [`TourStopReached`] as it arrives at each stop and a [`TourStopLeft`] as it sets off again,
+/// to start narration or show annotations in time with the camera.
+#[derive(Component, Clone, Debug)]
+pub struct FlyCamTour {
+    pub stops: Vec<TourStop>,
+    /// Seconds each flight between stops takes
+    pub flight_time: f32,
+    /// Eases each flight: how far along it is, from 0 to 1, at each point in time from 0 to 1
+    pub curve: fn(f32) -> f32,
+    /// Whether it flies on by itself once it's waited at a stop, rather than waiting to be told
+    pub autoplay: bool,
+    /// Whether the stop after the last one is the first, and the one before the first the last
+    pub looping: bool,
+    stop: usize,
+    flight: Option<Flight>,
+    waited: f32,
+    /// The name of the stop it's just set off from, to send a [`TourStopLeft`] for
+    left: Option<String>,
+}
+
+/// A flight to the current stop
+#[derive(Clone, Copy, Debug)]
+struct Flight {
+    /// Where it set off from, or `None` before it's set off
+    from: Option<(Vec3, Quat)>,
+    elapsed: f32,
+}
+
+impl FlyCamTour {
+    pub fn new(stops: Vec<TourStop>) -> Self {
+        Self {
+            stops,
+            flight_time: 3.,
+            curve: |t| t * t * (3. - 2. * t),
+            autoplay: true,
+            looping: false,
+            stop: 0,
+            flight: Some(Flight {
+                from: None,
+                elapsed: 0.,
+            }),
+            waited: 0.,
+            left: None,
+        }
+    }
+
+    /// Takes `flight_time` seconds between stops
+    pub fn with_flight_time(mut self, flight_time: f32) -> Self {
+        self.flight_time = flight_time;
+        self
+    }
+
+    /// Eases each flight with `curve` instead of smoothstep
+    pub fn with_curve(mut self, curve: fn(f32) -> f32) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// The stop it's at or flying to
+    pub fn stop(&self) -> Option<&TourStop> {
+        self.stops.get(self.stop)
+    }
+
+    /// The index in [`stops`](Self::stops) of the stop it's at or flying to
+    pub fn stop_index(&self) -> usize {
+        self.stop
+    }
+
+    /// Whether it's at its stop rather than on the way there
+    pub fn is_at_stop(&self) -> bool {
+        self.flight.is_none()
+    }
+
+    /// Flies on to the next stop, if there is one
+    pub fn next(&mut self) {
+        if self.stop + 1 < self.stops.len() {
+            self.goto(self.stop + 1);
+        } else if self.looping {
+            self.goto(0);
+        }
+    }
+
+    /// Flies back to the stop before, if there is one
+    pub fn previous(&mut self) {
+        if self.stop > 0 {
+            self.goto(self.stop - 1);
+        } else if self.looping && !self.stops.is_empty() {
+            self.goto(self.stops.len() - 1);
+        }
+    }
+
+    /// Flies from wherever the camera is to the stop at `index` in [`stops`](Self::stops)
+    pub fn goto(&mut self, index: usize) {
+        if index >= self.stops.len() || (index == self.stop && self.is_at_stop()) {
+            return;
+        }
+        if self.is_at_stop() {
+            self.left = self.stop().map(|stop| stop.name.clone());
+        }
+        self.stop = index;
+        self.flight = Some(Flight {
+            from: None,
+            elapsed: 0.,
+        });
+    }
+
+    /// Flies to the first stop called `name`, returning whether there is one
+    pub fn goto_named(&mut self, name: &str) -> bool {
+        match self.stops.iter().position(|stop| stop.name == name) {
+            Some(index) => {
+                self.goto(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Acts on a [`TourCommand`]
+    pub fn command(&mut self, command: &TourCommand) {
+        match command {
+            TourCommand::Next => self.next(),
+            TourCommand::Previous => self.previous(),
+            TourCommand::Goto(index) => self.goto(*index),
+            TourCommand::GotoNamed(name) => {
+                self.goto_named(name);
+            }
+        }
+    }
+}
+
+/// Where to go next on a [`FlyCamTour`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TourCommand {
+    Next,
+    Previous,
+    /// The stop at this index
+    Goto(usize),
+    /// The first stop with this name
+    GotoNamed(String),
+}
+
+/// Sent when a [`FlyCamTour`] arrives at a stop
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TourStopReached {
+    pub camera: Entity,
+    /// The stop's name
+    pub stop: String,
+}
+
+/// Sent when a [`FlyCamTour`] sets off from a stop it had arrived at
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TourStopLeft {
+    pub camera: Entity,
+    /// The stop's name
+    pub stop: String,
+}
+
+/// A [`TourCommand`] for one flycam's tour, or for all of them
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TourControl {
+    /// The flycam to act on, or `None` for all of them
+    pub camera: Option<Entity>,
+    pub command: TourCommand,
+}
+
+impl TourControl {
+    /// A command for every tour
+    pub fn all(command: TourCommand) -> Self {
+        Self {
+            camera: None,
+            command,
+        }
+    }
+
+    /// A command for a single flycam's tour
+    pub fn camera(camera: Entity, command: TourCommand) -> Self {
+        Self {
+            camera: Some(camera),
+            command,
+        }
+    }
+}
+
+/// Flies each tour between its stops, taking over the camera's target
+pub(crate) fn take_tours(
+    time: Res<FlyCamTime>,
+    mut controls: EventReader<TourControl>,
+    mut reached: EventWriter<TourStopReached>,
+    mut left: EventWriter<TourStopLeft>,
+    mut query: Query<(
+        Entity,
+        &mut FlyCamTour,
+        &mut FlyCamTarget,
+        &mut FlyCamRotation,
+        Option<&FlyCamFrame>,
+    )>,
+) {
+    let dt = time.delta_seconds();
+    let controls: Vec<&TourControl> = controls.iter().collect();
+
+    for (camera, mut tour, mut target, mut rotation, frame) in query.iter_mut() {
+        for control in &controls {
+            if control.camera.is_none_or(|c| c == camera) {
+                tour.command(&control.command);
+            }
+        }
+        let stop = match tour.stop() {
+            Some(stop) => stop.clone(),
+            None => continue,
+        };
+        let view = stop.view();
+        let (translation, view) = match tour.flight {
+            Some(mut flight) => {
+                let (from, from_view) = *flight
+                    .from
+                    .get_or_insert((target.translation, target.rotation));
+                flight.elapsed += dt;
+                let t = if tour.flight_time > 0. {
+                    flight.elapsed / tour.flight_time
+                } else {
+                    1.
+                };
+                if t >= 1. {
+                    tour.flight = None;
+                    tour.waited = 0.;
+                    reached.send(TourStopReached {
+                        camera,
+                        stop: stop.name.clone(),
+                    });
+                    (stop.translation, view)
+                } else {
+                    tour.flight = Some(flight);
+                    let progress = (tour.curve)(t);
+                    (
+                        from.lerp(stop.translation, progress),
+                        from_view.slerp(view, progress),
+                    )
+                }
+            }
+            None => {
+                tour.waited += dt;
+                if tour.autoplay && tour.waited >= stop.dwell {
+                    tour.next();
+                }
+                (stop.translation, view)
+            }
+        };
+        if let Some(stop) = tour.left.take() {
+            left.send(TourStopLeft { camera, stop });
+        }
+        target.translation = translation;
+        target.rotation = view;
+        let frame = frame.map_or(Quat::IDENTITY, |frame| frame.0);
+        rotation.set_view(frame.inverse() * view);
+    }
+}
@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+
+use crate::rotation::wrap_angle;
+use crate::{FlyCam, FlyCamIdle, FlyCamMode, FlyCamRotation, FlyCamTarget, FlyCamTime, OrbitState};
+
+/// Slowly turns a flycam around what it's looking at once it's been left alone for a while,
+/// like a turntable in a product viewer or behind a menu. Any input, or anything else moving
+/// the camera, stops it again. Add it to a flycam to turn it on.
+///
+/// It turns [`FlyCamMode::Fly`] cameras around a point [`distance`](Self::distance) in front
+/// of them, and [`FlyCamMode::Orbit`] and [`FlyCamMode::MouseOnly`] cameras around their focus
+/// point.
+#[derive(Component, Clone, Debug)]
+pub struct FlyCamTurntable {
+    /// Seconds without input before the camera starts turning
+    pub idle_time: f32,
+    /// Radians per second the camera turns around the global y axis, positive to the left
+    pub speed: f32,
+    /// How far in front of a flying camera the point it turns around is
+    pub distance: f32,
+    /// Seconds since the last input, or since something else last moved the camera
+    idle: f32,
+    /// Where the camera last left its target, and its yaw and pitch, to tell whether anything
+    /// else has moved it
+    last: Option<(Vec3, Vec2)>,
+    /// The point being turned around while turning
+    focus: Option<Vec3>,
+}
+
+impl Default for FlyCamTurntable {
+    fn default() -> Self {
+        Self::new(10.)
+    }
+}
+
+impl FlyCamTurntable {
+    /// Starts turning after `idle_time` seconds without input
+    pub fn new(idle_time: f32) -> Self {
+        Self {
+            idle_time,
+            speed: 0.2,
+            distance: 10.,
+            idle: 0.,
+            last: None,
+            focus: None,
+        }
+    }
+
+    /// Whether the camera is turning at the moment
+    pub fn turning(&self) -> bool {
+        self.focus.is_some()
+    }
+
+    /// Starts counting the idle time again
+    fn wake(&mut self) {
+        self.idle = 0.;
+        self.focus = None;
+    }
+}
+
+/// Turns idle turntable flycams, waking them on any input
+#[allow(clippy::type_complexity)]
+pub(crate) fn turntable(
+    time: Res<FlyCamTime>,
+    idle: Res<FlyCamIdle>,
+    mut query: Query<
+        (
+            &mut FlyCamTurntable,
+            &mut FlyCamTarget,
+            &mut FlyCamRotation,
+            &FlyCamMode,
+            Option<&mut OrbitState>,
+        ),
+        With<FlyCam>,
+    >,
+) {
+    let dt = time.delta_seconds();
+    for (mut turntable, mut target, mut rotation, mode, orbit) in query.iter_mut() {
+        let mut orbit = match (mode, orbit) {
+            (FlyCamMode::Orbit | FlyCamMode::MouseOnly, Some(orbit)) => Some(orbit),
+            (FlyCamMode::Fly, _) => None,
+            _ => {
+                turntable.wake();
+                turntable.last = None;
+                continue;
+            }
+        };
+        let angles = |orbit: &Option<Mut<OrbitState>>, rotation: &FlyCamRotation| match orbit {
+            Some(orbit) => Vec2::new(orbit.yaw, orbit.pitch),
+            None => Vec2::new(rotation.yaw, rotation.pitch),
+        };
+        let moved = turntable.last.is_some_and(|(translation, last_angles)| {
+            // Orbit cameras rebuild the turned target themselves, rounding a little differently.
+            let tolerance = 1e-5 * (1. + translation.length());
+            translation.distance(target.translation) > tolerance
+                || !last_angles.abs_diff_eq(angles(&orbit, &rotation), 1e-5)
+        });
+        if idle.active() || moved {
+            turntable.wake();
+        } else {
+            turntable.idle += dt;
+        }
+        // Only the time past `idle_time` counts, so turning starts smoothly.
+        let turn = dt.min(turntable.idle - turntable.idle_time);
+        if turn > 0. && turntable.speed != 0. {
+            let ahead = target.translation + target.forward() * turntable.distance;
+            let focus = match &orbit {
+                Some(orbit) => orbit.focus,
+                None => turntable.focus.unwrap_or(ahead),
+            };
+            turntable.focus = Some(focus);
+            let angle = turntable.speed * turn;
+            let spin = Quat::from_rotation_y(angle);
+            target.translation = focus + spin * (target.translation - focus);
+            target.rotation = spin * target.rotation;
+            match &mut orbit {
+                Some(orbit) => orbit.yaw = wrap_angle(orbit.yaw + angle),
+                None => rotation.rotate(angle, 0., 0.),
+            }
+        }
+        turntable.last = Some((target.translation, angles(&orbit, &rotation)));
+    }
+}
@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use crate::{FlyCamPaths, FlyCamTour, FlyCamWaypoints};
+
+/// What a sphere runs into moving through the scene, for [`FlyCamPathValidator`]. Implement it
+/// with your physics engine's shape casts.
+///
+/// Closures taking the sweep's origin, direction, radius and length work too.
+pub trait SweepSource: Send + Sync + 'static {
+    /// How far a sphere of `radius` at `origin` can move in `direction` (a unit vector) before
+    /// it touches something, or `None` if it gets `max_distance` without touching anything
+    fn sweep(&self, origin: Vec3, direction: Vec3, radius: f32, max_distance: f32) -> Option<f32>;
+}
+
+impl<F> SweepSource for F
+where
+    F: Fn(Vec3, Vec3, f32, f32) -> Option<f32> + Send + Sync + 'static,
+{
+    fn sweep(&self, origin: Vec3, direction: Vec3, radius: f32, max_distance: f32) -> Option<f32> {
+        self(origin, direction, radius, max_distance)
+    }
+}
+
+/// Somewhere a camera path runs into the scene
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PathHit {
+    /// Which part of the path it's in: the pair of points, waypoints or tour stops it's
+    /// between, counting from 0 for the first and second
+    pub segment: usize,
+    /// Where the camera is when it first touches something
+    pub position: Vec3,
+    /// How far along the whole path that is
+    pub distance: f32,
+}
+
+/// What [`FlyCamPathValidator`] found along a path
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PathReport {
+    /// The first place in each part of the path that runs into something, in order
+    pub hits: Vec<PathHit>,
+    /// How long the whole path is
+    pub length: f32,
+}
+
+impl PathReport {
+    /// Whether the camera gets all the way along without touching anything
+    pub fn is_clear(&self) -> bool {
+        self.hits.is_empty()
+    }
+}
+
+/// Checks camera paths against the scene by sweeping a sphere the size of the camera along
+/// them, so cinematics that flew cleanly when they were made don't clip through walls after a
+/// level edit. Run it from a test or a tool, or whenever the level changes.
+#[derive(Clone)]
+pub struct FlyCamPathValidator {
+    pub source: Arc<dyn SweepSource>,
+    /// Radius of the sphere standing in for the camera, which should be at least its near
+    /// plane distance
+    pub radius: f32,
+}
+
+impl FlyCamPathValidator {
+    pub fn new(source: impl SweepSource) -> Self {
+        Self {
+            source: Arc::new(source),
+            radius: 0.25,
+        }
+    }
+
+    /// Checks the straight lines from each of `points` to the next
+    pub fn check_points(&self, points: &[Vec3]) -> PathReport {
+        self.check(
+            points
+                .windows(2)
+                .enumerate()
+                .map(|(segment, pair)| (segment, pair[0], pair[1])),
+        )
+    }
+
+    /// Checks the smooth path through some waypoints, sampled `per_segment` times between each
+    /// pair of them
+    pub fn check_waypoints(&self, waypoints: &FlyCamWaypoints, per_segment: usize) -> PathReport {
+        let per_segment = per_segment.max(1);
+        let points = waypoints.preview(per_segment);
+        self.check(
+            points
+                .windows(2)
+                .enumerate()
+                .map(|(step, pair)| (step / per_segment, pair[0], pair[1])),
+        )
+    }
+
+    /// Checks the flights between a tour's stops, and back to the first if it loops. The flight
+    /// to the first stop starts wherever the camera is, so isn't checked.
+    pub fn check_tour(&self, tour: &FlyCamTour) -> PathReport {
+        let mut points: Vec<Vec3> = tour.stops.iter().map(|stop| stop.translation).collect();
+        if tour.looping && points.len() > 1 {
+            points.push(points[0]);
+        }
+        self.check_points(&points)
+    }
+
+    /// Checks every path in [`FlyCamPaths`], including the recordings it's traced, by name
+    pub fn check_paths(&self, paths: &FlyCamPaths) -> Vec<(String, PathReport)> {
+        paths
+            .iter()
+            .map(|(name, points)| (name.to_owned(), self.check_points(points)))
+            .collect()
+    }
+
+    /// Sweeps along each line, noting the first hit in each part of the path
+    fn check(&self, lines: impl Iterator<Item = (usize, Vec3, Vec3)>) -> PathReport {
+        let mut report = PathReport::default();
+        for (segment, from, to) in lines {
+            let length = from.distance(to);
+            let already_hit = report.hits.last().map(|hit| hit.segment) == Some(segment);
+            if length > 0. && !already_hit {
+                let direction = (to - from) / length;
+                if let Some(distance) = self.source.sweep(from, direction, self.radius, length) {
+                    let distance = distance.clamp(0., length);
+                    report.hits.push(PathHit {
+                        segment,
+                        position: from + direction * distance,
+                        distance: report.length + distance,
+                    });
+                }
+            }
+            report.length += length;
+        }
+        report
+    }
+}
@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+
+use crate::rotation::wrap_angle;
+use crate::{
+    get_boost, pressed, FlyCam, FlyCamInputBlockers, FlyCamKeys, FlyCamMode, FlyCamTarget,
+    FlyCamTime, KeyBindings, MovementSettings,
+};
+
+/// Settings for [`FlyCamMode::Vehicle`], where A/D steer and W/S work the throttle.
+/// The top speed is [`MovementSettings::speed`], boosted as usual.
+/// Angles are in radians.
+pub struct VehicleSettings {
+    /// How quickly the throttle and brake change the speed, in units per second squared
+    pub acceleration: f32,
+    /// How much of its speed the vehicle loses per second while coasting
+    pub drag: f32,
+    /// Top reverse speed as a fraction of the top speed
+    pub reverse: f32,
+    /// Radians per second that A/D turn the vehicle
+    pub turn_speed: f32,
+    /// Radians per second that SPACE/RSHIFT pitch the nose up and down
+    pub climb_speed: f32,
+    /// Steepest the vehicle climbs or dives
+    pub max_pitch: f32,
+}
+
+impl Default for VehicleSettings {
+    fn default() -> Self {
+        Self {
+            acceleration: 12.,
+            drag: 0.3,
+            reverse: 0.3,
+            turn_speed: 1.5,
+            climb_speed: 1.,
+            max_pitch: 60f32.to_radians(),
+        }
+    }
+}
+
+/// How a vehicle camera is moving. Added when a flycam switches to [`FlyCamMode::Vehicle`].
+#[derive(Component, Clone, Copy, Debug)]
+pub struct VehicleState {
+    /// Units per second along the vehicle's heading, negative when reversing
+    pub speed: f32,
+    /// Heading around the global y axis
+    pub yaw: f32,
+    /// Nose up (positive) or down
+    pub pitch: f32,
+}
+
+/// Starts the vehicle at rest where the camera is, facing the same way
+#[allow(clippy::type_complexity)]
+pub(crate) fn enter_vehicle(
+    mut commands: Commands,
+    settings: Res<VehicleSettings>,
+    query: Query<(Entity, &Transform, &FlyCamMode), (With<FlyCam>, Changed<FlyCamMode>)>,
+) {
+    for (entity, transform, mode) in query.iter() {
+        if *mode != FlyCamMode::Vehicle {
+            continue;
+        }
+        let forward = transform.forward();
+        commands.entity(entity).insert(VehicleState {
+            speed: 0.,
+            yaw: f32::atan2(-forward.x, -forward.z),
+            pitch: forward
+                .y
+                .clamp(-1., 1.)
+                .asin()
+                .clamp(-settings.max_pitch, settings.max_pitch),
+        });
+    }
+}
+
+/// Steers with A/D, accelerates and brakes with W/S and pitches with SPACE/RSHIFT,
+/// coasting to a stop when nothing is held
+pub(crate) fn vehicle_camera(
+    keys: Res<FlyCamKeys>,
+    time: Res<FlyCamTime>,
+    settings: Res<MovementSettings>,
+    vehicle_settings: Res<VehicleSettings>,
+    bindings: Res<KeyBindings>,
+    blockers: Res<FlyCamInputBlockers>,
+    mut query: Query<(&mut FlyCamTarget, &FlyCamMode, &mut VehicleState), With<FlyCam>>,
+) {
+    let dt = time.delta_seconds();
+    let bindings = bindings.for_mode(FlyCamMode::Vehicle);
+
+    let mut throttle = 0.;
+    if pressed(&keys, &bindings.move_forward) {
+        throttle += 1.;
+    }
+    if pressed(&keys, &bindings.move_backward) {
+        throttle -= 1.;
+    }
+    let mut steer = 0.;
+    if pressed(&keys, &bindings.move_left) {
+        steer += 1.;
+    }
+    if pressed(&keys, &bindings.move_right) {
+        steer -= 1.;
+    }
+    let mut climb = 0.;
+    if pressed(&keys, &bindings.move_ascend) {
+        climb += 1.;
+    }
+    if pressed(&keys, &bindings.move_descend) {
+        climb -= 1.;
+    }
+    // Blocked, it coasts as if nothing were held.
+    if blockers.blocked().movement {
+        (throttle, steer, climb) = (0., 0., 0.);
+    }
+
+    let mut top_speed = settings.speed * get_boost(&keys, &settings);
+    if settings.max_speed > 0. {
+        top_speed = top_speed.min(settings.max_speed);
+    }
+    for (mut target, mode, mut state) in query.iter_mut() {
+        if *mode != FlyCamMode::Vehicle {
+            continue;
+        }
+        state.yaw = wrap_angle(state.yaw + steer * vehicle_settings.turn_speed * dt);
+        state.pitch = (state.pitch + climb * vehicle_settings.climb_speed * dt)
+            .clamp(-vehicle_settings.max_pitch, vehicle_settings.max_pitch);
+
+        let speed = if throttle != 0. {
+            state.speed + throttle * vehicle_settings.acceleration * dt
+        } else {
+            state.speed * (-vehicle_settings.drag * dt).exp()
+        };
+        state.speed = speed.clamp(-top_speed * vehicle_settings.reverse, top_speed);
+
+        let rotation = Quat::from_rotation_y(state.yaw) * Quat::from_rotation_x(state.pitch);
+        target.translation += rotation * -Vec3::Z * state.speed * dt;
+        target.rotation = rotation;
+    }
+}
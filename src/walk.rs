@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+
+use crate::target::smoothing_blend;
+use crate::{FlyCam, FlyCamMode, HeightSource};
+
+/// Settings for jumping and crouching in [`FlyCamMode::Walk`], and for walking on the ground
+/// when [`TerrainSettings::source`](crate::TerrainSettings) says where it is
+pub struct WalkSettings {
+    /// How high a jump lifts the camera, in units
+    pub jump_height: f32,
+    /// Downwards acceleration while jumping, in units per second squared
+    pub gravity: f32,
+    /// How far crouching lowers the camera
+    pub crouch_depth: f32,
+    /// How fast the camera goes down into a crouch and back up, in units per second
+    pub crouch_transition_speed: f32,
+    /// Multiplies the movement speed while fully crouched
+    pub crouch_speed_multiplier: f32,
+    /// How far above the ground the camera is when standing
+    pub eye_height: f32,
+    /// Tallest ledge the camera steps up onto, like a kerb or a stair. Anything taller
+    /// blocks the way, unless the camera jumps onto it.
+    pub max_step_height: f32,
+    /// Time constant in seconds for easing up a step
+    pub step_smoothing: f32,
+}
+
+impl Default for WalkSettings {
+    fn default() -> Self {
+        Self {
+            jump_height: 1.,
+            gravity: 9.81,
+            crouch_depth: 0.8,
+            crouch_transition_speed: 4.,
+            crouch_speed_multiplier: 0.5,
+            eye_height: 1.7,
+            max_step_height: 0.4,
+            step_smoothing: 0.1,
+        }
+    }
+}
+
+/// How a walking camera is standing. Added when a flycam switches to [`FlyCamMode::Walk`];
+/// without any ground to walk on, the height it switches at is where it stands.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct WalkState {
+    /// Standing height on the ground, once there's ground to walk on
+    pub floor: Option<f32>,
+    /// Height above standing height from jumping or falling, never negative
+    pub jump: f32,
+    /// Upwards speed of the jump, in units per second
+    pub vertical_speed: f32,
+    /// How far the camera has gone down into a crouch, up to
+    /// [`WalkSettings::crouch_depth`]
+    pub crouch: f32,
+}
+
+impl WalkState {
+    /// Whether the camera is standing on the ground rather than in the air
+    pub fn is_grounded(&self) -> bool {
+        self.jump <= 0. && self.vertical_speed <= 0.
+    }
+
+    /// Height of the camera relative to standing height
+    pub fn height(&self) -> f32 {
+        self.jump - self.crouch
+    }
+
+    /// How much of the movement speed is left while crouching
+    pub(crate) fn speed_multiplier(&self, settings: &WalkSettings) -> f32 {
+        if settings.crouch_depth <= 0. {
+            return 1.;
+        }
+        let amount = (self.crouch / settings.crouch_depth).clamp(0., 1.);
+        1. + (settings.crouch_speed_multiplier - 1.) * amount
+    }
+
+    /// Moves the jump and crouch on by `dt` seconds, returning how far the camera goes up
+    pub(crate) fn step(
+        &mut self,
+        settings: &WalkSettings,
+        jump: bool,
+        crouch: bool,
+        dt: f32,
+    ) -> f32 {
+        let before = self.height();
+
+        if jump && self.is_grounded() {
+            // Fast enough to reach jump_height before gravity stops it
+            self.vertical_speed = (2. * settings.gravity * settings.jump_height.max(0.)).sqrt();
+        }
+        if !self.is_grounded() {
+            self.jump += self.vertical_speed * dt;
+            self.vertical_speed -= settings.gravity * dt;
+            if self.jump <= 0. {
+                self.jump = 0.;
+                self.vertical_speed = 0.;
+            }
+        }
+
+        let depth = if crouch { settings.crouch_depth } else { 0. };
+        let change = settings.crouch_transition_speed * dt;
+        self.crouch = if self.crouch < depth {
+            (self.crouch + change).min(depth)
+        } else {
+            (self.crouch - change).max(depth)
+        };
+
+        self.height() - before
+    }
+
+    /// Stops `offset` from walking into ledges taller than a step, then steps up onto the
+    /// ground or falls down to it. Returns the movement to make instead, which also includes
+    /// the change in height.
+    pub(crate) fn follow_ground(
+        &mut self,
+        settings: &WalkSettings,
+        source: &dyn HeightSource,
+        position: Vec3,
+        mut offset: Vec3,
+        dt: f32,
+    ) -> Vec3 {
+        let floor = *self.floor.get_or_insert(position.y - self.height());
+        let standing = |point: Vec3| {
+            source
+                .height_at(Vec2::new(point.x, point.z))
+                .map(|ground| ground + settings.eye_height)
+        };
+        let mut ground = match standing(position + offset) {
+            Some(ground) => ground,
+            None => return offset,
+        };
+        // Jumping gets the camera onto taller ledges.
+        if ground - (floor + self.jump) > settings.max_step_height {
+            offset.x = 0.;
+            offset.z = 0.;
+            ground = match standing(position) {
+                Some(ground) => ground,
+                None => return offset,
+            };
+        }
+
+        let before = floor + self.jump;
+        if ground > floor {
+            // Keep the same height in the air, and ease up once on the step.
+            let rise = (ground - floor) * smoothing_blend(settings.step_smoothing, dt);
+            self.floor = Some(floor + rise);
+            self.jump = (self.jump - rise).max(0.);
+        } else {
+            // Fall the rest of the way.
+            self.floor = Some(ground);
+            self.jump += floor - ground;
+        }
+        let after = self.floor.unwrap_or(ground) + self.jump;
+        offset.y += after - before;
+        offset
+    }
+}
+
+/// Starts walking cameras standing where they are
+#[allow(clippy::type_complexity)]
+pub(crate) fn enter_walk(
+    mut commands: Commands,
+    query: Query<(Entity, &FlyCamMode), (With<FlyCam>, Changed<FlyCamMode>)>,
+) {
+    for (entity, mode) in query.iter() {
+        if *mode == FlyCamMode::Walk {
+            commands.entity(entity).insert(WalkState::default());
+        }
+    }
+}
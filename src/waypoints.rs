@@ -0,0 +1,347 @@
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+
+use crate::{FlyCam, FlyCamKeys, FlyCamPaths, FlyCamSchedule, FlyCamSystem, FlyCamTarget};
+
+/// A camera pose along a [`FlyCamWaypoints`] path
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlyCamWaypoint {
+    pub translation: Vec3,
+    pub rotation: Quat,
+}
+
+/// Poses a camera path goes through, in order. The waypoint editor added by
+/// [`FlyCamWaypointEditorPlugin`] edits the one in the resource of this type, and with the `ron`
+/// feature they're an asset saved as `.waypoints.ron`.
+#[derive(Clone, Debug, Default, PartialEq, TypeUuid)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[uuid = "7a0d6b0e-38a1-4a4c-9d71-0f5c8e2b9a43"]
+pub struct FlyCamWaypoints {
+    pub waypoints: Vec<FlyCamWaypoint>,
+}
+
+impl FlyCamWaypoints {
+    /// The pose `t` of the way along the path, counting one per waypoint: 0 is the first, 1 the
+    /// second and 1.5 halfway from there to the third. The path curves smoothly through the
+    /// waypoints (a Catmull-Rom spline) and turns evenly between them. `None` without waypoints.
+    pub fn sample(&self, t: f32) -> Option<FlyCamWaypoint> {
+        let last = self.waypoints.len().checked_sub(1)?;
+        let t = t.clamp(0., last as f32);
+        let i = (t.floor() as usize).min(last.saturating_sub(1));
+        let s = t - i as f32;
+        let at = |i: isize| self.waypoints[i.clamp(0, last as isize) as usize];
+        let i = i as isize;
+        let [p0, p1, p2, p3] = [at(i - 1), at(i), at(i + 1), at(i + 2)].map(|w| w.translation);
+        let translation = 0.5
+            * (2. * p1
+                + (p2 - p0) * s
+                + (2. * p0 - 5. * p1 + 4. * p2 - p3) * s * s
+                + (3. * p1 - p0 - 3. * p2 + p3) * s * s * s);
+        Some(FlyCamWaypoint {
+            translation,
+            rotation: at(i).rotation.slerp(at(i + 1).rotation, s).normalize(),
+        })
+    }
+
+    /// Points along the path, `per_segment` between each pair of waypoints, for previewing it
+    pub fn preview(&self, per_segment: usize) -> Vec<Vec3> {
+        let segments = self.waypoints.len().saturating_sub(1);
+        let steps = segments * per_segment.max(1);
+        if steps == 0 {
+            return self.waypoints.iter().map(|w| w.translation).collect();
+        }
+        (0..=steps)
+            .filter_map(|step| self.sample(step as f32 / steps as f32 * segments as f32))
+            .map(|waypoint| waypoint.translation)
+            .collect()
+    }
+
+    /// Makes an edit, ignoring indices past the end
+    pub fn apply(&mut self, edit: WaypointEdit) {
+        let len = self.waypoints.len();
+        match edit {
+            WaypointEdit::Push(waypoint) => self.waypoints.push(waypoint),
+            WaypointEdit::Insert(index, waypoint) => {
+                self.waypoints.insert(index.min(len), waypoint)
+            }
+            WaypointEdit::Remove(index) if index < len => {
+                self.waypoints.remove(index);
+            }
+            WaypointEdit::Move { from, to } if from < len && to < len => {
+                let waypoint = self.waypoints.remove(from);
+                self.waypoints.insert(to, waypoint);
+            }
+            WaypointEdit::Replace(waypoints) => *self = waypoints,
+            WaypointEdit::Clear => self.waypoints.clear(),
+            WaypointEdit::Remove(_) | WaypointEdit::Move { .. } => {}
+        }
+    }
+
+    /// The waypoints as RON, for saving as a `.waypoints.ron` asset
+    #[cfg(feature = "ron")]
+    pub fn to_ron(&self) -> ron::Result<String> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    /// Reads waypoints saved with [`FlyCamWaypoints::to_ron`]
+    #[cfg(feature = "ron")]
+    pub fn from_ron(ron: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(ron)
+    }
+}
+
+/// Changes the waypoints being edited, see [`FlyCamWaypointEditorPlugin`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum WaypointEdit {
+    /// Adds a waypoint at the end
+    Push(FlyCamWaypoint),
+    /// Adds a waypoint before the one at the index, or at the end
+    Insert(usize, FlyCamWaypoint),
+    Remove(usize),
+    /// Moves the waypoint at `from` to `to`, shifting the ones between along
+    Move {
+        from: usize,
+        to: usize,
+    },
+    /// Swaps in a whole new set, e.g. one you loaded yourself
+    Replace(FlyCamWaypoints),
+    Clear,
+}
+
+/// Settings for the waypoint editor added by [`FlyCamWaypointEditorPlugin`]
+pub struct FlyCamWaypointEditorSettings {
+    /// Keys that go into and out of the editor
+    pub toggle: Vec<KeyCode>,
+    /// Keys that drop a waypoint at the camera's pose while editing
+    pub drop: Vec<KeyCode>,
+    /// Keys that save the waypoints to `file` while editing
+    pub save: Vec<KeyCode>,
+    /// Asset path of a `.waypoints.ron` file to load the waypoints from and save them to,
+    /// with the `ron` feature. Needs bevy's `AssetPlugin`.
+    pub file: Option<String>,
+    /// How many points the preview draws between each pair of waypoints
+    pub preview_detail: usize,
+}
+
+impl Default for FlyCamWaypointEditorSettings {
+    fn default() -> Self {
+        Self {
+            toggle: vec![KeyCode::F7],
+            drop: vec![KeyCode::Insert],
+            save: vec![KeyCode::F8],
+            file: None,
+            preview_detail: 16,
+        }
+    }
+}
+
+/// Whether the waypoint editor is on
+#[derive(Default)]
+pub struct FlyCamWaypointEditor {
+    active: bool,
+}
+
+impl FlyCamWaypointEditor {
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+}
+
+/// Name of the editor's preview in [`FlyCamPaths`]
+const PREVIEW: &str = "waypoints";
+
+/// Adds an editor for laying out camera paths in the app: the toggle key turns it on, and the
+/// drop key then adds a waypoint wherever the first flycam is, looking where it looks. Send
+/// [`WaypointEdit`] events to reorder and delete them. With
+/// [`FlyCamPathsPlugin`](crate::FlyCamPathsPlugin) too, the path through them is previewed
+/// while editing.
+///
+/// The waypoints are in the [`FlyCamWaypoints`] resource. With the `ron` feature and
+/// [`FlyCamWaypointEditorSettings::file`] set, they're loaded from that asset whenever it
+/// (re)loads, and the save key writes them back on desktop.
+///
+/// Add it alongside [`PlayerPlugin`](crate::PlayerPlugin) or
+/// [`NoCameraPlayerPlugin`](crate::NoCameraPlayerPlugin).
+pub struct FlyCamWaypointEditorPlugin;
+impl Plugin for FlyCamWaypointEditorPlugin {
+    fn build(&self, app: &mut App) {
+        let stage = FlyCamSchedule::stage(app);
+        app.init_resource::<FlyCamWaypointEditorSettings>()
+            .init_resource::<FlyCamWaypointEditor>()
+            .init_resource::<FlyCamWaypoints>()
+            .add_event::<WaypointEdit>()
+            .add_system_to_stage(stage, waypoint_editor.after(FlyCamSystem::Control))
+            .add_system_to_stage(stage, apply_edits.after(waypoint_editor))
+            .add_system_to_stage(stage, preview_waypoints.after(apply_edits));
+        #[cfg(feature = "ron")]
+        file::add_file(app);
+    }
+}
+
+/// Toggles the editor and drops waypoints
+fn waypoint_editor(
+    keys: Res<FlyCamKeys>,
+    settings: Res<FlyCamWaypointEditorSettings>,
+    mut editor: ResMut<FlyCamWaypointEditor>,
+    mut edits: EventWriter<WaypointEdit>,
+    flycams: Query<&FlyCamTarget, With<FlyCam>>,
+) {
+    if keys.any_just_pressed(settings.toggle.iter().copied()) {
+        editor.active = !editor.active;
+    }
+    if !editor.active || !keys.any_just_pressed(settings.drop.iter().copied()) {
+        return;
+    }
+    if let Some(target) = flycams.iter().next() {
+        edits.send(WaypointEdit::Push(FlyCamWaypoint {
+            translation: target.translation,
+            rotation: target.rotation,
+        }));
+    }
+}
+
+/// Makes the edits sent this frame
+fn apply_edits(mut edits: EventReader<WaypointEdit>, mut waypoints: ResMut<FlyCamWaypoints>) {
+    for edit in edits.iter() {
+        waypoints.apply(edit.clone());
+    }
+}
+
+/// Shows the path through the waypoints while editing
+fn preview_waypoints(
+    settings: Res<FlyCamWaypointEditorSettings>,
+    editor: Res<FlyCamWaypointEditor>,
+    waypoints: Res<FlyCamWaypoints>,
+    paths: Option<ResMut<FlyCamPaths>>,
+) {
+    let mut paths = match paths {
+        Some(paths) => paths,
+        None => return,
+    };
+    if !editor.active {
+        if paths.get(PREVIEW).is_some() {
+            paths.remove(PREVIEW);
+        }
+    } else if editor.is_changed() || waypoints.is_changed() {
+        paths.set(PREVIEW, waypoints.preview(settings.preview_detail));
+    }
+}
+
+/// Loading and saving the waypoints as a `.waypoints.ron` asset
+#[cfg(feature = "ron")]
+mod file {
+    use bevy::asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset};
+    use bevy::prelude::*;
+
+    use super::{FlyCamWaypointEditor, FlyCamWaypointEditorSettings, FlyCamWaypoints};
+    use crate::FlyCamKeys;
+
+    #[derive(Default)]
+    struct FlyCamWaypointsLoader;
+
+    impl AssetLoader for FlyCamWaypointsLoader {
+        fn load<'a>(
+            &'a self,
+            bytes: &'a [u8],
+            load_context: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+            Box::pin(async move {
+                let waypoints: FlyCamWaypoints = ron::de::from_bytes(bytes)?;
+                load_context.set_default_asset(LoadedAsset::new(waypoints));
+                Ok(())
+            })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["waypoints.ron"]
+        }
+    }
+
+    /// Keeps the waypoints asset loaded
+    struct WaypointsHandle(Handle<FlyCamWaypoints>);
+
+    /// Loads the settings' file, if there is one
+    pub(super) fn add_file(app: &mut App) {
+        let path = match &app.world.resource::<FlyCamWaypointEditorSettings>().file {
+            Some(path) => path.clone(),
+            None => return,
+        };
+        app.add_asset::<FlyCamWaypoints>()
+            .init_asset_loader::<FlyCamWaypointsLoader>()
+            .add_system(load_waypoints)
+            .add_system(save_waypoints);
+        let handle = app
+            .world
+            .resource::<AssetServer>()
+            .load::<FlyCamWaypoints, _>(path.as_str());
+        app.insert_resource(WaypointsHandle(handle));
+    }
+
+    /// Copies the file into the resource whenever it (re)loads
+    fn load_waypoints(
+        mut events: EventReader<AssetEvent<FlyCamWaypoints>>,
+        assets: Res<Assets<FlyCamWaypoints>>,
+        handle: Res<WaypointsHandle>,
+        mut waypoints: ResMut<FlyCamWaypoints>,
+    ) {
+        for event in events.iter() {
+            let changed = match event {
+                AssetEvent::Created { handle } | AssetEvent::Modified { handle } => handle,
+                AssetEvent::Removed { .. } => continue,
+            };
+            if *changed != handle.0 {
+                continue;
+            }
+            if let Some(loaded) = assets.get(changed) {
+                *waypoints = loaded.clone();
+            }
+        }
+    }
+
+    /// Writes the resource to the file when the save key is pressed while editing
+    fn save_waypoints(
+        keys: Res<FlyCamKeys>,
+        settings: Res<FlyCamWaypointEditorSettings>,
+        editor: Res<FlyCamWaypointEditor>,
+        waypoints: Res<FlyCamWaypoints>,
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android")))] asset_settings: Option<
+            Res<bevy::asset::AssetServerSettings>,
+        >,
+    ) {
+        if !editor.is_active() || !keys.any_just_pressed(settings.save.iter().copied()) {
+            return;
+        }
+        let (path, ron) = match (&settings.file, waypoints.to_ron()) {
+            (Some(path), Ok(ron)) => (path, ron),
+            (_, Err(err)) => {
+                warn!("Could not save the waypoints: {}", err);
+                return;
+            }
+            (None, _) => return,
+        };
+        #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+        {
+            let folder =
+                asset_settings.map_or("assets".into(), |settings| settings.asset_folder.clone());
+            let file = bevy::asset::FileAssetIo::get_base_path()
+                .join(folder)
+                .join(path);
+            match std::fs::write(&file, ron) {
+                Ok(()) => info!("Saved the waypoints to {}", file.display()),
+                Err(err) => warn!(
+                    "Could not save the waypoints to {}: {}",
+                    file.display(),
+                    err
+                ),
+            }
+        }
+        #[cfg(any(target_arch = "wasm32", target_os = "android"))]
+        {
+            info!(
+                "Can't save {} here, so here are the waypoints:\n{}",
+                path, ron
+            );
+        }
+    }
+}
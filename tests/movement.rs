@@ -0,0 +1,2476 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use bevy::ecs::system::SystemState;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy::render::camera::Projection;
+use bevy::render::primitives::Frustum;
+use bevy_flycam::{
+    decode_pose, encode_pose, BlockedInput, FlyCam, FlyCamAction, FlyCamAutoSpeed, FlyCamAutofocus,
+    FlyCamDollyZoom, FlyCamFluid, FlyCamFrozenFrustum, FlyCamFrustumPlugin, FlyCamGamepadSettings,
+    FlyCamInput, FlyCamInputBlockers, FlyCamMode, FlyCamNetProxy, FlyCamNetState, FlyCamOrigin,
+    FlyCamOriginPlugin, FlyCamOriginSettings, FlyCamPathPlayback, FlyCamPathPlaybackSettings,
+    FlyCamPathValidator, FlyCamPaths, FlyCamPathsPlugin, FlyCamPhotoMode, FlyCamPhotoPlugin,
+    FlyCamPrecisePosition, FlyCamRays, FlyCamRecorder, FlyCamRig, FlyCamRotation, FlyCamSchedule,
+    FlyCamSessions, FlyCamShiftWithOrigin, FlyCamSwimming, FlyCamTarget, FlyCamTestApp,
+    FlyCamTimeSource, FlyCamTour, FlyCamWaypoint, FlyCamWaypointEditorPlugin, FlyCamWaypoints,
+    FocusDistance, InputDevice, ModeChanged, MovementSettings, OriginShift, PathHit,
+    PathPlaybackFinished, PhotoTaken, SensitivityProfiles, SetFlyCamMode, Spectatable,
+    TerrainSettings, TourCommand, TourControl, TourStop, TourStopLeft, TourStopReached,
+    WaypointEdit,
+};
+
+#[test]
+fn forward_moves_at_speed() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -6.));
+
+    test.release(KeyCode::W);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -6.));
+}
+
+#[test]
+fn diagonal_is_no_faster() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+
+    test.press(KeyCode::W);
+    test.press(KeyCode::D);
+    test.advance(1.);
+    let translation = test.transform(camera).translation;
+    assert!((translation.length() - 12.).abs() < 1e-3, "{translation}");
+    assert!(
+        (translation.x + translation.z).abs() < 1e-3,
+        "{translation}"
+    );
+}
+
+#[test]
+fn boost_tiers_multiply() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+
+    test.press(KeyCode::LShift);
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -24.));
+
+    // The tier with the most held keys wins.
+    test.press(KeyCode::LControl);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -120.));
+}
+
+#[test]
+fn forward_stays_level() {
+    let mut test = FlyCamTestApp::new();
+    let camera =
+        test.spawn_camera(Transform::default().looking_at(Vec3::new(0., -1., -1.), Vec3::Y));
+
+    test.press(KeyCode::W);
+    test.advance(1.);
+    test.assert_translation(camera, Vec3::new(0., 0., -12.));
+}
+
+#[test]
+fn max_speed_caps_boosting() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        max_speed: 20.,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+
+    test.press(KeyCode::W);
+    test.press(KeyCode::LShift);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -10.));
+}
+
+#[test]
+fn max_speed_caps_translate_actions_and_vehicles() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        max_speed: 20.,
+        mode_blend_time: 0.,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+
+    // A gamepad or network source asking for 100 units in half a second only gets 10.
+    test.send(FlyCamInput::all(FlyCamAction::Translate(Vec3::new(
+        0., 0., -100.,
+    ))));
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -10.));
+
+    // A frame where no time passes has no speed to cap.
+    test.send(FlyCamInput::all(FlyCamAction::Translate(Vec3::new(
+        0., 0., -1.,
+    ))));
+    test.advance(0.);
+    test.assert_translation(camera, Vec3::new(0., 0., -11.));
+
+    test.settings_mut().speed = 100.;
+    test.send(SetFlyCamMode {
+        camera: Some(camera),
+        mode: FlyCamMode::Vehicle,
+    });
+    test.advance_frames(2, 0.);
+    test.press(KeyCode::W);
+    test.advance_frames(100, 0.1);
+    let before = test.transform(camera).translation;
+    test.advance(0.5);
+    let travelled = test.transform(camera).translation.distance(before);
+    assert!(
+        (travelled - 10.).abs() < 1e-3,
+        "vehicle went {} in half a second",
+        travelled
+    );
+}
+
+#[test]
+fn cruising_ramps_up_and_resets() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        cruise_multiplier: 3.,
+        cruise_delay: 1.,
+        cruise_ramp_time: 1.,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+
+    test.press(KeyCode::W);
+    test.advance_frames(10, 0.1);
+    test.assert_translation(camera, Vec3::new(0., 0., -12.));
+
+    // 1.2, 1.4, ... 3 times 1.2 units a frame as the cruise ramps up
+    test.advance_frames(10, 0.1);
+    test.assert_translation(camera, Vec3::new(0., 0., -37.2));
+
+    test.release(KeyCode::W);
+    test.advance(0.1);
+    test.press(KeyCode::W);
+    test.advance(0.1);
+    test.assert_translation(camera, Vec3::new(0., 0., -38.4));
+}
+
+#[test]
+fn fly_along_look_follows_the_view() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        fly_along_look: true,
+        ..Default::default()
+    });
+    let camera =
+        test.spawn_camera(Transform::default().looking_at(Vec3::new(0., -1., -1.), Vec3::Y));
+
+    test.press(KeyCode::W);
+    test.advance(1.);
+    let expected = Vec3::new(0., -1., -1.).normalize() * 12.;
+    test.assert_translation(camera, expected);
+}
+
+#[test]
+fn ascend_uses_world_up() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::from_rotation(Quat::from_rotation_x(-0.5)));
+
+    test.press(KeyCode::Space);
+    test.advance(0.25);
+    test.assert_translation(camera, Vec3::new(0., 3., 0.));
+}
+
+#[test]
+fn stepping_moves_once_per_press() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        stepping: true,
+        step_distance: 2.,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+
+    test.press(KeyCode::D);
+    test.advance_frames(5, 0.1);
+    test.assert_translation(camera, Vec3::new(2., 0., 0.));
+
+    test.release(KeyCode::D);
+    test.advance(0.1);
+    test.press(KeyCode::D);
+    test.advance(0.1);
+    test.assert_translation(camera, Vec3::new(4., 0., 0.));
+}
+
+#[test]
+fn plane_lock_blocks_the_axis() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        plane_lock: true,
+        fly_along_look: true,
+        ..Default::default()
+    });
+    let camera =
+        test.spawn_camera(Transform::default().looking_at(Vec3::new(0., -1., -1.), Vec3::Y));
+
+    test.press(KeyCode::W);
+    test.press(KeyCode::Space);
+    test.advance(1.);
+    let translation = test.transform(camera).translation;
+    assert_eq!(translation.y, 0.);
+    assert!(translation.z < 0., "{translation}");
+}
+
+#[test]
+fn mouse_look_turns_by_sensitivity() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+
+    test.move_mouse(Vec2::new(100., 0.));
+    test.advance(0.1);
+    let settings = MovementSettings::default();
+    let degrees = settings.sensitivity * 100. * FlyCamTestApp::WINDOW_HEIGHT;
+    test.assert_rotation(camera, Quat::from_rotation_y(-degrees.to_radians()));
+
+    test.move_mouse(Vec2::new(0., 1e6));
+    test.advance(0.1);
+    let rotation = test.rotation(camera);
+    assert_eq!(rotation.pitch, -PI / 2.);
+}
+
+#[test]
+fn zooming_in_slows_mouse_look() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    let settings = MovementSettings::default();
+    let degrees = settings.sensitivity * 100. * FlyCamTestApp::WINDOW_HEIGHT;
+
+    test.press(KeyCode::C);
+    test.advance(1.);
+    test.move_mouse(Vec2::new(100., 0.));
+    test.advance(0.1);
+    let zoomed = degrees * settings.zoom_fov / settings.fov;
+    test.assert_rotation(camera, Quat::from_rotation_y(-zoomed.to_radians()));
+
+    test.settings_mut().fov_compensated_look = false;
+    test.move_mouse(Vec2::new(100., 0.));
+    test.advance(0.1);
+    test.assert_rotation(
+        camera,
+        Quat::from_rotation_y(-(zoomed + degrees).to_radians()),
+    );
+}
+
+#[test]
+fn yaw_keys_turn_with_speed() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+
+    test.press(KeyCode::Q);
+    test.advance(0.5);
+    let expected = 12. * 0.5 / 100. * PI * 2.;
+    test.assert_rotation(camera, Quat::from_rotation_y(expected));
+}
+
+#[test]
+fn scroll_dollies_forward() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+
+    test.scroll(1.);
+    test.advance(0.1);
+    let distance = 1024. * MovementSettings::default().sensitivity;
+    test.assert_translation(camera, Vec3::new(0., 0., -distance));
+}
+
+#[test]
+fn translation_smoothing_catches_up() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        translation_smoothing: 0.1,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.release(KeyCode::W);
+    let lagging = test.transform(camera).translation;
+    assert!(lagging.z > -6. && lagging.z < 0., "{lagging}");
+
+    test.advance_frames(100, 0.1);
+    test.assert_translation(camera, Vec3::new(0., 0., -6.));
+}
+
+#[test]
+fn look_lag_eases_the_view_but_not_movement() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        rotation_smoothing: 0.2,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+    let sensitivity = MovementSettings::default().sensitivity;
+    let quarter_turn = 90. / (sensitivity * FlyCamTestApp::WINDOW_HEIGHT);
+    let goal = Quat::from_rotation_y(-FRAC_PI_2);
+
+    test.move_mouse(Vec2::new(quarter_turn, 0.));
+    test.press(KeyCode::W);
+    test.advance(0.1);
+    let lagging = test
+        .transform(camera)
+        .rotation
+        .angle_between(Quat::IDENTITY);
+    assert!(lagging > 0. && lagging < FRAC_PI_2, "{lagging}");
+    // Movement isn't smoothed at all.
+    test.assert_translation(camera, Vec3::new(0., 0., -1.2));
+
+    test.release(KeyCode::W);
+    test.advance_frames(100, 0.1);
+    test.assert_rotation(camera, goal);
+}
+
+#[test]
+fn grid_snap_rounds_the_camera_only() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        grid_snap: 1.,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+
+    test.press(KeyCode::W);
+    test.advance_frames(3, 0.1);
+    test.assert_translation(camera, Vec3::new(0., 0., -4.));
+}
+
+#[test]
+fn actions_can_target_one_camera() {
+    let mut test = FlyCamTestApp::new();
+    let first = test.spawn_camera(Transform::default());
+    let second = test.spawn_camera(Transform::from_xyz(10., 0., 0.));
+
+    test.send(FlyCamInput::camera(
+        second,
+        FlyCamAction::Translate(Vec3::new(0., 1., 0.)),
+    ));
+    test.advance(0.1);
+    test.assert_translation(first, Vec3::ZERO);
+    test.assert_translation(second, Vec3::new(10., 1., 0.));
+
+    test.send(FlyCamInput::all(FlyCamAction::MoveRight(1.)));
+    test.advance(0.5);
+    test.assert_translation(first, Vec3::new(6., 0., 0.));
+    test.assert_translation(second, Vec3::new(16., 1., 0.));
+}
+
+#[test]
+fn walk_mode_cannot_fly() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        mode_blend_time: 0.,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+    test.send(SetFlyCamMode {
+        camera: None,
+        mode: FlyCamMode::Walk,
+    });
+    test.advance(0.);
+
+    test.send(FlyCamInput::all(FlyCamAction::MoveUp(1.)));
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -6.));
+}
+
+#[test]
+fn walk_mode_jumps_and_crouches() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        mode_blend_time: 0.,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+    test.send(SetFlyCamMode {
+        camera: None,
+        mode: FlyCamMode::Walk,
+    });
+    test.advance(0.);
+
+    // A 1 unit jump under default gravity takes about 0.9s to land.
+    test.press(KeyCode::Space);
+    let mut peak = 0f32;
+    for _ in 0..60 {
+        test.advance(1. / 60.);
+        peak = peak.max(test.transform(camera).translation.y);
+    }
+    assert!((peak - 1.).abs() < 0.1, "jumped {peak} high");
+    test.assert_translation(camera, Vec3::ZERO);
+
+    // Holding space doesn't jump again.
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::ZERO);
+    test.release(KeyCode::Space);
+
+    test.press(KeyCode::RShift);
+    test.advance_frames(10, 0.1);
+    test.assert_translation(camera, Vec3::new(0., -0.8, 0.));
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., -0.8, -3.));
+
+    test.release(KeyCode::RShift);
+    test.release(KeyCode::W);
+    test.advance_frames(10, 0.1);
+    test.assert_translation(camera, Vec3::new(0., 0., -3.));
+}
+
+#[test]
+fn fluids_slow_and_lift_walkers() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        mode_blend_time: 0.,
+        ..Default::default()
+    });
+    test.app
+        .world
+        .spawn()
+        .insert_bundle(SpatialBundle::default())
+        .insert(FlyCamFluid::new(Vec3::splat(100.)));
+    let camera = test.spawn_camera(Transform::default());
+    test.send(SetFlyCamMode {
+        camera: None,
+        mode: FlyCamMode::Walk,
+    });
+    test.advance(0.);
+    assert!(test.app.world.get::<FlyCamSwimming>(camera).is_some());
+
+    // Easing up to 40% of full speed, and floating up at the buoyancy speed
+    test.press(KeyCode::W);
+    test.advance_frames(120, 1. / 60.);
+    let translation = test.transform(camera).translation;
+    assert!((translation.z + 7.2).abs() < 0.2, "swam to {translation}");
+    assert!(
+        (translation.y - 0.75).abs() < 0.05,
+        "floated to {translation}"
+    );
+}
+
+#[test]
+fn walking_steps_up_kerbs_but_not_walls() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        mode_blend_time: 0.,
+        ..Default::default()
+    });
+    // A kerb at x = 5 and a wall at x = 10
+    test.app
+        .insert_resource(TerrainSettings::default().with_source(|position: Vec2| {
+            Some(match position.x {
+                x if x < 5. => 0.,
+                x if x < 10. => 0.3,
+                _ => 2.,
+            })
+        }));
+    let camera = test.spawn_camera(Transform::from_xyz(0., 1.7, 0.));
+    test.send(SetFlyCamMode {
+        camera: None,
+        mode: FlyCamMode::Walk,
+    });
+    test.advance(0.);
+
+    test.press(KeyCode::D);
+    test.advance_frames(90, 1. / 60.);
+    let translation = test.transform(camera).translation;
+    assert!(
+        translation.x > 9.5 && translation.x < 10.,
+        "walked to {translation}"
+    );
+    assert!((translation.y - 2.).abs() < 1e-3, "stood at {translation}");
+}
+
+#[test]
+fn terrain_mode_follows_the_ground() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        mode_blend_time: 0.,
+        ..Default::default()
+    });
+    test.app.insert_resource(
+        TerrainSettings {
+            smoothing: 0.,
+            ..Default::default()
+        }
+        .with_source(|position: Vec2| Some(position.x / 2.)),
+    );
+    let camera = test.spawn_camera(Transform::default());
+    test.send(SetFlyCamMode {
+        camera: None,
+        mode: FlyCamMode::Terrain,
+    });
+    test.advance(0.);
+
+    test.press(KeyCode::D);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(6., 5., 0.));
+}
+
+#[test]
+fn frozen_mode_ignores_input() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    test.send(SetFlyCamMode {
+        camera: Some(camera),
+        mode: FlyCamMode::Frozen,
+    });
+    test.advance(0.);
+    let changes: Vec<ModeChanged> = test
+        .app
+        .world
+        .resource_mut::<Events<ModeChanged>>()
+        .drain()
+        .collect();
+    assert_eq!(
+        changes,
+        [ModeChanged {
+            camera,
+            from: FlyCamMode::Fly,
+            to: FlyCamMode::Frozen,
+        }]
+    );
+
+    test.press(KeyCode::W);
+    test.move_mouse(Vec2::new(100., 100.));
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::ZERO);
+    test.assert_rotation(camera, Quat::IDENTITY);
+}
+
+#[test]
+fn replay_reproduces_the_flight() {
+    let settings = MovementSettings {
+        look_smoothing: 0.05,
+        translation_smoothing: 0.,
+        ..Default::default()
+    };
+    let mut test = FlyCamTestApp::with_settings(settings);
+    let camera = test.spawn_camera(Transform::from_xyz(1., 2., 3.));
+    test.app
+        .world
+        .entity_mut(camera)
+        .insert(FlyCamRecorder::default());
+
+    test.press(KeyCode::W);
+    test.move_mouse(Vec2::new(40., -10.));
+    test.advance(1. / 60.);
+    test.press(KeyCode::LShift);
+    test.advance(1. / 30.);
+    test.release(KeyCode::W);
+    test.press(KeyCode::Q);
+    test.scroll(2.);
+    test.advance_frames(10, 1. / 144.);
+    let end = test.transform(camera);
+
+    let recording = test
+        .app
+        .world
+        .entity_mut(camera)
+        .remove::<FlyCamRecorder>()
+        .unwrap()
+        .recording;
+    assert_eq!(recording.frames.len(), 12);
+
+    let mut replay = FlyCamTestApp::with_settings(MovementSettings::default());
+    let other = replay.spawn_camera(Transform::from_xyz(-50., 0., 0.));
+    // Live input is ignored while playing.
+    replay.press(KeyCode::D);
+    replay.play(other, recording);
+    replay.assert_translation(other, end.translation);
+    replay.assert_rotation(other, end.rotation);
+}
+
+#[test]
+fn replay_turns_as_far_as_the_recording_in_any_window() {
+    use bevy_flycam::FlyCamPlayback;
+
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    test.app
+        .world
+        .entity_mut(camera)
+        .insert(FlyCamRecorder::default());
+    test.move_mouse(Vec2::new(100., 20.));
+    test.press(KeyCode::W);
+    test.advance(0.1);
+    let end = test.transform(camera);
+    let recording = test
+        .app
+        .world
+        .entity_mut(camera)
+        .remove::<FlyCamRecorder>()
+        .unwrap()
+        .recording;
+
+    // Mouse look turns by the window's size and the sensitivity, but replay doesn't care.
+    let mut replay = FlyCamTestApp::with_settings(MovementSettings {
+        sensitivity: 0.0001,
+        ..Default::default()
+    });
+    replay
+        .app
+        .world
+        .resource_mut::<Windows>()
+        .get_primary_mut()
+        .unwrap()
+        .update_actual_size_from_backend(640, 480);
+    let other = replay.spawn_camera(Transform::default());
+    replay
+        .app
+        .world
+        .entity_mut(other)
+        .insert(FlyCamPlayback::new(recording));
+    replay.advance(0.1);
+    replay.assert_translation(other, end.translation);
+    replay.assert_rotation(other, end.rotation);
+}
+
+#[test]
+fn replay_moves_as_far_as_the_recording_at_any_frame_rate() {
+    use bevy_flycam::FlyCamPlayback;
+
+    let settings = MovementSettings {
+        translation_smoothing: 0.,
+        ..Default::default()
+    };
+    let mut test = FlyCamTestApp::with_settings(settings.clone());
+    let camera = test.spawn_camera(Transform::default());
+    test.app
+        .world
+        .entity_mut(camera)
+        .insert(FlyCamRecorder::default());
+    test.press(KeyCode::W);
+    test.advance_frames(6, 1. / 60.);
+    let recording = test
+        .app
+        .world
+        .entity_mut(camera)
+        .remove::<FlyCamRecorder>()
+        .unwrap()
+        .recording;
+
+    // Six frames of a tenth of a second each still only replay the tenth of a second recorded.
+    let mut replay = FlyCamTestApp::with_settings(settings);
+    let other = replay.spawn_camera(Transform::default());
+    replay
+        .app
+        .world
+        .entity_mut(other)
+        .insert(FlyCamPlayback::new(recording));
+    replay.advance_frames(6, 0.1);
+    replay.assert_translation(other, Vec3::new(0., 0., -1.2));
+}
+
+#[test]
+fn net_proxy_follows_the_camera() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::from_rotation(Quat::from_rotation_y(0.5)));
+    test.app
+        .world
+        .entity_mut(camera)
+        .insert(FlyCamNetState::default());
+    let proxy = test
+        .app
+        .world
+        .spawn()
+        .insert_bundle(TransformBundle::default())
+        .insert(FlyCamNetProxy::new(FlyCamNetState::default()))
+        .id();
+
+    test.press(KeyCode::W);
+    for _ in 0..10 {
+        test.advance(0.1);
+        let state = *test.app.world.get::<FlyCamNetState>(camera).unwrap();
+        test.app
+            .world
+            .get_mut::<FlyCamNetProxy>(proxy)
+            .unwrap()
+            .receive(state);
+    }
+    let state = *test.app.world.get::<FlyCamNetState>(camera).unwrap();
+    assert!((state.velocity.length() - 12.).abs() < 1e-3, "{state:?}");
+
+    // Dead reckoning keeps the proxy on the camera's heels while no updates arrive.
+    test.advance_frames(50, 0.1);
+    let goal = state.translation + state.velocity * 5.;
+    let translation = test.app.world.get::<Transform>(proxy).unwrap().translation;
+    assert!(translation.distance(goal) < 1.5, "{translation} vs {goal}");
+    assert!(translation.distance(test.transform(camera).translation) < 1.5);
+}
+
+#[test]
+fn spectate_cycles_through_players() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        mode_blend_time: 0.,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+    let players: Vec<Entity> = [Vec3::new(10., 0., 0.), Vec3::new(-10., 0., 0.)]
+        .into_iter()
+        .map(|translation| {
+            test.app
+                .world
+                .spawn()
+                .insert_bundle(TransformBundle::from_transform(
+                    Transform::from_translation(translation),
+                ))
+                .insert(Spectatable::default())
+                .id()
+        })
+        .collect();
+    test.advance(0.);
+
+    for player in &players {
+        test.press(KeyCode::Tab);
+        test.advance(0.1);
+        test.release(KeyCode::Tab);
+        test.advance(0.1);
+        assert_eq!(
+            *test.app.world.get::<FlyCamMode>(camera).unwrap(),
+            FlyCamMode::Spectate
+        );
+        let expected = test
+            .app
+            .world
+            .get::<Transform>(*player)
+            .unwrap()
+            .translation
+            + Spectatable::default().offset;
+        test.assert_translation(camera, expected);
+    }
+
+    test.press(KeyCode::Tab);
+    test.advance(0.1);
+    assert_eq!(
+        *test.app.world.get::<FlyCamMode>(camera).unwrap(),
+        FlyCamMode::Fly
+    );
+}
+
+#[test]
+fn photo_mode_stays_in_range() {
+    let mut test = FlyCamTestApp::new();
+    test.app.add_plugin(FlyCamPhotoPlugin);
+    let original = test
+        .app
+        .world
+        .spawn()
+        .insert_bundle(Camera3dBundle::default())
+        .insert(FlyCam)
+        .id();
+    test.advance(0.);
+
+    test.press(KeyCode::F12);
+    test.advance(0.);
+    test.release(KeyCode::F12);
+    let photo = test
+        .app
+        .world
+        .resource::<FlyCamPhotoMode>()
+        .camera()
+        .unwrap();
+    assert!(!test.app.world.get::<Camera>(original).unwrap().is_active);
+
+    test.press(KeyCode::W);
+    test.advance_frames(30, 0.1);
+    test.release(KeyCode::W);
+    test.assert_translation(photo, Vec3::new(0., 0., -20.));
+    test.assert_translation(original, Vec3::ZERO);
+
+    test.press(KeyCode::Return);
+    test.advance(0.1);
+    let shots: Vec<PhotoTaken> = test
+        .app
+        .world
+        .resource_mut::<Events<PhotoTaken>>()
+        .drain()
+        .collect();
+    assert_eq!(shots.len(), 1);
+    assert_eq!(shots[0].camera, photo);
+
+    test.press(KeyCode::F12);
+    test.advance(0.);
+    assert!(test.app.world.get_entity(photo).is_none());
+    assert!(test.app.world.get::<Camera>(original).unwrap().is_active);
+    assert_eq!(
+        *test.app.world.get::<FlyCamMode>(original).unwrap(),
+        FlyCamMode::Fly
+    );
+}
+
+#[test]
+fn frozen_frustum_stays_behind() {
+    let mut test = FlyCamTestApp::new();
+    test.app.add_plugin(FlyCamFrustumPlugin);
+    let camera = test.spawn_camera(Transform::default());
+    test.app
+        .world
+        .entity_mut(camera)
+        .insert(Projection::default())
+        .insert(Frustum::default());
+
+    test.press(KeyCode::F4);
+    test.advance(0.);
+    test.release(KeyCode::F4);
+    let frozen = test
+        .app
+        .world
+        .get::<FlyCamFrozenFrustum>(camera)
+        .unwrap()
+        .clone();
+    let far = 1000. * (PI / 8.).tan();
+    assert!(frozen.corners()[4].abs_diff_eq(Vec3::new(-far, -far, -1000.), 1e-2));
+
+    // Bevy moves the frustum along with the camera, and it's put back.
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.release(KeyCode::W);
+    test.app.world.entity_mut(camera).insert(Frustum::default());
+    test.advance(0.);
+    let frustum = test.app.world.get::<Frustum>(camera).unwrap();
+    assert_eq!(
+        frustum.planes.map(|plane| plane.normal_d()),
+        frozen.frustum().planes.map(|plane| plane.normal_d())
+    );
+    assert_eq!(
+        test.app
+            .world
+            .get::<FlyCamFrozenFrustum>(camera)
+            .unwrap()
+            .corners(),
+        frozen.corners()
+    );
+
+    test.press(KeyCode::F4);
+    test.advance(0.);
+    assert!(test.app.world.get::<FlyCamFrozenFrustum>(camera).is_none());
+}
+
+#[test]
+fn paths_trace_recordings() {
+    let mut test = FlyCamTestApp::new();
+    test.app.add_plugin(FlyCamPathsPlugin);
+    let camera = test.spawn_camera(Transform::default());
+    test.app
+        .world
+        .entity_mut(camera)
+        .insert(FlyCamRecorder::default());
+    let path = |test: &FlyCamTestApp| {
+        let paths = test.app.world.resource::<FlyCamPaths>();
+        paths
+            .get(&format!("recording {camera:?}"))
+            .unwrap()
+            .to_vec()
+    };
+
+    test.advance(0.);
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.release(KeyCode::W);
+    test.advance(0.);
+    test.press(KeyCode::D);
+    test.advance(0.5);
+    test.release(KeyCode::D);
+    test.advance(0.);
+    assert_eq!(
+        path(&test),
+        vec![Vec3::ZERO, Vec3::new(0., 0., -6.), Vec3::new(6., 0., -6.)]
+    );
+
+    assert!(test.app.world.resource::<FlyCamPaths>().is_visible());
+    test.press(KeyCode::F5);
+    test.advance(0.);
+    assert!(!test.app.world.resource::<FlyCamPaths>().is_visible());
+}
+
+#[test]
+fn waypoint_editor_drops_and_rearranges_waypoints() {
+    let mut test = FlyCamTestApp::new();
+    test.app
+        .add_plugin(FlyCamPathsPlugin)
+        .add_plugin(FlyCamWaypointEditorPlugin);
+    test.spawn_camera(Transform::default());
+    let tap = |test: &mut FlyCamTestApp, key| {
+        test.press(key);
+        test.advance(0.);
+        test.release(key);
+        test.advance(0.);
+    };
+    let waypoints = |test: &FlyCamTestApp| {
+        let waypoints = test.app.world.resource::<FlyCamWaypoints>();
+        waypoints
+            .waypoints
+            .iter()
+            .map(|w| w.translation)
+            .collect::<Vec<_>>()
+    };
+
+    // Nothing is dropped outside the editor.
+    tap(&mut test, KeyCode::Insert);
+    assert!(waypoints(&test).is_empty());
+
+    tap(&mut test, KeyCode::F7);
+    tap(&mut test, KeyCode::Insert);
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.release(KeyCode::W);
+    tap(&mut test, KeyCode::Insert);
+    let ahead = Vec3::new(0., 0., -6.);
+    assert_eq!(waypoints(&test), vec![Vec3::ZERO, ahead]);
+    let sampled = test.app.world.resource::<FlyCamWaypoints>().sample(0.5);
+    assert!(sampled.unwrap().translation.abs_diff_eq(ahead / 2., 1e-5));
+    let preview = test
+        .app
+        .world
+        .resource::<FlyCamPaths>()
+        .get("waypoints")
+        .unwrap()
+        .to_vec();
+    assert_eq!(preview.len(), 17);
+    assert_eq!((preview[0], preview[16]), (Vec3::ZERO, ahead));
+
+    let mut edits = test.app.world.resource_mut::<Events<WaypointEdit>>();
+    edits.send(WaypointEdit::Move { from: 1, to: 0 });
+    edits.send(WaypointEdit::Remove(1));
+    test.advance(0.);
+    assert_eq!(waypoints(&test), vec![ahead]);
+
+    tap(&mut test, KeyCode::F7);
+    let paths = test.app.world.resource::<FlyCamPaths>();
+    assert!(paths.get("waypoints").is_none());
+}
+
+#[test]
+fn path_playback_can_be_paused_scrubbed_and_sped_up() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    test.app
+        .world
+        .resource_mut::<FlyCamPathPlaybackSettings>()
+        .toggle_pause = vec![KeyCode::T];
+    let waypoints = FlyCamWaypoints {
+        waypoints: vec![
+            FlyCamWaypoint {
+                translation: Vec3::ZERO,
+                rotation: Quat::IDENTITY,
+            },
+            FlyCamWaypoint {
+                translation: Vec3::new(0., 0., -10.),
+                rotation: Quat::IDENTITY,
+            },
+        ],
+    };
+    test.app
+        .world
+        .entity_mut(camera)
+        .insert(FlyCamPathPlayback::new(waypoints, 2.));
+    let edit = |test: &mut FlyCamTestApp, edit: fn(&mut FlyCamPathPlayback)| {
+        edit(&mut test.app.world.get_mut(camera).unwrap());
+    };
+
+    // A quarter of the way through the time, and it eases off the first waypoint.
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.release(KeyCode::W);
+    test.assert_translation(camera, Vec3::new(0., 0., -2.03125));
+
+    test.press(KeyCode::T);
+    test.advance(0.5);
+    test.release(KeyCode::T);
+    test.assert_translation(camera, Vec3::new(0., 0., -2.03125));
+
+    edit(&mut test, |playback| playback.seek(1.));
+    test.advance(0.);
+    test.assert_translation(camera, Vec3::new(0., 0., -5.));
+
+    edit(&mut test, |playback| playback.rate = 2.);
+    test.press(KeyCode::T);
+    test.advance(0.5);
+    test.release(KeyCode::T);
+    test.assert_translation(camera, Vec3::new(0., 0., -10.));
+    let finished: Vec<PathPlaybackFinished> = test
+        .app
+        .world
+        .resource_mut::<Events<PathPlaybackFinished>>()
+        .drain()
+        .collect();
+    assert_eq!(finished, vec![PathPlaybackFinished { camera }]);
+
+    // Then it lets go.
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -16.));
+}
+
+#[test]
+fn tours_fly_between_stops_and_take_commands() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    let tour = FlyCamTour::new(vec![
+        TourStop::new("hall", Transform::from_xyz(0., 0., -10.)).with_dwell(1.),
+        TourStop::new("kitchen", Transform::from_xyz(10., 0., -10.)),
+        TourStop::new("garden", Transform::from_xyz(10., 0., 0.)).looking_at(Vec3::ZERO),
+    ])
+    .with_flight_time(2.);
+    test.app.world.entity_mut(camera).insert(tour);
+
+    // A quarter of the way through the flight, and it eases out of where it started.
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -1.5625));
+    test.advance_frames(2, 0.75);
+    test.assert_translation(camera, Vec3::new(0., 0., -10.));
+
+    // It waits at the hall, then flies on by itself, even with other input.
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -10.));
+    test.release(KeyCode::W);
+    test.advance_frames(6, 0.5);
+    test.assert_translation(camera, Vec3::new(10., 0., -10.));
+
+    test.send(TourControl::all(TourCommand::GotoNamed("garden".into())));
+    test.advance_frames(4, 0.5);
+    test.assert_translation(camera, Vec3::new(10., 0., 0.));
+    test.assert_rotation(camera, Quat::from_rotation_y(std::f32::consts::FRAC_PI_2));
+
+    test.send(TourControl::camera(camera, TourCommand::Previous));
+    test.advance_frames(4, 0.5);
+    test.assert_translation(camera, Vec3::new(10., 0., -10.));
+    let tour = test.app.world.get::<FlyCamTour>(camera).unwrap();
+    assert_eq!(tour.stop().unwrap().name, "kitchen");
+    assert!(tour.is_at_stop());
+}
+
+#[test]
+fn tours_announce_reaching_and_leaving_stops() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    let tour = FlyCamTour::new(vec![
+        TourStop::new("hall", Transform::from_xyz(0., 0., -10.)).with_dwell(1.),
+        TourStop::new("kitchen", Transform::from_xyz(10., 0., -10.)),
+    ])
+    .with_flight_time(1.);
+    test.app.world.entity_mut(camera).insert(tour);
+    let stops = |test: &mut FlyCamTestApp| {
+        let reached: Vec<String> = test
+            .app
+            .world
+            .resource_mut::<Events<TourStopReached>>()
+            .drain()
+            .map(|event| event.stop)
+            .collect();
+        let left: Vec<String> = test
+            .app
+            .world
+            .resource_mut::<Events<TourStopLeft>>()
+            .drain()
+            .map(|event| event.stop)
+            .collect();
+        (reached, left)
+    };
+
+    test.advance_frames(2, 0.5);
+    assert_eq!(stops(&mut test), (vec!["hall".into()], vec![]));
+    test.advance_frames(2, 0.5);
+    assert_eq!(stops(&mut test), (vec![], vec!["hall".into()]));
+    test.advance_frames(2, 0.5);
+    assert_eq!(stops(&mut test), (vec!["kitchen".into()], vec![]));
+
+    // Turning round part way back to the hall never reached it, so it never left it either.
+    test.send(TourControl::all(TourCommand::Previous));
+    test.advance(0.5);
+    test.send(TourControl::all(TourCommand::Next));
+    test.advance(0.5);
+    assert_eq!(stops(&mut test), (vec![], vec!["kitchen".into()]));
+}
+
+#[test]
+fn path_validator_finds_walls_in_the_way() {
+    // A wall across z = -5, for cameras coming from in front of it.
+    let validator = FlyCamPathValidator::new(
+        |origin: Vec3, direction: Vec3, radius: f32, max_distance: f32| {
+            let gap = origin.z - radius + 5.;
+            let closing = -direction.z;
+            (closing > 0. && gap / closing <= max_distance).then_some(gap / closing)
+        },
+    );
+    let tour = FlyCamTour::new(vec![
+        TourStop::new("start", Transform::from_xyz(0., 0., 0.)),
+        TourStop::new("left", Transform::from_xyz(-10., 0., 0.)),
+        TourStop::new("through", Transform::from_xyz(-10., 0., -10.)),
+    ]);
+
+    let report = validator.check_tour(&tour);
+    assert_eq!(report.length, 20.);
+    assert_eq!(
+        report.hits,
+        vec![PathHit {
+            segment: 1,
+            position: Vec3::new(-10., 0., -4.75),
+            distance: 14.75,
+        }]
+    );
+    assert!(validator
+        .check_points(&[Vec3::ZERO, Vec3::new(0., 0., -4.)])
+        .is_clear());
+}
+
+#[test]
+fn sessions_save_and_restore_the_camera() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.release(KeyCode::W);
+    test.settings_mut().speed = 20.;
+    test.app.world.entity_mut(camera).insert(FlyCamMode::Walk);
+    test.advance(0.);
+    let mut sessions = SystemState::<FlyCamSessions>::new(&mut test.app.world);
+    let session = sessions.get_mut(&mut test.app.world).save(camera).unwrap();
+
+    test.press(KeyCode::A);
+    test.advance(1.);
+    test.release(KeyCode::A);
+    test.settings_mut().speed = 1.;
+    test.app.world.entity_mut(camera).insert(FlyCamMode::Fly);
+    test.advance(0.);
+
+    assert!(sessions
+        .get_mut(&mut test.app.world)
+        .restore(camera, &session));
+    test.advance(0.);
+    test.assert_translation(camera, Vec3::new(0., 0., -6.));
+    assert_eq!(test.settings_mut().speed, 20.);
+    assert_eq!(
+        *test.app.world.get::<FlyCamMode>(camera).unwrap(),
+        FlyCamMode::Walk
+    );
+}
+
+#[test]
+fn poses_round_trip_through_short_strings() {
+    let transform = Transform::from_xyz(3.5, 2., -10.).with_rotation(Quat::from_euler(
+        EulerRot::YXZ,
+        45f32.to_radians(),
+        -12.5f32.to_radians(),
+        0.,
+    ));
+    let encoded = encode_pose(&transform);
+    assert_eq!(encoded, "3.5_2_-10_45_-12.5");
+    let decoded = decode_pose(&encoded).unwrap();
+    assert!(decoded.translation.abs_diff_eq(transform.translation, 1e-6));
+    assert!(decoded.rotation.abs_diff_eq(transform.rotation, 1e-6));
+
+    let rolled = Transform::from_rotation(Quat::from_rotation_z(0.5));
+    let decoded = decode_pose(&encode_pose(&rolled)).unwrap();
+    assert!(decoded.rotation.abs_diff_eq(rolled.rotation, 1e-4));
+    assert_eq!(decode_pose("1_2_3"), None);
+    assert_eq!(decode_pose("1_2_three_4_5"), None);
+}
+
+#[test]
+fn rig_splits_yaw_and_pitch() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test
+        .app
+        .world
+        .spawn()
+        .insert_bundle(TransformBundle::default())
+        .id();
+    let body = test
+        .app
+        .world
+        .spawn()
+        .insert_bundle(TransformBundle::default())
+        .insert(FlyCam)
+        .insert(FlyCamRig { camera })
+        .push_children(&[camera])
+        .id();
+    test.advance(0.);
+
+    test.move_mouse(Vec2::new(-200., -100.));
+    test.press(KeyCode::W);
+    test.advance(0.5);
+
+    let rotation = test.rotation(body);
+    assert!(rotation.yaw > 0. && rotation.pitch > 0., "{rotation:?}");
+    test.assert_rotation(body, Quat::from_rotation_y(rotation.yaw));
+    let eye = test.app.world.get::<Transform>(camera).unwrap().rotation;
+    assert!(eye.angle_between(Quat::from_rotation_x(rotation.pitch)) < 1e-3);
+    test.assert_translation(body, Vec3::new(0., 0., -6.));
+
+    // The split doesn't count as something else moving the camera, so the next frame carries
+    // on along the new heading.
+    test.advance(0.5);
+    let pitch = test.rotation(body).pitch;
+    assert!((pitch - rotation.pitch).abs() < 1e-5);
+    let heading = Quat::from_rotation_y(rotation.yaw) * -Vec3::Z;
+    test.assert_translation(body, Vec3::new(0., 0., -6.) + heading * 6.);
+}
+
+#[test]
+fn origin_shifts_under_far_cameras() {
+    let mut test = FlyCamTestApp::new();
+    test.app
+        .insert_resource(FlyCamOriginSettings { threshold: 100. })
+        .add_plugin(FlyCamOriginPlugin);
+    let camera = test.spawn_camera(Transform::from_xyz(90., 0., 0.));
+    let landmark = test
+        .app
+        .world
+        .spawn()
+        .insert_bundle(SpatialBundle::from(Transform::from_xyz(200., 0., 0.)))
+        .insert(FlyCamShiftWithOrigin)
+        .id();
+
+    test.press(KeyCode::D);
+    test.advance(1.);
+    test.assert_translation(camera, Vec3::ZERO);
+    let landmark = test
+        .app
+        .world
+        .get::<Transform>(landmark)
+        .unwrap()
+        .translation;
+    assert!(
+        landmark.abs_diff_eq(Vec3::new(98., 0., 0.), 1e-3),
+        "{landmark}"
+    );
+    let shifts: Vec<OriginShift> = test
+        .app
+        .world
+        .resource_mut::<Events<OriginShift>>()
+        .drain()
+        .collect();
+    assert_eq!(shifts, vec![OriginShift(Vec3::new(102., 0., 0.))]);
+    assert_eq!(
+        test.app.world.resource::<FlyCamOrigin>().offset,
+        DVec3::new(102., 0., 0.)
+    );
+
+    // Flying on carries on from the new origin.
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(6., 0., 0.));
+}
+
+#[test]
+fn precise_position_keeps_tiny_steps_far_out() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        speed: 0.06,
+        ..Default::default()
+    });
+    test.app
+        .insert_resource(FlyCamOriginSettings { threshold: 100. })
+        .add_plugin(FlyCamOriginPlugin);
+    let camera = test.spawn_camera(Transform::default());
+    test.app
+        .world
+        .entity_mut(camera)
+        .insert(FlyCamPrecisePosition::new(DVec3::new(1e9, 0., 0.)));
+    test.advance(0.);
+    test.assert_translation(camera, Vec3::ZERO);
+
+    // A thousandth of a unit per frame, far too small for an f32 a billion units out
+    test.press(KeyCode::D);
+    test.advance_frames(100, 1. / 60.);
+    let position = test
+        .app
+        .world
+        .get::<FlyCamPrecisePosition>(camera)
+        .unwrap()
+        .position;
+    assert!(
+        (position - DVec3::new(1e9 + 0.1, 0., 0.)).length() < 1e-4,
+        "{position}"
+    );
+}
+
+#[test]
+fn auto_speed_follows_the_distance() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    // A wall across z = -100
+    let mut auto_speed =
+        FlyCamAutoSpeed::new(|origin: Vec3, _direction: Vec3| Some(origin.z + 100.));
+    auto_speed.smoothing = 0.;
+    test.app.world.entity_mut(camera).insert(auto_speed);
+    test.advance(0.);
+
+    // 100 units from the wall is 10 times the speed, and 88 units is 8.8 times.
+    test.press(KeyCode::W);
+    test.advance(0.1);
+    test.assert_translation(camera, Vec3::new(0., 0., -12.));
+    test.advance(0.1);
+    test.assert_translation(camera, Vec3::new(0., 0., -22.56));
+}
+
+#[test]
+fn cursor_ray_goes_through_the_cursor() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::from_xyz(1., 2., 3.));
+    test.app
+        .world
+        .entity_mut(camera)
+        .insert(Camera::default())
+        .insert(Projection::default());
+    test.set_cursor(Some(Vec2::new(
+        FlyCamTestApp::WINDOW_WIDTH,
+        FlyCamTestApp::WINDOW_HEIGHT / 2.,
+    )));
+    test.advance(0.);
+
+    let ray = |test: &mut FlyCamTestApp| {
+        let mut rays = SystemState::<FlyCamRays>::new(&mut test.app.world);
+        rays.get(&test.app.world).cursor_ray(camera).unwrap()
+    };
+    // Grabbed, it goes through the crosshair.
+    let grabbed = ray(&mut test);
+    assert!(
+        grabbed.direction.abs_diff_eq(Vec3::NEG_Z, 1e-5),
+        "{grabbed:?}"
+    );
+    assert!((grabbed.origin.truncate() - Vec2::new(1., 2.)).length() < 1e-4);
+
+    // Otherwise it goes through the right edge of the view.
+    test.press(KeyCode::Escape);
+    test.advance(0.);
+    let free = ray(&mut test);
+    let aspect = FlyCamTestApp::WINDOW_WIDTH / FlyCamTestApp::WINDOW_HEIGHT;
+    let expected = Vec3::new((PI / 8.).tan() * aspect, 0., -1.).normalize();
+    assert!(free.direction.abs_diff_eq(expected, 1e-4), "{free:?}");
+}
+
+#[test]
+fn input_blockers_hold_back_each_input() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    let block = |test: &mut FlyCamTestApp, input| {
+        test.app
+            .world
+            .resource_mut::<FlyCamInputBlockers>()
+            .block("dialog", input);
+    };
+
+    block(
+        &mut test,
+        BlockedInput {
+            movement: true,
+            ..default()
+        },
+    );
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::ZERO);
+
+    // Scrolling still works until that's blocked too.
+    test.release(KeyCode::W);
+    test.scroll(1.);
+    test.advance(0.1);
+    let distance = 1024. * MovementSettings::default().sensitivity;
+    test.assert_translation(camera, Vec3::new(0., 0., -distance));
+
+    block(&mut test, BlockedInput::ALL);
+    test.scroll(1.);
+    test.advance(0.1);
+    test.assert_translation(camera, Vec3::new(0., 0., -distance));
+
+    test.app
+        .world
+        .resource_mut::<FlyCamInputBlockers>()
+        .unblock("dialog");
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -distance - 6.));
+}
+
+#[test]
+fn text_box_blockers_hold_back_hotkeys() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    test.app
+        .world
+        .entity_mut(camera)
+        .insert(Projection::default());
+    test.app
+        .world
+        .resource_mut::<FlyCamInputBlockers>()
+        .block("text box", BlockedInput::ALL);
+    let fov = |test: &FlyCamTestApp| match test.app.world.get::<Projection>(camera) {
+        Some(Projection::Perspective(perspective)) => perspective.fov,
+        _ => panic!("not a perspective flycam"),
+    };
+    let wide = fov(&test);
+
+    // Typing a C into the text box doesn't zoom.
+    test.press(KeyCode::C);
+    test.advance(1.);
+    assert_eq!(fov(&test), wide);
+
+    test.app
+        .world
+        .resource_mut::<FlyCamInputBlockers>()
+        .unblock("text box");
+    test.advance(1.);
+    assert!(fov(&test) < wide);
+}
+
+#[test]
+fn input_blockers_hold_back_every_mode() {
+    for mode in [
+        FlyCamMode::Orbit,
+        FlyCamMode::Rts,
+        FlyCamMode::Drone,
+        FlyCamMode::Vehicle,
+        FlyCamMode::MouseOnly,
+    ] {
+        let mut test = FlyCamTestApp::with_settings(MovementSettings {
+            mode_blend_time: 0.,
+            ..Default::default()
+        });
+        let camera = test.spawn_camera(Transform::default());
+        test.send(SetFlyCamMode {
+            camera: Some(camera),
+            mode,
+        });
+        // Switching, setting the mode up and placing the camera can take a frame each.
+        test.advance_frames(3, 0.);
+        let before = test.transform(camera);
+
+        test.app
+            .world
+            .resource_mut::<FlyCamInputBlockers>()
+            .block("dialog", BlockedInput::ALL);
+        test.press(KeyCode::W);
+        test.press(KeyCode::Q);
+        test.press_button(MouseButton::Left);
+        test.move_mouse(Vec2::new(100., 50.));
+        test.scroll(3.);
+        test.advance(0.5);
+        assert_eq!(test.transform(camera), before, "{:?} moved", mode);
+    }
+}
+
+#[test]
+fn real_time_keeps_flying_while_paused() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+
+    test.press(KeyCode::W);
+    test.advance_paused(0.5);
+    test.assert_translation(camera, Vec3::ZERO);
+
+    test.settings_mut().time_source = FlyCamTimeSource::Real;
+    test.advance_paused(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -6.));
+}
+
+#[test]
+fn hitches_are_cut_short() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        max_delta: 0.1,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+
+    test.press(KeyCode::W);
+    test.advance(5.);
+    test.assert_translation(camera, Vec3::new(0., 0., -1.2));
+}
+
+#[test]
+fn idle_cameras_are_left_unchanged() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    test.press(KeyCode::W);
+    test.advance(0.1);
+    test.release(KeyCode::W);
+    test.advance_frames(10, 0.1);
+
+    let mut changed = SystemState::<(
+        Query<Entity, Changed<Transform>>,
+        Query<Entity, Changed<FlyCamTarget>>,
+        Query<Entity, Changed<FlyCamRotation>>,
+    )>::new(&mut test.app.world);
+    changed.get(&test.app.world);
+    test.advance_frames(10, 0.1);
+    let (transforms, targets, rotations) = changed.get(&test.app.world);
+    assert!(!transforms.contains(camera));
+    assert!(!targets.contains(camera));
+    assert!(!rotations.contains(camera));
+}
+
+#[test]
+fn long_spins_stay_level() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+
+    // Q yaws at 0.75 radians a second, so this is about a hundred turns.
+    test.press(KeyCode::Q);
+    test.advance_frames(800, 1.);
+    let rotation = test.rotation(camera);
+    assert!((-PI..=PI).contains(&rotation.yaw), "{rotation:?}");
+    assert_eq!((rotation.pitch, rotation.roll), (0., 0.));
+    let transform = test.transform(camera);
+    assert!(transform.rotation.is_normalized());
+    assert!((transform.rotation * Vec3::Y).abs_diff_eq(Vec3::Y, 1e-5));
+}
+
+#[test]
+fn control_can_run_before_update() {
+    /// Where the camera was headed for when `Update` ran
+    #[derive(Default)]
+    struct Seen(Vec3);
+    fn see(mut seen: ResMut<Seen>, targets: Query<&FlyCamTarget>) {
+        seen.0 = targets.single().translation;
+    }
+
+    let mut test = FlyCamTestApp::with_schedule(FlyCamSchedule::new(CoreStage::PreUpdate));
+    test.app.init_resource::<Seen>().add_system(see);
+    test.spawn_camera(Transform::default());
+
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    assert_eq!(test.app.world.resource::<Seen>().0, Vec3::new(0., 0., -6.));
+}
+
+#[test]
+fn fixed_timestep_is_drawn_between_steps() {
+    let mut test = FlyCamTestApp::with_schedule(FlyCamSchedule::fixed(0.1));
+    test.advance(0.1);
+    let camera = test.spawn_camera(Transform::default());
+    test.advance(0.1);
+
+    test.press(KeyCode::W);
+    test.advance(0.05);
+    test.assert_translation(camera, Vec3::ZERO);
+    // One step of 1.2 has run, and the clock is halfway to the next.
+    test.advance(0.1);
+    test.assert_translation(camera, Vec3::new(0., 0., -0.6));
+    test.advance(0.1);
+    test.assert_translation(camera, Vec3::new(0., 0., -1.8));
+    test.advance(0.05);
+    test.assert_translation(camera, Vec3::new(0., 0., -2.4));
+}
+
+#[test]
+fn gamepad_speed_follows_the_stick() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    let gamepad = test.connect_gamepad(0);
+    test.advance(0.);
+
+    // Inside the deadzone nothing happens.
+    test.set_gamepad_axis(gamepad, GamepadAxisType::LeftStickY, 0.08);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::ZERO);
+
+    // Halfway past the deadzone, the default squared curve gives a quarter of the speed.
+    test.set_gamepad_axis(gamepad, GamepadAxisType::LeftStickY, 0.55);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -1.5));
+
+    test.set_gamepad_axis(gamepad, GamepadAxisType::LeftStickY, 0.);
+    test.set_gamepad_button(gamepad, GamepadButtonType::RightTrigger2, 1.);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 6., -1.5));
+}
+
+#[test]
+fn gamepad_looks_with_its_own_sensitivity() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    let gamepad = test.connect_gamepad(0);
+    test.advance(0.);
+
+    test.set_gamepad_axis(gamepad, GamepadAxisType::RightStickX, 1.);
+    test.advance(0.5);
+    let rotation = test.rotation(camera);
+    assert!((rotation.yaw + 1.25).abs() < 1e-5, "{rotation:?}");
+
+    test.app
+        .world
+        .resource_mut::<FlyCamGamepadSettings>()
+        .look_sensitivity = 1.;
+    test.set_gamepad_axis(gamepad, GamepadAxisType::RightStickX, 0.);
+    test.set_gamepad_axis(gamepad, GamepadAxisType::RightStickY, 1.);
+    test.advance(0.5);
+    let rotation = test.rotation(camera);
+    assert!((rotation.pitch - 0.5).abs() < 1e-5, "{rotation:?}");
+}
+
+#[test]
+fn zooming_in_slows_gamepad_look() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    let gamepad = test.connect_gamepad(0);
+    test.press(KeyCode::C);
+    test.advance(1.);
+
+    // A quarter of the field of view turns a quarter as fast.
+    let settings = MovementSettings::default();
+    test.set_gamepad_axis(gamepad, GamepadAxisType::RightStickX, 1.);
+    test.advance(0.5);
+    let rotation = test.rotation(camera);
+    let zoomed = 1.25 * settings.zoom_fov / settings.fov;
+    assert!((rotation.yaw + zoomed).abs() < 1e-5, "{rotation:?}");
+}
+
+#[test]
+fn pushing_a_stick_past_its_deadzone_isnt_idle() {
+    use bevy_flycam::FlyCamIdle;
+
+    let mut test = FlyCamTestApp::new();
+    test.spawn_camera(Transform::default());
+    let gamepad = test.connect_gamepad(0);
+    let idle = |test: &FlyCamTestApp| *test.app.world.resource::<FlyCamIdle>();
+
+    // A stick drifting inside the deadzone doesn't count.
+    test.set_gamepad_axis(gamepad, GamepadAxisType::LeftStickX, 0.05);
+    test.advance(0.5);
+    test.advance(0.5);
+    assert_eq!(idle(&test).seconds(), 1.);
+
+    test.set_gamepad_axis(gamepad, GamepadAxisType::RightStickY, 0.5);
+    test.advance(0.5);
+    assert!(idle(&test).active());
+    assert_eq!(idle(&test).seconds(), 0.);
+}
+
+#[test]
+fn each_device_has_its_own_sensitivity() {
+    use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    test.app.insert_resource(
+        SensitivityProfiles::default()
+            .with(InputDevice::Mouse, 2.)
+            .with(InputDevice::Trackpad, 0.5),
+    );
+
+    test.move_mouse(Vec2::new(100., 0.));
+    test.advance(0.1);
+    let settings = MovementSettings::default();
+    let degrees = settings.sensitivity * 200. * FlyCamTestApp::WINDOW_HEIGHT;
+    test.assert_rotation(camera, Quat::from_rotation_y(-degrees.to_radians()));
+
+    test.app
+        .world
+        .resource_mut::<SensitivityProfiles>()
+        .set(InputDevice::Mouse, 1.);
+    test.send(MouseWheel {
+        unit: MouseScrollUnit::Pixel,
+        x: 0.,
+        y: 1.,
+    });
+    test.advance(0.1);
+    let distance = 512. * settings.sensitivity;
+    let forward = Quat::from_rotation_y(-degrees.to_radians()) * -Vec3::Z;
+    test.assert_translation(camera, forward * distance);
+}
+
+#[test]
+fn mouse_acceleration_speeds_up_fast_flicks() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        mouse_acceleration: 2.,
+        mouse_acceleration_threshold: 500.,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+    let sensitivity = MovementSettings::default().sensitivity;
+    test.advance(0.1);
+
+    // 100 pixels a second is under the threshold, so it stays raw.
+    test.move_mouse(Vec2::new(10., 0.));
+    test.advance(0.1);
+    let degrees = sensitivity * 10. * FlyCamTestApp::WINDOW_HEIGHT;
+    test.assert_rotation(camera, Quat::from_rotation_y(-degrees.to_radians()));
+
+    // 1000 pixels a second is twice the threshold, so it turns twice as far.
+    test.move_mouse(Vec2::new(100., 0.));
+    test.advance(0.1);
+    let degrees = degrees + sensitivity * 200. * FlyCamTestApp::WINDOW_HEIGHT;
+    test.assert_rotation(camera, Quat::from_rotation_y(-degrees.to_radians()));
+}
+
+#[test]
+fn sideways_scrolling_strafes() {
+    use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    let sideways = MouseWheel {
+        unit: MouseScrollUnit::Line,
+        x: -1.,
+        y: 0.,
+    };
+
+    // Ignored unless it has a sensitivity
+    test.send(sideways.clone());
+    test.advance(0.1);
+    test.assert_translation(camera, Vec3::ZERO);
+
+    test.app
+        .world
+        .resource_mut::<MovementSettings>()
+        .horizontal_scroll_sensitivity = 0.001;
+    test.send(sideways);
+    test.advance(0.1);
+    test.assert_translation(camera, Vec3::new(1.024, 0., 0.));
+}
+
+#[test]
+fn lost_cursor_grabs_come_back_on_click() {
+    use bevy::ecs::event::Events;
+    use bevy_flycam::{CursorGrabChanged, FlyCamCursorGrab};
+
+    let last_change = |test: &FlyCamTestApp| {
+        let events = test.app.world.resource::<Events<CursorGrabChanged>>();
+        events.get_reader().iter(events).last().copied()
+    };
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        recapture_on_click: true,
+        ..Default::default()
+    });
+    test.advance(0.1);
+    let grab = *test.app.world.resource::<FlyCamCursorGrab>();
+    assert!(grab.grabbed() && !grab.lost());
+    assert_eq!(
+        last_change(&test),
+        Some(CursorGrabChanged { grabbed: true })
+    );
+
+    test.press(KeyCode::Escape);
+    test.advance(0.1);
+    test.release(KeyCode::Escape);
+    let grab = *test.app.world.resource::<FlyCamCursorGrab>();
+    assert!(!grab.grabbed() && grab.lost());
+    assert_eq!(
+        last_change(&test),
+        Some(CursorGrabChanged { grabbed: false })
+    );
+
+    test.press_button(MouseButton::Left);
+    test.advance(0.1);
+    test.advance(0.1);
+    let grab = *test.app.world.resource::<FlyCamCursorGrab>();
+    assert!(grab.grabbed() && !grab.lost());
+}
+
+#[test]
+fn touch_drags_look_around() {
+    use bevy::input::touch::TouchPhase;
+
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+
+    test.touch(0, TouchPhase::Started, Vec2::new(100., 100.));
+    test.advance(0.1);
+    test.touch(0, TouchPhase::Moved, Vec2::new(200., 100.));
+    test.advance(0.1);
+    let turn = 100. * MovementSettings::default().touch_sensitivity;
+    test.assert_rotation(camera, Quat::from_rotation_y(-turn));
+
+    // Two fingers pinching in place cancel out.
+    test.touch(1, TouchPhase::Started, Vec2::new(300., 100.));
+    test.advance(0.1);
+    test.touch(0, TouchPhase::Moved, Vec2::new(150., 100.));
+    test.touch(1, TouchPhase::Moved, Vec2::new(350., 100.));
+    test.advance(0.1);
+    test.assert_rotation(camera, Quat::from_rotation_y(-turn));
+}
+
+#[test]
+fn zooming_in_slows_touch_look() {
+    use bevy::input::touch::TouchPhase;
+
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    test.press(KeyCode::C);
+    test.advance(1.);
+
+    test.touch(0, TouchPhase::Started, Vec2::new(100., 100.));
+    test.advance(0.1);
+    test.touch(0, TouchPhase::Moved, Vec2::new(200., 100.));
+    test.advance(0.1);
+    let settings = MovementSettings::default();
+    let turn = 100. * settings.touch_sensitivity * settings.zoom_fov / settings.fov;
+    test.assert_rotation(camera, Quat::from_rotation_y(-turn));
+}
+
+#[test]
+fn double_tapping_forward_sprints() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        double_tap_sprint: 0.3,
+        sprint_multiplier: 4.,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+    let speed = MovementSettings::default().speed;
+
+    // Taps too far apart just walk.
+    test.press(KeyCode::W);
+    test.advance(0.1);
+    test.release(KeyCode::W);
+    test.advance(0.5);
+    test.press(KeyCode::W);
+    test.advance(0.1);
+    test.assert_translation(camera, Vec3::new(0., 0., -speed * 0.2));
+
+    test.release(KeyCode::W);
+    test.advance(0.1);
+    test.press(KeyCode::W);
+    test.advance(0.1);
+    test.assert_translation(camera, Vec3::new(0., 0., -speed * 0.6));
+
+    // Letting go ends the sprint.
+    test.release(KeyCode::W);
+    test.advance(0.5);
+    test.press(KeyCode::W);
+    test.advance(0.1);
+    test.assert_translation(camera, Vec3::new(0., 0., -speed * 0.7));
+}
+
+#[test]
+fn boost_can_toggle_instead_of_hold() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        toggle_boost: true,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+    let speed = MovementSettings::default().speed;
+
+    test.press(KeyCode::LShift);
+    test.advance(0.1);
+    test.release(KeyCode::LShift);
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -speed * 4. * 0.5));
+
+    test.press(KeyCode::LShift);
+    test.advance(0.1);
+    test.release(KeyCode::LShift);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -speed * (2. + 0.1 + 0.5)));
+}
+
+#[test]
+fn boost_toggles_in_2d_too() {
+    use bevy_flycam::{FlyCam2d, FlyCam2dSettings, NoCameraFlyCam2dPlugin};
+
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        toggle_boost: true,
+        ..Default::default()
+    });
+    test.app.add_plugin(NoCameraFlyCam2dPlugin);
+    let camera = test
+        .app
+        .world
+        .spawn()
+        .insert_bundle(TransformBundle::default())
+        .insert(OrthographicProjection::default())
+        .insert(FlyCam2d)
+        .id();
+    let speed = FlyCam2dSettings::default().pan_speed;
+
+    test.press(KeyCode::LShift);
+    test.advance(0.1);
+    test.release(KeyCode::LShift);
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., speed * 4. * 0.5, 0.));
+}
+
+#[test]
+fn crouch_toggles_leave_flying_descent_held() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        toggle_crouch: true,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+    let speed = MovementSettings::default().speed;
+
+    // RShift descends while held, rather than latching like a crouch would.
+    test.press(KeyCode::RShift);
+    test.advance(0.5);
+    test.release(KeyCode::RShift);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., -speed * 0.5, 0.));
+}
+
+#[test]
+fn one_handed_profile_rebinds_the_keys() {
+    use bevy_flycam::{FlyCamProfiles, KeyBindings};
+
+    // Crouching toggles in the profile, so it can't share a key with descending.
+    let bindings = KeyBindings::one_handed();
+    assert!(bindings
+        .crouch
+        .iter()
+        .all(|key| !bindings.move_descend.contains(key)));
+
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    let speed = MovementSettings::default().speed;
+    assert!(test
+        .app
+        .world
+        .resource_mut::<FlyCamProfiles>()
+        .activate("one-handed"));
+
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::ZERO);
+    test.release(KeyCode::W);
+    test.press(KeyCode::I);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -speed * 0.5));
+
+    test.app.world.resource_mut::<FlyCamProfiles>().deactivate();
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -speed * 0.5));
+    test.release(KeyCode::I);
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -speed));
+}
+
+#[test]
+fn look_keys_turn_at_a_steady_rate() {
+    use bevy_flycam::KeyBindings;
+
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        keyboard_look_speed: 60.,
+        ..Default::default()
+    });
+    test.app
+        .insert_resource(KeyBindings::default().arrow_look());
+    let camera = test.spawn_camera(Transform::default());
+
+    test.press(KeyCode::Right);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::ZERO);
+    test.assert_rotation(camera, Quat::from_rotation_y(-30f32.to_radians()));
+
+    test.release(KeyCode::Right);
+    test.press(KeyCode::Up);
+    test.advance(0.25);
+    test.assert_rotation(
+        camera,
+        Quat::from_rotation_y(-30f32.to_radians()) * Quat::from_rotation_x(15f32.to_radians()),
+    );
+}
+
+#[test]
+fn zooming_in_slows_the_look_keys() {
+    use bevy_flycam::KeyBindings;
+
+    let settings = MovementSettings {
+        keyboard_look_speed: 60.,
+        ..Default::default()
+    };
+    let mut test = FlyCamTestApp::with_settings(settings.clone());
+    test.app
+        .insert_resource(KeyBindings::default().arrow_look());
+    let camera = test.spawn_camera(Transform::default());
+    test.press(KeyCode::C);
+    test.advance(1.);
+
+    test.press(KeyCode::Right);
+    test.advance(0.5);
+    let zoomed = 30f32.to_radians() * settings.zoom_fov / settings.fov;
+    test.assert_rotation(camera, Quat::from_rotation_y(-zoomed));
+}
+
+#[test]
+fn trackball_orbits_follow_the_cursor() {
+    use bevy_flycam::OrbitSettings;
+
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        mode_blend_time: 0.,
+        ..Default::default()
+    });
+    test.app.insert_resource(OrbitSettings {
+        trackball: true,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+    test.send(SetFlyCamMode {
+        camera: Some(camera),
+        mode: FlyCamMode::Orbit,
+    });
+    test.press(KeyCode::Escape);
+    test.advance(0.);
+    test.release(KeyCode::Escape);
+    test.advance(0.);
+
+    // Dragging from the middle halfway to the top of the window turns the ball by 30°.
+    let center = Vec2::new(
+        FlyCamTestApp::WINDOW_WIDTH / 2.,
+        FlyCamTestApp::WINDOW_HEIGHT / 2.,
+    );
+    let drag = FlyCamTestApp::WINDOW_HEIGHT / 4.;
+    test.press_button(MouseButton::Left);
+    test.set_cursor(Some(center + Vec2::new(drag, 0.)));
+    test.move_mouse(Vec2::new(drag, 0.));
+    test.advance(0.1);
+    let turn = 30f32.to_radians();
+    test.assert_rotation(camera, Quat::from_rotation_y(-turn));
+    test.assert_translation(
+        camera,
+        Vec3::new(-turn.sin() * 10., 0., turn.cos() * 10. - 10.),
+    );
+
+    // Dragging back undoes it, and dragging up tilts the view up.
+    test.set_cursor(Some(center));
+    test.move_mouse(Vec2::new(-drag, 0.));
+    test.advance(0.1);
+    test.assert_rotation(camera, Quat::IDENTITY);
+    test.set_cursor(Some(center + Vec2::new(0., drag)));
+    test.move_mouse(Vec2::new(0., -drag));
+    test.advance(0.1);
+    test.assert_rotation(camera, Quat::from_rotation_x(turn));
+}
+
+#[test]
+fn orbit_zooms_a_notch_per_hundred_pixels_of_scroll() {
+    use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        mode_blend_time: 0.,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+    test.send(SetFlyCamMode {
+        camera: Some(camera),
+        mode: FlyCamMode::Orbit,
+    });
+    test.advance_frames(2, 0.);
+
+    // A trackpad's 100 pixels zoom like one notch of a wheel, 10% closer to the focus point.
+    test.send(MouseWheel {
+        unit: MouseScrollUnit::Pixel,
+        x: 0.,
+        y: 100.,
+    });
+    test.advance(0.1);
+    test.assert_translation(camera, Vec3::new(0., 0., -1.));
+}
+
+#[test]
+fn turntable_turns_idle_cameras_until_touched() {
+    use bevy_flycam::FlyCamTurntable;
+
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    let mut turntable = FlyCamTurntable::new(1.);
+    turntable.speed = 0.5;
+    test.app.world.entity_mut(camera).insert(turntable);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::ZERO);
+
+    // Half a second past the idle time it's turned a quarter radian around a point 10 ahead.
+    test.advance(1.);
+    let turntable = |test: &FlyCamTestApp| {
+        test.app
+            .world
+            .get::<FlyCamTurntable>(camera)
+            .unwrap()
+            .turning()
+    };
+    assert!(turntable(&test));
+    let angle = 0.25f32;
+    let turned = Vec3::new(angle.sin() * 10., 0., angle.cos() * 10. - 10.);
+    test.assert_translation(camera, turned);
+    test.assert_rotation(camera, Quat::from_rotation_y(angle));
+
+    test.move_mouse(Vec2::new(-1., 0.));
+    test.advance(0.5);
+    assert!(!turntable(&test));
+    test.advance(0.5);
+    assert!(!turntable(&test));
+    assert!(test.transform(camera).translation.abs_diff_eq(turned, 1e-3));
+}
+
+#[test]
+fn screensaver_plays_while_idle_and_hands_back() {
+    use bevy_flycam::{FlyCamRecording, FlyCamScreensaver, RecordedFrame};
+
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    let recording = FlyCamRecording {
+        start: Some((Vec3::new(5., 0., 0.), FlyCamRotation::default())),
+        frames: vec![
+            RecordedFrame {
+                delta: 0.1,
+                actions: vec![FlyCamAction::MoveRight(1.)],
+                motion: None,
+            };
+            10
+        ],
+    };
+    test.app
+        .world
+        .entity_mut(camera)
+        .insert(FlyCamScreensaver::new(recording, 1.));
+    test.advance(0.5);
+    test.advance(0.6);
+    test.assert_translation(camera, Vec3::ZERO);
+
+    // It jumps to the start of the recording and plays on from there.
+    for _ in 0..3 {
+        test.advance(0.1);
+    }
+    test.assert_translation(camera, Vec3::new(8.6, 0., 0.));
+    let playing = |test: &FlyCamTestApp| {
+        test.app
+            .world
+            .get::<FlyCamScreensaver>(camera)
+            .unwrap()
+            .playing()
+    };
+    assert!(playing(&test));
+
+    // Input takes over straight away, from where the recording had got to.
+    test.press(KeyCode::W);
+    test.advance(0.1);
+    test.assert_translation(camera, Vec3::new(8.6, 0., -1.2));
+    assert!(!playing(&test));
+    test.release(KeyCode::W);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(8.6, 0., -1.2));
+}
+
+#[test]
+fn roll_offset_tilts_the_view_only() {
+    use bevy_flycam::KeyBindings;
+
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        keyboard_look_speed: 60.,
+        ..Default::default()
+    });
+    test.app.insert_resource(KeyBindings {
+        tilt_left: vec![KeyCode::T],
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+    test.app
+        .world
+        .get_mut::<FlyCamRotation>(camera)
+        .unwrap()
+        .roll_offset = 0.25;
+
+    // Mouse look still yaws around the world's up, and D still strafes level.
+    let sensitivity = MovementSettings::default().sensitivity;
+    test.move_mouse(Vec2::new(
+        90. / (sensitivity * FlyCamTestApp::WINDOW_HEIGHT),
+        0.,
+    ));
+    test.advance(0.);
+    test.press(KeyCode::D);
+    test.advance(0.5);
+    test.release(KeyCode::D);
+    let yaw = Quat::from_rotation_y(-FRAC_PI_2);
+    test.assert_rotation(camera, yaw * Quat::from_rotation_z(0.25));
+    test.assert_translation(camera, Vec3::new(0., 0., 6.));
+    assert_eq!(test.rotation(camera).roll, 0.);
+
+    test.press(KeyCode::T);
+    test.advance(0.5);
+    let tilt = 0.25 + 30f32.to_radians();
+    assert!((test.rotation(camera).roll_offset - tilt).abs() < 1e-5);
+    test.assert_rotation(camera, yaw * Quat::from_rotation_z(tilt));
+}
+
+#[test]
+fn dolly_zoom_keeps_the_subject_the_same_size() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::default());
+    test.app
+        .world
+        .entity_mut(camera)
+        .insert(Projection::default())
+        .insert(FlyCamDollyZoom::new(5., 10., 1.).with_curve(|t| t));
+    let fov = |test: &FlyCamTestApp| match test.app.world.get::<Projection>(camera) {
+        Some(Projection::Perspective(perspective)) => perspective.fov,
+        _ => unreachable!(),
+    };
+    let half_height = 10. * (PI / 8.).tan();
+
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -2.5));
+    assert!((fov(&test) - 2. * (half_height / 7.5).atan()).abs() < 1e-5);
+
+    test.advance(0.5);
+    test.advance(0.);
+    test.assert_translation(camera, Vec3::new(0., 0., -5.));
+    assert!((fov(&test) - 2. * (half_height / 5.).atan()).abs() < 1e-5);
+    assert!(test.app.world.get::<FlyCamDollyZoom>(camera).is_none());
+}
+
+#[test]
+fn autofocus_follows_whatever_is_ahead() {
+    let mut test = FlyCamTestApp::new();
+    let camera = test.spawn_camera(Transform::from_xyz(0., 0., 4.));
+    // A wall at z = -10
+    let mut autofocus = FlyCamAutofocus::new(|origin: Vec3, direction: Vec3| {
+        (direction.z < 0.).then(|| (origin.z + 10.) / -direction.z)
+    });
+    autofocus.smoothing = 0.;
+    test.app.world.entity_mut(camera).insert(autofocus);
+    let focus = |test: &FlyCamTestApp| test.app.world.get::<FocusDistance>(camera).unwrap().0;
+
+    test.advance(0.);
+    assert!((focus(&test) - 14.).abs() < 1e-4);
+
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    test.release(KeyCode::W);
+    assert!((focus(&test) - 8.).abs() < 1e-4);
+
+    // Turned away from the wall, it focuses as far as it goes.
+    test.press(KeyCode::Q);
+    test.advance_frames(50, 1. / 12.);
+    test.release(KeyCode::Q);
+    test.advance(0.);
+    assert_eq!(focus(&test), 1000.);
+}
+
+#[test]
+fn mouse_only_mode_orbits_dollies_and_pans() {
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        mode_blend_time: 0.,
+        ..Default::default()
+    });
+    let camera = test.spawn_camera(Transform::default());
+    test.send(SetFlyCamMode {
+        camera: Some(camera),
+        mode: FlyCamMode::MouseOnly,
+    });
+    test.press(KeyCode::Escape);
+    test.advance(0.);
+    test.release(KeyCode::Escape);
+    let center = Vec2::new(
+        FlyCamTestApp::WINDOW_WIDTH / 2.,
+        FlyCamTestApp::WINDOW_HEIGHT / 2.,
+    );
+    test.set_cursor(Some(center));
+    test.advance(0.);
+    test.assert_translation(camera, Vec3::ZERO);
+
+    // Dragging up with the right button halves the distance to the focus point 10 ahead.
+    test.press_button(MouseButton::Right);
+    test.move_mouse(Vec2::new(0., -100.));
+    test.advance(0.1);
+    test.release_button(MouseButton::Right);
+    test.assert_translation(camera, Vec3::new(0., 0., -5.));
+
+    // Dragging right with the left button swings round to look along +x.
+    let sensitivity = MovementSettings::default().sensitivity;
+    test.press_button(MouseButton::Left);
+    test.move_mouse(Vec2::new(
+        90. / (sensitivity * FlyCamTestApp::WINDOW_HEIGHT),
+        0.,
+    ));
+    test.advance(0.1);
+    test.release_button(MouseButton::Left);
+    test.assert_translation(camera, Vec3::new(-5., 0., -10.));
+    test.assert_rotation(camera, Quat::from_rotation_y(-FRAC_PI_2));
+
+    // The right edge pans to the camera's right at half the distance per second.
+    test.set_cursor(Some(Vec2::new(FlyCamTestApp::WINDOW_WIDTH - 5., center.y)));
+    test.advance(1.);
+    test.assert_translation(camera, Vec3::new(-5., 0., -7.5));
+}
+
+#[cfg(feature = "startup-overrides")]
+#[test]
+fn startup_overrides_place_the_first_flycam() {
+    use bevy_flycam::FlyCamStartup;
+
+    let mut test = FlyCamTestApp::new();
+    let pose = Transform::from_xyz(1., 2., 3.).with_rotation(Quat::from_rotation_y(1.));
+    test.app.insert_resource(FlyCamStartup {
+        pose: Some(pose),
+        speed: Some(5.),
+    });
+    let first = test.spawn_camera(Transform::default());
+    let second = test.spawn_camera(Transform::default());
+    test.advance(0.);
+    test.assert_translation(first, pose.translation);
+    test.assert_rotation(first, pose.rotation);
+    test.assert_translation(second, Vec3::ZERO);
+
+    test.press(KeyCode::W);
+    test.advance(1.);
+    test.assert_translation(second, Vec3::new(0., 0., -5.));
+}
+
+#[cfg(feature = "startup-overrides")]
+#[test]
+fn startup_overrides_inserted_before_the_plugin_are_kept() {
+    use bevy_flycam::{FlyCamStartup, NoCameraPlayerPlugin};
+
+    let startup = FlyCamStartup {
+        pose: None,
+        speed: Some(5.),
+    };
+    let mut app = App::new();
+    app.insert_resource(startup)
+        .add_plugin(NoCameraPlayerPlugin);
+    assert_eq!(*app.world.resource::<FlyCamStartup>(), startup);
+}
+
+#[cfg(feature = "virtual-joysticks")]
+#[test]
+fn virtual_joystick_moves_while_the_other_side_looks() {
+    use bevy::input::touch::TouchPhase;
+    use bevy_flycam::FlyCamJoysticksPlugin;
+
+    let mut test = FlyCamTestApp::new();
+    test.app.add_plugin(FlyCamJoysticksPlugin);
+    let camera = test.spawn_camera(Transform::default());
+
+    // The stick's middle is 100 pixels in from the bottom left, and touches are measured from
+    // the top.
+    let center = Vec2::new(100., FlyCamTestApp::WINDOW_HEIGHT - 100.);
+    test.touch(0, TouchPhase::Started, center);
+    test.advance(0.1);
+    test.touch(0, TouchPhase::Moved, center + Vec2::new(60., 0.));
+    test.advance(0.5);
+    let speed = MovementSettings::default().speed;
+    test.assert_translation(camera, Vec3::new(speed * 0.5, 0., 0.));
+    test.assert_rotation(camera, Quat::IDENTITY);
+
+    test.touch(0, TouchPhase::Ended, center);
+    test.touch(1, TouchPhase::Started, Vec2::new(1000., 300.));
+    test.advance(0.1);
+    test.touch(1, TouchPhase::Moved, Vec2::new(1100., 300.));
+    test.advance(0.1);
+    test.assert_translation(camera, Vec3::new(speed * 0.5, 0., 0.));
+    let turn = 100. * MovementSettings::default().touch_sensitivity;
+    test.assert_rotation(camera, Quat::from_rotation_y(-turn));
+}
+
+#[cfg(feature = "gyro")]
+#[test]
+fn gyro_aims_and_double_taps_recenter() {
+    use bevy::input::touch::TouchPhase;
+    use bevy_flycam::{DeviceOrientation, FlyCamGyroPlugin};
+
+    let mut test = FlyCamTestApp::new();
+    test.app.add_plugin(FlyCamGyroPlugin);
+    let camera = test.spawn_camera(Transform::default());
+
+    // Held upright, facing north
+    test.send(DeviceOrientation::from_browser(0., 90., 0.));
+    test.advance(0.1);
+    test.assert_rotation(camera, Quat::IDENTITY);
+
+    test.send(DeviceOrientation::from_browser(20., 100., 0.));
+    test.advance(0.1);
+    let rotation = test.rotation(camera);
+    assert!(
+        (rotation.yaw - 20f32.to_radians()).abs() < 1e-4,
+        "{rotation:?}"
+    );
+    assert!(
+        (rotation.pitch - 10f32.to_radians()).abs() < 1e-4,
+        "{rotation:?}"
+    );
+
+    test.touch(0, TouchPhase::Started, Vec2::new(600., 300.));
+    test.touch(0, TouchPhase::Ended, Vec2::new(600., 300.));
+    test.advance(0.1);
+    test.touch(0, TouchPhase::Started, Vec2::new(600., 300.));
+    test.advance(0.1);
+    let rotation = test.rotation(camera);
+    assert!(
+        (rotation.yaw - 20f32.to_radians()).abs() < 1e-4,
+        "{rotation:?}"
+    );
+    assert!(rotation.pitch.abs() < 1e-4, "{rotation:?}");
+}
+
+#[cfg(feature = "gestures")]
+#[test]
+fn trackpad_gestures_dolly_and_turn() {
+    use bevy_flycam::{FlyCamGesturesPlugin, TrackpadGesture};
+
+    let mut test = FlyCamTestApp::new();
+    test.app.add_plugin(FlyCamGesturesPlugin);
+    let camera = test.spawn_camera(Transform::default());
+
+    test.send(TrackpadGesture::Pinch(0.25));
+    test.advance(0.1);
+    let distance = 1024. * MovementSettings::default().sensitivity;
+    test.assert_translation(camera, Vec3::new(0., 0., -distance));
+
+    test.send(TrackpadGesture::Rotate(0.5));
+    test.advance(0.1);
+    let rotation = test.rotation(camera);
+    assert!((rotation.yaw + 0.5).abs() < 1e-5, "{rotation:?}");
+}
+
+#[cfg(feature = "picking")]
+#[test]
+fn picking_drags_hold_the_camera_still() {
+    use bevy_flycam::FlyCamPickingPlugin;
+    use bevy_mod_picking::PickableMesh;
+
+    let mut test = FlyCamTestApp::new();
+    test.app.add_plugin(FlyCamPickingPlugin);
+    let camera = test.spawn_camera(Transform::default());
+    let gizmo = test
+        .app
+        .world
+        .spawn()
+        .insert(PickableMesh::default())
+        .insert(Interaction::Clicked)
+        .id();
+
+    test.press(KeyCode::W);
+    test.move_mouse(Vec2::new(100., 0.));
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::ZERO);
+    test.assert_rotation(camera, Quat::IDENTITY);
+
+    // Once the drag is over the camera moves again.
+    *test.app.world.get_mut::<Interaction>(gizmo).unwrap() = Interaction::None;
+    test.advance(0.);
+    test.advance(0.5);
+    test.assert_translation(camera, Vec3::new(0., 0., -6.));
+}
+
+#[cfg(feature = "picking")]
+#[test]
+fn picking_drags_hold_orbit_cameras_still() {
+    use bevy_flycam::FlyCamPickingPlugin;
+    use bevy_mod_picking::PickableMesh;
+
+    let mut test = FlyCamTestApp::with_settings(MovementSettings {
+        mode_blend_time: 0.,
+        ..Default::default()
+    });
+    test.app.add_plugin(FlyCamPickingPlugin);
+    let camera = test.spawn_camera(Transform::default());
+    test.send(SetFlyCamMode {
+        camera: Some(camera),
+        mode: FlyCamMode::Orbit,
+    });
+    test.advance_frames(3, 0.);
+    let before = test.transform(camera);
+
+    // Dragging a gizmo with the left button would otherwise orbit around it.
+    test.app
+        .world
+        .spawn()
+        .insert(PickableMesh::default())
+        .insert(Interaction::Clicked);
+    test.press_button(MouseButton::Left);
+    test.move_mouse(Vec2::new(100., 0.));
+    test.press(KeyCode::W);
+    test.advance(0.5);
+    assert_eq!(test.transform(camera), before);
+}